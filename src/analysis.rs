@@ -0,0 +1,1619 @@
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
+    fmt, fs, io,
+    path::PathBuf,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use srp::common::{Task, TaskResult, Tasks, TasksResult, Trace};
+use symex::{
+    elf_util::VisualPathResult,
+    general_assembly::{project::PCHook, RunConfig},
+    run_elf::run_elf,
+};
+
+use crate::{
+    breakdown::{breakdown_factor, sensitivity},
+    ceiling_check::ceiling_violations,
+    chain::{Chain, ChainLatency},
+    combination_log::CombinationLog,
+    combinations::{combinations, reservoir_sample},
+    hooks::{
+        critical_section_hooks, cyccnt_hook, exclude_hooks, interrupt_enable_hooks, precondition_hooks,
+        spinlock_hooks, stack_hook, stack_lap, CsMechanism, MemoryHookAddress, MemoryReadHook, MemoryWriteHook,
+        NvicAddresses, PcHook, Precondition, RegisterReadHook, RegisterWriteHook, UserHooks, CYCCNT_LABEL,
+        EXCLUDED_LABEL, SYMBOLIC_LABEL,
+    },
+    interrupts::{check_interrupts, irq_from_bit_vector, resource_ids, resource_name, Chip, InterruptTable},
+    laps::{cache_key, load_cached, record_laps, replay_laps, store_cached, PathLaps},
+    lock_order::{lock_order_hazards, LockOrderHazard},
+    manifest::Manifest,
+    rta::{self, Interferer, Reservation, RtaConfig},
+    task::{
+        collapse_paths, create_task, global_max_lock, longest_sections_by_resource, preemption_level,
+        preemption_levels, prune_dominated, resource_users, section_laps, stack_required, validate_priorities,
+        Activation, EqualPriority, InputTask,
+    },
+    trace::{trace_from_laps, validate_trace, without_sections, TraceError, TraceIntegrityError, TraceIssue},
+    trace_file::load_trace,
+    vectors::{vector_symbols, Vector},
+};
+
+// Everything needed to run the analysis of an application.
+pub struct AnalysisConfig {
+    // path to the elf file to analyse.
+    pub elf: String,
+    pub tasks: Vec<InputTask>,
+    // NVIC PRIGROUP, see `preemption_level`.
+    pub prigroup: Option<u8>,
+    // stream every combination to this jsonl file.
+    pub jsonl_out: Option<PathBuf>,
+    // search the breakdown factor of the task set, see `breakdown_factor`.
+    pub breakdown: bool,
+    // search how much each WCET can grow on its own, see `sensitivity`.
+    pub sensitivity: bool,
+    // regexes of functions left out of the measured cycles, e.g. semihosting or logging calls that are
+    // not in a production build.
+    pub exclude: Vec<String>,
+    // cycles per context save or restore, see `RtaConfig`.
+    pub context_switch_cost: u32,
+    // cycles of interrupt entry, see `RtaConfig`.
+    pub interrupt_latency: u32,
+    // keep at most this many paths per task, the ones with the most cycles.
+    pub max_paths: Option<usize>,
+    // analyse only this many combinations, drawn at random with `seed`, when there are more. The result is
+    // then the worst of the sample, not a bound.
+    pub sample_combinations: Option<usize>,
+    pub seed: u64,
+    // resource ceilings (as a task priority) to use instead of the highest priority of the tasks locking
+    // the resource, for locks that raise to a fixed higher ceiling.
+    pub ceilings: Vec<(String, u32)>,
+    // resources left out of the blocking, their critical sections count as ordinary execution. Only sound for
+    // resources locked by a single task, a shared one gets a warning.
+    pub ignored_resources: Vec<String>,
+    // hooks added by the user on top of the built in ones, see the `with_*_hook` methods.
+    pub hooks: UserHooks,
+    // memory with a known value when any task starts, see `precondition_hooks`.
+    pub preconditions: Vec<Precondition>,
+    // collapse the paths of each task into one worst case as soon as they are found, see `collapse_paths`.
+    pub collapse_paths: bool,
+    // analyse every combination of paths, without leaving out the paths dominated by another path of the same
+    // task first, see `prune_dominated`.
+    pub exhaustive: bool,
+    // chains of tasks to bound the end to end latency of.
+    pub chains: Vec<Chain>,
+    // polling servers for aperiodic load, analysed along with the tasks.
+    pub servers: Vec<Server>,
+    // traces to use for tasks instead of running symex on them, by task name, see `trace_file`. The deadline,
+    // inter arrival time and priority still come from the task.
+    pub trace_files: Vec<(String, PathBuf)>,
+    // write the paths symex found for each task to this directory, see `laps`.
+    pub record_laps: Option<PathBuf>,
+    // read the paths of each task from a directory written with `record_laps` instead of running symex, the
+    // hooks and excludes used for the recording apply.
+    pub replay_laps: Option<PathBuf>,
+    // directory to cache the paths symex finds for each task in, see `cache_key`. A task with the same elf,
+    // entry symbol, critical section mechanism, excludes and preconditions reads them from there instead of
+    // running symex again. Nothing is cached with user hooks, they can't be part of the key. `None` disables
+    // the cache.
+    pub cache_dir: Option<PathBuf>,
+    // symbol of the code enabling the task interrupts (RTIC does it in `main` before `init` returns), used to
+    // warn about tasks whose interrupt is never enabled. The check is skipped without it.
+    pub init_symbol: Option<String>,
+    // extra cycles of the first job of each task after reset, see `RtaConfig::cold_start_penalty`.
+    pub cold_start_penalty: u32,
+    // groups of paths of different tasks that can only happen together (e.g. both following the same
+    // configuration flag), as `(task, path index)`. Combinations picking some of a group but not all of it are
+    // not analysed, see `correlated`. Path indices are the ones after sorting and `max_paths`.
+    pub correlations: Vec<Vec<(String, usize)>>,
+    // the share of the CPU the application gets, the whole CPU if `None`.
+    pub reservation: Option<Reservation>,
+    // whether tasks on different vectors can share a priority.
+    pub equal_priority: EqualPriority,
+    // fail on any critical section value that isn't constant, as the resource it locks is unknown and the
+    // blocking can't be attributed. Stricter than `strict`, which only turns the reduced confidence into an
+    // error after all paths are known.
+    pub require_concrete_locks: bool,
+    // turn problems in the analysis that would be warnings into errors.
+    pub strict: bool,
+    // check every trace with `validate_trace` before the response time analysis, failing on a broken one.
+    pub verify: bool,
+    // warn when every task is schedulable but the max utilization is below this, which more often means
+    // the WCETs weren't measured properly (e.g. symex stopped early) than that the load is that light. 0
+    // turns the check off.
+    pub utilization_floor: f64,
+    // tasks whose symbolic execution was stopped for running too long, left out of the schedule. Their WCET
+    // and so the interference they cause are unknown, the report never calls the tasks schedulable with one.
+    pub timed_out: Vec<String>,
+    // the chip whose interrupt table the task interrupts and NVIC masks are looked up in.
+    pub chip: Chip,
+}
+
+impl AnalysisConfig {
+    // Whether symex has to run on the elf of the application. It doesn't when every task comes from a trace
+    // file, has an elf of its own or the laps are replayed.
+    pub fn needs_elf(&self) -> bool {
+        self.replay_laps.is_none()
+            && !self.tasks.iter().all(|task| {
+                task.elf.is_some() || self.trace_files.iter().any(|(name, _)| *name == task.name)
+            })
+    }
+
+    // Runs `hook` instead of the instruction at every symbol matching `symbol`, see `PcHook`.
+    pub fn with_pc_hook(mut self, symbol: Regex, hook: PcHook) -> AnalysisConfig {
+        self.hooks.pc_hooks.push((symbol, hook));
+        self
+    }
+
+    pub fn with_register_read_hook(mut self, register: &str, hook: RegisterReadHook) -> AnalysisConfig {
+        self.hooks.register_read_hooks.push((register.to_owned(), hook));
+        self
+    }
+
+    pub fn with_register_write_hook(
+        mut self,
+        register: &str,
+        hook: RegisterWriteHook,
+    ) -> AnalysisConfig {
+        self.hooks.register_write_hooks.push((register.to_owned(), hook));
+        self
+    }
+
+    pub fn with_memory_read_hook(
+        mut self,
+        address: MemoryHookAddress,
+        hook: MemoryReadHook,
+    ) -> AnalysisConfig {
+        self.hooks.memory_read_hooks.push((address, hook));
+        self
+    }
+
+    pub fn with_memory_write_hook(
+        mut self,
+        address: MemoryHookAddress,
+        hook: MemoryWriteHook,
+    ) -> AnalysisConfig {
+        self.hooks.memory_write_hooks.push((address, hook));
+        self
+    }
+}
+
+// A polling server reserving `budget` cycles every `period` for aperiodic work. It is analysed as a periodic
+// task without critical sections, with its period as deadline.
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub name: String,
+    pub budget: u32,
+    pub period: u32,
+    // priority like a task priority, defaults to above every task.
+    pub priority: Option<u32>,
+}
+
+impl Server {
+    // Errs when `level` doesn't fit srp's `u8`, a server above a task on level 255 has no level left.
+    fn task(&self, level: u32) -> Result<Task, String> {
+        let prio = u8::try_from(level).map_err(|_| {
+            format!(
+                "server {} is on preemption level {level}, levels go up to {}, give it a lower priority",
+                self.name,
+                u8::MAX
+            )
+        })?;
+        Ok(Task {
+            id: self.name.to_owned(),
+            prio,
+            deadline: self.period,
+            inter_arrival: self.period,
+            trace: Trace {
+                id: self.name.to_owned(),
+                start: 0,
+                end: self.budget,
+                inner: vec![],
+            },
+        })
+    }
+}
+
+// A task whose WCET alone is larger than its deadline, it misses the deadline whatever the schedule is.
+#[derive(Serialize, Deserialize)]
+pub struct WcetOverDeadline {
+    pub task: String,
+    pub wcet: usize,
+    pub deadline: u32,
+}
+
+// What a warning is about, for tools that accept some warnings but not others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningKind {
+    // symex executed nothing on some paths of a task, usually a wrong entry symbol.
+    EmptyPaths,
+    // some results of a task are less trustworthy, the message lists why (see `TaskConfidence`).
+    ReducedConfidence,
+    // the interrupt of a task is never enabled, see `AnalysisConfig::init_symbol`.
+    LikelyUnused,
+    // a resource left out with `ignored_resources` is locked by more than one task.
+    SharedIgnoredResource,
+    // two tasks nest the same resources in opposite orders, see `lock_order_hazards`.
+    LockOrder,
+    // a task releases a resource on some paths but leaves it locked on others.
+    UnreleasedLock,
+    // the locks and unlocks on some paths of a task don't pair up, see `TraceIssue`.
+    UnbalancedSections,
+    // the max utilization is below `AnalysisConfig::utilization_floor`.
+    LowUtilization,
+    // the chip has another core than the one symex times instructions for, see `CYCLE_MODEL_CORE`.
+    CycleModel,
+    // a critical section leaves tasks below the ceiling of its resource unmasked, see `ceiling_violations`.
+    PriorityInversion,
+    // the WCET of a task is above its inter arrival time, it overloads its core on its own.
+    WcetOverInterArrival,
+}
+
+// A problem found during the analysis that doesn't stop it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    // the task the warning is about, `None` for warnings about several tasks.
+    pub task: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.task {
+            Some(task) => write!(f, "task {task}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// Why symex could not analyse a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalysisError {
+    // the elf could not be read, with its path.
+    ElfRead(String, String),
+    // the file is not an elf, with its path.
+    ElfParse(String),
+    // the elf has no symbol with the name of the function, usually a misspelled interrupt or entry symbol.
+    FunctionNotFound(String),
+    // symex failed while running the function, e.g. on an instruction it doesn't support or a solver error.
+    SymbolicExecution(String, String),
+    // the configuration doesn't hold together (an unknown interrupt, a priority out of range, a chain naming
+    // no task, an invalid exclude regex, ...), with what is wrong.
+    Config(String),
+    // the trace file of a task could not be loaded, with the task and why.
+    TraceFile(String, String),
+    // the laps of a task could not be replayed or recorded, with the task and why.
+    Laps(String, String),
+    // a critical section value isn't constant under `require_concrete_locks`, with where it is.
+    SymbolicLock(String),
+    // a warning under `strict`.
+    Strict(Warning),
+    // symex failed on a task, with the task and why.
+    Task(String, Box<AnalysisError>),
+    // a path of a task is too long for the u32 cycles of a trace, with the task and the cycle count.
+    CycleOverflow(String, usize),
+    // a trace failed `validate_trace` under `verify`, with the task and the path.
+    BrokenTrace(String, usize, TraceIntegrityError),
+    // the jsonl output could not be created or written, with its path and why.
+    JsonlOut(String, String),
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::ElfRead(elf, e) => write!(f, "could not read the elf file {elf}: {e}"),
+            AnalysisError::ElfParse(elf) => write!(f, "{elf} is not an elf file"),
+            AnalysisError::FunctionNotFound(function) => {
+                write!(f, "there is no symbol `{function}` in the elf")
+            }
+            AnalysisError::SymbolicExecution(function, e) => {
+                write!(f, "symbolic execution of `{function}` failed: {e}")
+            }
+            AnalysisError::Config(e) => write!(f, "{e}"),
+            AnalysisError::TraceFile(task, e) => write!(f, "task {task}: could not load its trace: {e}"),
+            AnalysisError::Laps(task, e) => write!(f, "task {task}: {e}"),
+            AnalysisError::SymbolicLock(e) => write!(f, "{e}"),
+            AnalysisError::Strict(warning) => write!(f, "{warning}"),
+            AnalysisError::Task(task, e) => write!(f, "task {task}: {e}"),
+            AnalysisError::CycleOverflow(task, cycle) => {
+                write!(f, "task {task}: cycle count {cycle} does not fit in a trace (u32)")
+            }
+            AnalysisError::BrokenTrace(task, path, e) => {
+                write!(f, "task {task} path {path}: broken trace, {e}")
+            }
+            AnalysisError::JsonlOut(path, e) => write!(f, "could not write the jsonl output {path}: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Confidence {
+    High,
+    // something during the analysis of the task had to be approximated, see the reasons.
+    Reduced,
+}
+
+// How much the WCET and critical sections found for a task can be trusted.
+#[derive(Serialize, Deserialize)]
+pub struct TaskConfidence {
+    pub task: String,
+    pub confidence: Confidence,
+    pub reasons: Vec<String>,
+}
+
+impl TaskConfidence {
+    fn new(task: &str) -> TaskConfidence {
+        TaskConfidence {
+            task: task.to_owned(),
+            confidence: Confidence::High,
+            reasons: vec![],
+        }
+    }
+
+    // Lowers the confidence, giving a short reason why.
+    pub fn reduce(&mut self, reason: String) {
+        self.confidence = Confidence::Reduced;
+        self.reasons.push(reason);
+    }
+}
+
+// The paths symex explored for a task, before `max_paths` drops any.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathStats {
+    pub task: String,
+    pub paths: usize,
+    // cycles of the shortest, longest and average path, 0 without paths.
+    pub min_cycles: usize,
+    pub max_cycles: usize,
+    pub mean_cycles: f64,
+    // cycles of every path, longest first.
+    pub cycles: Vec<usize>,
+    // the WCET may be missing paths: there are none, `max_paths` dropped some or a path ends inside a
+    // critical section (it was cut short instead of returning).
+    pub incomplete: bool,
+}
+
+// How the response time analysis of a task converged over all combinations.
+#[derive(Serialize, Deserialize)]
+pub struct TaskConvergence {
+    pub task: String,
+    // most fixed point iterations needed in any combination.
+    pub max_iterations: u32,
+    // false if some combination hit the iteration cap, the task is then unschedulable.
+    pub converged: bool,
+}
+
+// Serializable as a whole with the worst case traces, see `save_report`.
+#[derive(Serialize, Deserialize)]
+pub struct AnalysisReport {
+    // the inputs the report was made from.
+    pub manifest: Manifest,
+    // number of symex paths found for each task (1 if they were collapsed), in the configured task order.
+    pub path_counts: Vec<(String, usize)>,
+    // number of paths of each task left out of the combinations as another path dominates them, in the
+    // configured task order, see `AnalysisConfig::exhaustive`.
+    pub dominated_paths: Vec<(String, usize)>,
+    // tasks that can't meet their deadline even without interference or blocking.
+    pub wcet_over_deadline: Vec<WcetOverDeadline>,
+    // problems found during the analysis that don't stop it. Everything that makes a result less trustworthy
+    // ends up here: reduced confidence, lock order hazards, unused tasks.
+    pub warnings: Vec<Warning>,
+    // confidence in the result of each task, in the configured task order.
+    pub confidence: Vec<TaskConfidence>,
+    // number of combinations analysed, their path ids are only in the events and the jsonl output.
+    pub combination_count: u64,
+    pub max_utilization: f64,
+    // convergence of the response time analysis of each task, in the configured task order.
+    pub convergence: Vec<TaskConvergence>,
+    // most preemptions of each task in any combination, `None` if it is unbounded in one, in the configured
+    // task order. A high count makes a task sensitive to the context switch cost.
+    pub max_preemptions: Vec<(String, Option<u64>)>,
+    // the worst result of each task over all combinations.
+    #[serde(with = "crate::saved_report::tasks_result")]
+    pub worst: TasksResult,
+    // the symex path of each task in the combination its worst result is from, in the same order as `worst`.
+    // srp's `Task` has no room for it, so it is kept here.
+    pub worst_paths: Vec<(String, usize)>,
+    // the interference of each task in the combination its worst result is from, by interfering task, in the
+    // same order as `worst`.
+    pub interference: Vec<(String, Vec<Interferer>)>,
+    // pairs of tasks nesting the same resources in opposite orders, over all paths.
+    pub lock_order_hazards: Vec<LockOrderHazard>,
+    // the tasks locking each resource over all paths, by resource id.
+    pub resources: BTreeMap<String, BTreeSet<String>>,
+    // ceiling (as a preemption level) of each resource the analysis used, the configured ceiling if there is
+    // one, by resource id.
+    pub ceilings: BTreeMap<String, u8>,
+    // end to end latency of each chain, see `chain_latency`, in the configured order.
+    pub chain_latencies: Vec<ChainLatency>,
+    // the longest critical section over all paths, as `(cycles, task, resource)`.
+    pub max_lock: Option<(u32, String, String)>,
+    // the longest critical section on each resource over all paths, in cycles by resource id, to cross check
+    // the blocking of the tasks against.
+    pub longest_sections: BTreeMap<String, u32>,
+    // factor all WCETs can be scaled with before a deadline is missed, if it was asked for.
+    pub breakdown: Option<f64>,
+    // cycles each task can grow by before a deadline is missed (`None` if already missed), in the configured
+    // task order, if it was asked for.
+    pub sensitivity: Option<Vec<(String, Option<u32>)>>,
+    // most calls to excluded functions on any path of each task, in the configured task order.
+    pub excluded_calls: Vec<(String, usize)>,
+    // the paths explored for each task analysed with symex, in the configured task order.
+    pub path_stats: Vec<PathStats>,
+    // cycles at each read of the DWT cycle counter on the longest path of a task, in the order it reads it,
+    // for the tasks reading it, in the configured task order.
+    pub cyccnt_reads: Vec<(String, Vec<usize>)>,
+    // deepest stack of each task analysed with symex over all its paths, in bytes without the exception
+    // frame, in the configured task order. See `stack_hook`.
+    pub max_stack: Vec<(String, u64)>,
+    // bytes of stack each core needs with every level preempted at its deepest, see `stack_required`.
+    pub stack_required: BTreeMap<u8, u64>,
+    // `(analysed, total)` if only a random sample of the combinations was analysed, see
+    // `AnalysisConfig::sample_combinations`. The worst case is then only the worst observed.
+    pub sampled: Option<(usize, u64)>,
+    // the WCET of each task, its longest path in cycles, in the configured task order. Tasks without paths
+    // are left out.
+    pub wcets: Vec<(String, u32)>,
+    // cycles each background task gets in every inter arrival time in the worst combination, see
+    // `rta::background_budget`, in the configured task order.
+    pub background_budgets: Vec<(String, u64)>,
+    // the utilization of each task and server on its longest path, see `rta::task_utilization`, in the
+    // configured order with the servers last. Tasks without paths are left out.
+    pub utilizations: Vec<(String, f64)>,
+    // tasks left out of the schedule as their `enabled_after` symbol never enables their interrupt, with that
+    // symbol.
+    pub not_enabled: Vec<(String, String)>,
+    // tasks left out of the schedule as their symbolic execution timed out, see `AnalysisConfig::timed_out`.
+    pub timed_out: Vec<String>,
+    // the chip of the application, to name the interrupts in NVIC masks with `resource_name`.
+    pub chip: Chip,
+}
+
+// The core whose instruction timings symex counts the cycles of a path with. `RunConfig` has no way to pick
+// another model, symex has one timing per instruction of ARMv6-M.
+pub const CYCLE_MODEL_CORE: &str = "Cortex-M0+";
+
+// Progress of `run_analysis_streaming`, in the order the events happen.
+#[derive(Clone, Copy)]
+pub enum AnalysisEvent<'a> {
+    // the paths of a task are known, counted after `max_paths` and `collapse_paths`. Once per task, in the
+    // configured order, servers and tasks left out of the schedule aren't included.
+    TaskAnalyzed { task: &'a str, paths: usize },
+    // the response time analysis of a combination is done, `schedulable` if every task in it meets its
+    // deadline. `index` counts the analysed combinations, `paths` has the path index used for each task.
+    CombinationEvaluated {
+        index: usize,
+        paths: &'a [(String, usize)],
+        schedulable: bool,
+    },
+    // the last event, with the report `run_analysis_streaming` returns.
+    Done(&'a AnalysisReport),
+}
+
+// Runs symbolic execution on every task, forms all combinations of their paths and does the SRP response
+// time analysis on each, returning the worst case of each task. Errs on a configuration that doesn't hold
+// together or an input that can't be read, before any symex runs where it can be checked up front.
+pub fn run_analysis(config: &AnalysisConfig) -> Result<AnalysisReport, AnalysisError> {
+    run_analysis_streaming(config, |_| {})
+}
+
+// `run_analysis`, calling `on_event` as the analysis goes, e.g. to show the progress in a larger tool.
+pub fn run_analysis_streaming(
+    config: &AnalysisConfig,
+    mut on_event: impl FnMut(AnalysisEvent<'_>),
+) -> Result<AnalysisReport, AnalysisError> {
+    let unknown = check_interrupts(&config.tasks, config.chip.table());
+    if !unknown.is_empty() {
+        let errors: Vec<String> = unknown.iter().map(|(task, e)| format!("task {task}: {e}")).collect();
+        return Err(AnalysisError::Config(errors.join("\n")));
+    }
+    if let Some(reservation) = config.reservation {
+        if reservation.budget == 0 || reservation.budget > reservation.period {
+            return Err(AnalysisError::Config(
+                "the reservation budget has to be between 1 and its period".to_owned(),
+            ));
+        }
+    }
+    for chain in &config.chains {
+        for task in &chain.tasks {
+            if !config.tasks.iter().any(|t| t.name == *task) {
+                return Err(AnalysisError::Config(format!("chain {}: no task named {task}", chain.name)));
+            }
+        }
+    }
+    validate_priorities(&config.tasks, config.equal_priority).map_err(AnalysisError::Config)?;
+    for server in &config.servers {
+        if let Some(priority) = server.priority.filter(|p| u8::try_from(*p).is_err()) {
+            return Err(AnalysisError::Config(format!(
+                "server {} has priority {priority}, priorities go up to {}",
+                server.name,
+                u8::MAX
+            )));
+        }
+    }
+    let manifest =
+        Manifest::new(config).map_err(|e| AnalysisError::ElfRead(config.elf.to_owned(), e.to_string()))?;
+
+    let mut tasks = vec![];
+    let mut path_counts = vec![];
+    let mut wcet_over_deadline = vec![];
+    let mut confidence = vec![];
+    let mut warnings = vec![];
+    if config.chip.core() != CYCLE_MODEL_CORE {
+        let message = format!(
+            "symex counts cycles with the {CYCLE_MODEL_CORE} instruction timings, the {} of this chip has \
+             other timings, so the WCETs are not the ones of this chip",
+            config.chip.core()
+        );
+        warn(config, &mut warnings, WarningKind::CycleModel, None, message)?;
+    }
+    // every NVIC critical section ends by writing its mask to ISER, so their labels are enabled masks too.
+    let mut enabled_masks = vec![];
+
+    let mut excluded_calls = vec![];
+    let mut cyccnt_reads = vec![];
+    let mut max_stack = vec![];
+    let mut path_stats = vec![];
+
+    // symex runs once for each symbol, tasks enabled by the same init code share it.
+    let mut enables: HashMap<(&str, &str), Vec<String>> = HashMap::new();
+    let mut not_enabled = vec![];
+    for task in &config.tasks {
+        if let Some(symbol) = &task.enabled_after {
+            let masks = match enables.entry((task.elf(&config.elf), symbol)) {
+                Entry::Occupied(masks) => masks.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    interrupt_enables(task.elf(&config.elf), symbol, config.chip.table().nvic())
+                        .map_err(|e| AnalysisError::Task(task.name.to_owned(), Box::new(e)))?,
+                ),
+            };
+            if !enabled_interrupts(masks, config.chip.table()).contains(&task.interrupt) {
+                not_enabled.push((task.name.to_owned(), symbol.to_owned()));
+            }
+        }
+    }
+    let scheduled = |task: &InputTask| {
+        !not_enabled.iter().any(|(name, _)| *name == task.name) && !config.timed_out.contains(&task.name)
+    };
+
+    let levels = preemption_levels(&config.tasks, config.prigroup);
+    if let Some(background) = config.tasks.iter().find(|t| t.background) {
+        let shared = config.tasks.iter().zip(&levels).find(|(t, level)| !t.background && **level == 0);
+        if let Some((task, _)) = shared {
+            return Err(AnalysisError::Config(format!(
+                "task {} is on preemption level 0 with background task {}, give it a higher priority",
+                task.name, background.name
+            )));
+        }
+    }
+    let exclude = exclude_regexes(config)?;
+
+    // servers come after the tasks, above every task unless given a priority. Made here so a server without
+    // a level errs before symex runs.
+    let top_level = levels.iter().max().map_or(0, |l| l + 1);
+    let servers = config
+        .servers
+        .iter()
+        .map(|server| {
+            let level = server
+                .priority
+                .map_or(top_level, |p| preemption_level(p, config.prigroup));
+            server.task(level)
+        })
+        .collect::<Result<Vec<Task>, String>>()
+        .map_err(AnalysisError::Config)?;
+    for (task, level) in config.tasks.iter().zip(levels) {
+        if !scheduled(task) {
+            continue;
+        }
+        if let Some((_, file)) = config.trace_files.iter().find(|(name, _)| *name == task.name) {
+            let loaded =
+                load_trace(file).map_err(|e| AnalysisError::TraceFile(task.name.to_owned(), e.to_string()))?;
+            let wcet = loaded.trace.end.saturating_sub(loaded.trace.start);
+            if wcet > task.analysed_deadline() {
+                wcet_over_deadline.push(WcetOverDeadline {
+                    task: task.name.to_owned(),
+                    wcet: wcet as usize,
+                    deadline: task.analysed_deadline(),
+                });
+            }
+            let prio = u8::try_from(level).map_err(|_| {
+                AnalysisError::Config(format!(
+                    "task {} is on preemption level {level}, levels go up to {}",
+                    task.name,
+                    u8::MAX
+                ))
+            })?;
+            let t = Task {
+                id: task.name.to_owned(),
+                prio,
+                deadline: task.analysed_deadline(),
+                inter_arrival: task.activation.inter_arrival(),
+                trace: loaded.trace,
+            };
+            excluded_calls.push((task.name.to_owned(), 0));
+            confidence.push(TaskConfidence::new(&task.name));
+            path_counts.push((task.name.to_owned(), 1));
+            on_event(AnalysisEvent::TaskAnalyzed { task: &task.name, paths: 1 });
+            tasks.push(vec![(0, t)]);
+            continue;
+        }
+        let mut result = paths_of(config, task, &exclude)?;
+        if let Some(dir) = &config.record_laps {
+            record_laps(dir, &task.name, &result).map_err(|e| {
+                AnalysisError::Laps(task.name.to_owned(), format!("could not record the laps: {e}"))
+            })?;
+        }
+        excluded_calls.push((task.name.to_owned(), count_excluded_calls(&result)));
+        // paths are sorted longest first.
+        let reads = result.first().map_or(vec![], cyccnt_read_cycles);
+        if !reads.is_empty() {
+            cyccnt_reads.push((task.name.to_owned(), reads));
+        }
+        max_stack.push((task.name.to_owned(), result.iter().map(stack_depth).max().unwrap_or(0)));
+        wcet_over_deadline.extend(check_wcet_against_deadline(task, &result));
+        if config.require_concrete_locks {
+            if let Some(message) = symbolic_lock(task, &result) {
+                return Err(AnalysisError::SymbolicLock(message));
+            }
+        }
+        let mut task_confidence = task_confidence(task, &result);
+        // a path that ends before executing anything gives a WCET of 0 that looks like a pass, it is usually
+        // the wrong symbol.
+        let empty_paths = result
+            .iter()
+            .filter(|r| r.max_cycles == 0 && r.cycle_laps.is_empty())
+            .count();
+        if empty_paths > 0 {
+            let message = format!(
+                "symex executed no instructions on {empty_paths} of {} paths from `{}`, check the entry symbol",
+                result.len(),
+                task.entry_symbol()
+            );
+            warn(config, &mut warnings, WarningKind::EmptyPaths, Some(&task.name), message)?;
+        }
+        if task.cs_mechanism == CsMechanism::Nvic {
+            let laps = result.iter().flat_map(|r| r.cycle_laps.iter());
+            enabled_masks.extend(laps.map(|(_, label)| label.to_owned()));
+        }
+        let explored = result.len();
+        let cycles = result.iter().map(|r| r.max_cycles);
+        let mut stats = PathStats {
+            task: task.name.to_owned(),
+            paths: explored,
+            min_cycles: cycles.clone().min().unwrap_or(0),
+            max_cycles: cycles.clone().max().unwrap_or(0),
+            mean_cycles: cycles.clone().sum::<usize>() as f64 / explored.max(1) as f64,
+            cycles: cycles.collect(),
+            incomplete: explored == 0,
+        };
+        // paths are sorted longest first, so this keeps the WCET. A dropped path can still hold a longer
+        // critical section, hence the reduced confidence.
+        if let Some(max_paths) = config.max_paths {
+            if result.len() > max_paths {
+                task_confidence.reduce(format!("truncated {} paths", result.len() - max_paths));
+                stats.incomplete = true;
+                result.truncate(max_paths);
+            }
+        }
+        let unbalanced: Vec<(usize, TraceError)> = result
+            .iter()
+            .enumerate()
+            .filter_map(|(path, r)| {
+                let trace = trace_from_laps(0, r.max_cycles, &section_laps(r), task.name.to_owned());
+                trace.err().map(|e| (path, e))
+            })
+            .collect();
+        // the sections are still closed (at the end of the path, or of the section inside them), so the
+        // trace errs on the long side, but it is most likely an incomplete path or a hook in the wrong place.
+        if let Some((path, e)) = unbalanced.first() {
+            task_confidence.reduce(format!("unbalanced critical sections on {} paths", unbalanced.len()));
+            stats.incomplete = true;
+            let issues: Vec<String> = e.issues.iter().map(|issue| issue.to_string()).collect();
+            let message = format!(
+                "unbalanced critical sections on {} of {} paths, on path {path} {}: the path may be \
+                 incomplete or a hook misses a lock or unlock",
+                unbalanced.len(),
+                result.len(),
+                issues.join(", ")
+            );
+            warn(config, &mut warnings, WarningKind::UnbalancedSections, Some(&task.name), message)?;
+        }
+        path_stats.push(stats);
+        for (resource, held, released) in unreleased_locks(task, &result) {
+            let held: Vec<String> = held.iter().map(|p| p.to_string()).collect();
+            let message = format!(
+                "{} is released on {released} paths but left locked on path {}, likely an error path \
+                 missing an unlock",
+                resource_name(&resource, config.chip.table()),
+                held.join(", ")
+            );
+            warn(config, &mut warnings, WarningKind::UnreleasedLock, Some(&task.name), message)?;
+        }
+        if task_confidence.confidence == Confidence::Reduced {
+            let message = format!("reduced confidence ({})", task_confidence.reasons.join("; "));
+            warn(config, &mut warnings, WarningKind::ReducedConfidence, Some(&task.name), message)?;
+        }
+        confidence.push(task_confidence);
+        let mut tasks_of_task = vec![];
+        for (path, r) in result.iter().enumerate() {
+            let t = create_task(r, task, level)?;
+            tasks_of_task.push((path, t));
+        }
+        // the symex results and all but one trace are dropped here, so memory stays proportional to the
+        // number of tasks. The collapsed task is reported as path 0.
+        if config.collapse_paths {
+            let paths: Vec<Task> = tasks_of_task.into_iter().map(|(_, t)| t).collect();
+            tasks_of_task = collapse_paths(&paths).map(|t| (0, t)).into_iter().collect();
+        }
+        path_counts.push((task.name.to_owned(), tasks_of_task.len()));
+        on_event(AnalysisEvent::TaskAnalyzed {
+            task: &task.name,
+            paths: tasks_of_task.len(),
+        });
+        tasks.push(tasks_of_task);
+    }
+
+    if let Some(init_symbol) = &config.init_symbol {
+        enabled_masks.extend(interrupt_enables(&config.elf, init_symbol, config.chip.table().nvic())?);
+        let enabled = enabled_interrupts(&enabled_masks, config.chip.table());
+        for task in config.tasks.iter().filter(|task| scheduled(task)) {
+            if !enabled.contains(&task.interrupt) {
+                let message = format!(
+                    "likely-unused, {} is never enabled by `{init_symbol}` or any task",
+                    task.interrupt
+                );
+                warn(config, &mut warnings, WarningKind::LikelyUnused, Some(&task.name), message)?;
+            }
+        }
+    }
+
+    let wcets: Vec<(String, u32)> = tasks
+        .iter()
+        .filter_map(|paths| {
+            let longest = paths
+                .iter()
+                .map(|(_, t)| t)
+                .max_by_key(|t| t.trace.end.saturating_sub(t.trace.start))?;
+            Some((longest.id.to_owned(), longest.trace.end.saturating_sub(longest.trace.start)))
+        })
+        .collect();
+    // checked before the combinations: with more work per period than the period the response time has no
+    // fixed point, the iteration only stops at its cap. Background tasks have no period to keep up with.
+    for (name, wcet) in &wcets {
+        let Some(task) = config.tasks.iter().find(|task| task.name == *name) else {
+            continue;
+        };
+        let inter_arrival = task.activation.inter_arrival();
+        if !task.background && *wcet > inter_arrival {
+            let message = format!(
+                "WCET {wcet} cycles is above the inter arrival time {inter_arrival} cycles, the task alone \
+                 overloads its core and can't be schedulable"
+            );
+            warn(config, &mut warnings, WarningKind::WcetOverInterArrival, Some(name), message)?;
+        }
+    }
+
+    for server in servers {
+        path_counts.push((server.id.to_owned(), 1));
+        tasks.push(vec![(0, server)]);
+    }
+    let utilizations = tasks
+        .iter()
+        .filter_map(|paths| {
+            let longest = paths
+                .iter()
+                .map(|(_, t)| t)
+                .max_by_key(|t| t.trace.end.saturating_sub(t.trace.start))?;
+            Some((longest.id.to_owned(), rta::task_utilization(longest)))
+        })
+        .collect();
+
+    if !config.ignored_resources.is_empty() {
+        let ignored: Vec<String> =
+            config.ignored_resources.iter().flat_map(|name| resource_ids(name)).collect();
+        let all_paths: Vec<Task> = tasks.iter().flatten().map(|(_, t)| t.clone()).collect();
+        for (resource, users) in resource_users(&all_paths) {
+            if ignored.contains(&resource) && users.len() > 1 {
+                let users: Vec<&str> = users.iter().map(|u| u.as_str()).collect();
+                let message = format!(
+                    "ignored resource {} is locked by {}, the blocking between them is left out",
+                    resource_name(&resource, config.chip.table()),
+                    users.join(", ")
+                );
+                warn(config, &mut warnings, WarningKind::SharedIgnoredResource, None, message)?;
+            }
+        }
+        for (_, task) in tasks.iter_mut().flatten() {
+            task.trace = without_sections(&task.trace, &ignored);
+        }
+    }
+
+    if config.verify {
+        for (path, task) in tasks.iter().flatten() {
+            validate_trace(&task.trace)
+                .map_err(|e| AnalysisError::BrokenTrace(task.id.to_owned(), *path, e))?;
+        }
+    }
+
+    let all_paths: Vec<Task> = tasks.iter().flatten().map(|(_, t)| t.clone()).collect();
+    let lock_order_hazards = lock_order_hazards(&all_paths);
+    for hazard in &lock_order_hazards {
+        let table = config.chip.table();
+        let (first, second) = (
+            resource_name(&hazard.resources.0, table),
+            resource_name(&hazard.resources.1, table),
+        );
+        let message = format!(
+            "{} locks {second} inside {first} but {} locks {first} inside {second}",
+            hazard.task_a, hazard.task_b
+        );
+        warn(config, &mut warnings, WarningKind::LockOrder, None, message)?;
+    }
+    let max_lock = global_max_lock(&all_paths);
+    let longest_sections = longest_sections_by_resource(&all_paths);
+    let resources = resource_users(&all_paths);
+
+    let ceilings = ceiling_overrides(config, &all_paths).map_err(AnalysisError::Config)?;
+    let used_ceilings: BTreeMap<String, u8> = rta::resource_ceilings(&Tasks(all_paths.clone()))
+        .into_iter()
+        .map(|(id, ceiling)| (id.to_owned(), ceilings.get(id).copied().unwrap_or(ceiling)))
+        .collect();
+    for violation in ceiling_violations(&all_paths, &config.tasks, &used_ceilings, config.chip.table()) {
+        let message = format!(
+            "locks {} leaving {} unmasked below its ceiling {}, they can preempt the section (priority \
+             inversion)",
+            resource_name(&violation.resource, config.chip.table()),
+            violation.unmasked.join(", "),
+            violation.ceiling
+        );
+        warn(config, &mut warnings, WarningKind::PriorityInversion, Some(&violation.task), message)?;
+    }
+
+    // tasks in a correlation keep all their paths, the group could otherwise rule out the dominating path.
+    let mut dominated_paths = vec![];
+    if !config.exhaustive {
+        for paths in tasks.iter_mut() {
+            let Some((_, first)) = paths.first() else { continue };
+            let id = first.id.to_owned();
+            if config.correlations.iter().flatten().any(|(task, _)| *task == id) {
+                continue;
+            }
+            let before = paths.len();
+            *paths = prune_dominated(std::mem::take(paths));
+            dominated_paths.push((id, before - paths.len()));
+        }
+    }
+
+    // combinations are built one at a time and only the worst one of each task is kept, so memory doesn't
+    // grow with their product. A sample is drawn from them the same way, it is only as large as asked for.
+    let candidates = combinations(&tasks).filter(|combination| correlated(combination, &config.correlations));
+    let (sample, sampled) = match config.sample_combinations {
+        Some(n) => {
+            let (sample, total) = reservoir_sample(candidates.clone(), n, config.seed);
+            let sampled = (total > n as u64).then_some((sample.len(), total));
+            (Some(sample), sampled)
+        }
+        None => (None, None),
+    };
+    let list_to_test: Box<dyn Iterator<Item = Vec<(usize, Task)>> + '_> = match &sample {
+        Some(sample) => Box::new(sample.iter().cloned()),
+        None => Box::new(candidates.clone()),
+    };
+    let rta_config = RtaConfig {
+        context_switch_cost: config.context_switch_cost,
+        interrupt_latency: config.interrupt_latency,
+        ceilings,
+        equal_priority: config.equal_priority,
+        cold_start_penalty: config.cold_start_penalty,
+        reservation: config.reservation,
+        bus_contention: config
+            .tasks
+            .iter()
+            .map(|task| (task.name.to_owned(), task.bus_contention))
+            .collect(),
+        jitter: config
+            .tasks
+            .iter()
+            .map(|task| (task.name.to_owned(), task.jitter))
+            .collect(),
+        cores: config
+            .tasks
+            .iter()
+            .map(|task| (task.name.to_owned(), task.core()))
+            .collect(),
+    };
+
+    let jsonl_error = |e: io::Error| {
+        let path = config.jsonl_out.as_ref().map_or(String::new(), |path| path.display().to_string());
+        AnalysisError::JsonlOut(path, e.to_string())
+    };
+    let mut combination_log = config
+        .jsonl_out
+        .as_ref()
+        .map(|path| CombinationLog::create(path))
+        .transpose()
+        .map_err(jsonl_error)?;
+
+    let mut combination_count = 0;
+    // the worst result of each task (by position) so far and the combination it came from, see
+    // `worst_combinations`.
+    let mut worst_so_far: Vec<(TaskResult, Vec<(usize, Task)>)> = vec![];
+    let mut convergence: Vec<TaskConvergence> = config
+        .tasks
+        .iter()
+        .filter(|task| scheduled(task))
+        .map(|task| TaskConvergence {
+            task: task.name.to_owned(),
+            max_iterations: 0,
+            converged: true,
+        })
+        .collect();
+
+    let mut max_preemptions: Vec<(String, Option<u64>)> = config
+        .tasks
+        .iter()
+        .filter(|task| scheduled(task))
+        .map(|task| (task.name.to_owned(), Some(0)))
+        .collect();
+
+    let mut background_budgets: Vec<(String, u64)> = config
+        .tasks
+        .iter()
+        .filter(|task| task.background && scheduled(task))
+        .map(|task| (task.name.to_owned(), u64::MAX))
+        .collect();
+
+    let mut max_utilization: f64 = 0.0;
+    for (i, combination) in list_to_test.enumerate() {
+        let path_ids: Vec<(String, usize)> = combination
+            .iter()
+            .map(|(path, t)| (t.id.to_owned(), *path))
+            .collect();
+        let tasks = Tasks(combination.iter().map(|(_, t)| t.clone()).collect());
+        let rta = rta::response_time(&tasks, &rta_config);
+        for (r, c) in rta.results.0.iter().zip(&rta.convergence) {
+            if let Some(task) = convergence.iter_mut().find(|t| t.task == r.task.id) {
+                task.max_iterations = task.max_iterations.max(c.iterations);
+                task.converged &= c.converged;
+            }
+        }
+        for (r, p) in rta.results.0.iter().zip(&rta.preemptions) {
+            if let Some((_, max)) = max_preemptions.iter_mut().find(|(task, _)| *task == r.task.id) {
+                *max = max.zip(*p).map(|(max, p)| max.max(p));
+            }
+        }
+        for (task, budget) in &mut background_budgets {
+            if let Some(t) = tasks.0.iter().find(|t| t.id == *task) {
+                *budget = (*budget).min(rta::background_budget(t, &tasks, &rta_config));
+            }
+        }
+        let result = rta.results;
+        let utilization = rta::utilization(&tasks);
+        max_utilization = max_utilization.max(utilization);
+        if let Some(log) = &mut combination_log {
+            log.record(i, &path_ids, utilization, &result).map_err(jsonl_error)?;
+        }
+        let schedulable = result
+            .0
+            .iter()
+            .all(|r| matches!(r.response_time, Some(rt) if rt <= r.task.deadline));
+        on_event(AnalysisEvent::CombinationEvaluated {
+            index: i,
+            paths: &path_ids,
+            schedulable,
+        });
+        for (position, r) in result.0.iter().enumerate() {
+            match worst_so_far.get_mut(position) {
+                Some((worst, _)) if !worse_result(worst, r) => {}
+                Some(worst) => *worst = (clone_result(r), combination.clone()),
+                None => worst_so_far.push((clone_result(r), combination.clone())),
+            }
+        }
+        combination_count += 1;
+    }
+
+    // the combinations are gone through again for every factor tried, the same way.
+    let (breakdown, sensitivity) = match &sample {
+        Some(sample) => headroom(config, &rta_config, sample.iter().cloned()),
+        None => headroom(config, &rta_config, candidates),
+    };
+
+    let worst_paths = worst_so_far
+        .iter()
+        .enumerate()
+        .map(|(i, (_, combination))| (combination[i].1.id.to_owned(), combination[i].0))
+        .collect();
+    // only the results are kept for each combination, the worst ones are analysed again for the breakdown.
+    let interference = worst_so_far
+        .iter()
+        .enumerate()
+        .map(|(i, (_, combination))| {
+            let tasks = Tasks(combination.iter().map(|(_, t)| t.clone()).collect());
+            let mut rta = rta::response_time(&tasks, &rta_config);
+            (rta.results.0[i].task.id.to_owned(), rta.interferers.swap_remove(i))
+        })
+        .collect();
+
+    // tasks without paths have no combination to take a budget from.
+    background_budgets.retain(|(_, budget)| *budget != u64::MAX);
+
+    let worst = TasksResult(worst_so_far.into_iter().map(|(result, _)| result).collect());
+    let schedulable = worst
+        .0
+        .iter()
+        .all(|r| matches!(r.response_time, Some(rt) if rt <= r.task.deadline));
+    if schedulable && !worst.0.is_empty() && max_utilization < config.utilization_floor {
+        let message = format!(
+            "max utilization {max_utilization:.6} is below {}, the WCETs may not have been measured properly",
+            config.utilization_floor
+        );
+        warn(config, &mut warnings, WarningKind::LowUtilization, None, message)?;
+    }
+    let chain_latencies = config.chains.iter().map(|chain| ChainLatency::new(&worst, chain)).collect();
+
+    let report = AnalysisReport {
+        manifest,
+        path_counts,
+        dominated_paths,
+        wcet_over_deadline,
+        warnings,
+        confidence,
+        combination_count,
+        max_utilization,
+        convergence,
+        max_preemptions,
+        worst_paths,
+        interference,
+        worst,
+        lock_order_hazards,
+        resources,
+        ceilings: used_ceilings,
+        chain_latencies,
+        max_lock,
+        longest_sections,
+        breakdown,
+        sensitivity,
+        excluded_calls,
+        path_stats,
+        cyccnt_reads,
+        stack_required: stack_required(&config.tasks, config.prigroup, &max_stack),
+        max_stack,
+        wcets,
+        background_budgets,
+        utilizations,
+        not_enabled,
+        timed_out: config.timed_out.clone(),
+        sampled,
+        chip: config.chip,
+    };
+    on_event(AnalysisEvent::Done(&report));
+    Ok(report)
+}
+
+// `AnalysisReport::breakdown` and `AnalysisReport::sensitivity`.
+type Headroom = (Option<f64>, Option<Vec<(String, Option<u32>)>>);
+
+// The breakdown factor and the sensitivity of each task, if they are asked for, over `combinations`. Each
+// factor tried goes through all of them again, so they are taken as an iterator to clone rather than a list.
+fn headroom(
+    config: &AnalysisConfig,
+    rta_config: &RtaConfig,
+    combinations: impl Iterator<Item = Vec<(usize, Task)>> + Clone,
+) -> Headroom {
+    let task_sets =
+        combinations.map(|combination| combination.into_iter().map(|(_, t)| t).collect::<Vec<_>>());
+    let breakdown = config
+        .breakdown
+        .then(|| breakdown_factor(task_sets.clone(), rta_config));
+    let sensitivity = config.sensitivity.then(|| {
+        config
+            .tasks
+            .iter()
+            .map(|task| {
+                let extra = sensitivity(task_sets.clone(), rta_config, &task.name);
+                (task.name.to_owned(), extra)
+            })
+            .collect()
+    });
+    (breakdown, sensitivity)
+}
+
+// The paths of `task` from symex, or from the cache if it has them for the same inputs (see `cache_key`).
+fn cached_paths(
+    config: &AnalysisConfig,
+    task: &InputTask,
+    exclude: &[Regex],
+) -> Result<Vec<PathLaps>, AnalysisError> {
+    let run = || -> Result<Vec<PathLaps>, AnalysisError> {
+        let nvic = config.chip.table().nvic();
+        let results =
+            analyze_tasks(task, task.elf(&config.elf), exclude, &config.hooks, nvic, &config.preconditions)
+                .map_err(|e| AnalysisError::Task(task.name.to_owned(), Box::new(e)))?;
+        Ok(results.iter().map(PathLaps::from).collect())
+    };
+    let dir = match &config.cache_dir {
+        Some(dir) if config.hooks.is_empty() => dir,
+        _ => return run(),
+    };
+    // an elf that can't be read fails in `analyze_tasks` with the proper error.
+    let Ok(elf) = fs::read(task.elf(&config.elf)) else {
+        return run();
+    };
+    let key = cache_key(&elf, task, &config.exclude, config.chip.table().nvic(), &config.preconditions);
+    if let Some(paths) = load_cached(dir, &key) {
+        return Ok(paths);
+    }
+    let paths = run()?;
+    // the cache only saves time, the analysis goes on without it.
+    let _ = store_cached(dir, &key, &paths);
+    Ok(paths)
+}
+
+// `AnalysisConfig::exclude` compiled, an invalid pattern is a configuration error.
+fn exclude_regexes(config: &AnalysisConfig) -> Result<Vec<Regex>, AnalysisError> {
+    config
+        .exclude
+        .iter()
+        .map(|re| Regex::new(re).map_err(|e| AnalysisError::Config(format!("invalid exclude regex: {e}"))))
+        .collect()
+}
+
+// The paths of `task`, longest first, from `replay_laps`, the cache or symex.
+fn paths_of(
+    config: &AnalysisConfig,
+    task: &InputTask,
+    exclude: &[Regex],
+) -> Result<Vec<PathLaps>, AnalysisError> {
+    match &config.replay_laps {
+        Some(dir) => replay_laps(dir, &task.name).map_err(|e| {
+            AnalysisError::Laps(task.name.to_owned(), format!("could not replay the laps: {e}"))
+        }),
+        None => cached_paths(config, task, exclude),
+    }
+}
+
+// The longest path of the task named `name` as the analysis sees it, from `replay_laps`, the cache or symex.
+// `None` if there is no such task or it has no paths. Laps are all symex gives back of a path, it doesn't
+// return the instructions executed on it.
+pub fn worst_path(config: &AnalysisConfig, name: &str) -> Result<Option<PathLaps>, AnalysisError> {
+    // paths are sorted longest first.
+    Ok(task_paths(config, name)?.and_then(|paths| paths.into_iter().next()))
+}
+
+// The paths of the task named `name`, longest first, from `replay_laps`, the cache or symex. `None` if there
+// is no such task. Errs like `run_analysis` on an invalid exclude regex or paths that can't be had.
+pub fn task_paths(config: &AnalysisConfig, name: &str) -> Result<Option<Vec<PathLaps>>, AnalysisError> {
+    let Some(task) = config.tasks.iter().find(|t| t.name == name) else {
+        return Ok(None);
+    };
+    paths_of(config, task, &exclude_regexes(config)?).map(Some)
+}
+
+// Where the first critical section value that isn't constant is in the paths of `task`. Laps don't carry the
+// PC, so it is given as the cycle on the path.
+fn symbolic_lock(task: &InputTask, results: &[PathLaps]) -> Option<String> {
+    results.iter().enumerate().find_map(|(path, r)| {
+        let (cycle, _) = r.cycle_laps.iter().find(|(_, label)| label == SYMBOLIC_LABEL)?;
+        Some(format!(
+            "task {}: symbolic critical section value at cycle {cycle} of path {path}, make the locked \
+             value concrete or analyse without --require-concrete-locks",
+            task.name
+        ))
+    })
+}
+
+// Records a problem that doesn't stop the analysis, or stops it with an error in strict mode.
+fn warn(
+    config: &AnalysisConfig,
+    warnings: &mut Vec<Warning>,
+    kind: WarningKind,
+    task: Option<&str>,
+    message: String,
+) -> Result<(), AnalysisError> {
+    let warning = Warning {
+        kind,
+        task: task.map(str::to_owned),
+        message,
+    };
+    if config.strict {
+        return Err(AnalysisError::Strict(warning));
+    }
+    warnings.push(warning);
+    Ok(())
+}
+
+// Resources of `task` left locked at the end of some paths but released on others, with the paths leaving
+// them locked and the number of paths releasing them. A resource never released is left out, that is more
+// likely a lock the hooks don't see the release of than a forgotten unlock.
+fn unreleased_locks(task: &InputTask, results: &[PathLaps]) -> Vec<(String, Vec<usize>, usize)> {
+    let mut held: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut released: BTreeMap<String, usize> = BTreeMap::new();
+    for (path, result) in results.iter().enumerate() {
+        let laps = section_laps(result);
+        let unclosed: BTreeSet<String> =
+            match trace_from_laps(0, result.max_cycles, &laps, task.name.to_owned()) {
+                Ok(_) => BTreeSet::new(),
+                Err(e) => e
+                    .issues
+                    .into_iter()
+                    .filter_map(|issue| match issue {
+                        TraceIssue::Unclosed { id, .. } => Some(id),
+                        _ => None,
+                    })
+                    .collect(),
+            };
+        let locked: BTreeSet<&String> = laps.iter().map(|(_, label)| label).collect();
+        for resource in locked {
+            if unclosed.contains(resource) {
+                held.entry(resource.to_owned()).or_default().push(path);
+            } else {
+                *released.entry(resource.to_owned()).or_default() += 1;
+            }
+        }
+    }
+    held
+        .into_iter()
+        .filter_map(|(resource, paths)| {
+            let released = released.get(&resource).copied()?;
+            Some((resource, paths, released))
+        })
+        .collect()
+}
+
+// Names of the interrupts enabled by any of the ISER `masks`.
+fn enabled_interrupts(masks: &[String], table: &dyn InterruptTable) -> Vec<String> {
+    masks
+        .iter()
+        .filter_map(|mask| mask.parse::<u32>().ok())
+        .flat_map(|mask| irq_from_bit_vector(mask, table).0)
+        .map(str::to_owned)
+        .collect()
+}
+
+// Masks written to ISER on any path of `symbol`.
+fn interrupt_enables(elf: &str, symbol: &str, nvic: NvicAddresses) -> Result<Vec<String>, AnalysisError> {
+    check_symbol(&read_elf(elf)?, symbol)?;
+    let config = RunConfig {
+        pc_hooks: vec![],
+        register_read_hooks: vec![],
+        register_write_hooks: vec![],
+        memory_write_hooks: interrupt_enable_hooks(nvic),
+        memory_read_hooks: vec![],
+        show_path_results: false,
+    };
+    let results = run_elf(elf, symbol, config)
+        .map_err(|e| AnalysisError::SymbolicExecution(symbol.to_owned(), format!("{e:?}")))?;
+    Ok(results.into_iter().flat_map(|r| r.cycle_laps).map(|(_, label)| label).collect())
+}
+
+// The configured ceiling overrides by resource id and preemption level. An override below the highest
+// priority using the resource would let that task be preempted by another user while holding it, so it is
+// an error.
+fn ceiling_overrides(
+    config: &AnalysisConfig,
+    all_paths: &[Task],
+) -> Result<HashMap<String, u8>, String> {
+    let all_paths = Tasks(all_paths.to_vec());
+    let derived = rta::resource_ceilings(&all_paths);
+    let mut ceilings = HashMap::new();
+    for (name, priority) in &config.ceilings {
+        let ceiling = preemption_level(*priority, config.prigroup) as u8;
+        for id in resource_ids(name) {
+            if let Some(derived) = derived.get(id.as_str()) {
+                if ceiling < *derived {
+                    return Err(format!(
+                        "the ceiling of {} is set to {priority}, below the priority of a task using it",
+                        resource_name(&id, config.chip.table())
+                    ));
+                }
+            }
+            ceilings.insert(id, ceiling);
+        }
+    }
+    Ok(ceilings)
+}
+
+// How far below the stack pointer it was entered with a path took the stack, in bytes, see `stack_hook`.
+fn stack_depth(result: &PathLaps) -> u64 {
+    let last = result.cycle_laps.iter().rev().find_map(|(_, label)| stack_lap(label));
+    last.map_or(0, |(entry, lowest)| entry.saturating_sub(lowest))
+}
+
+// Cycles of the path at each read of the DWT cycle counter, see `cyccnt_hook`.
+fn cyccnt_read_cycles(result: &PathLaps) -> Vec<usize> {
+    result.cycle_laps.iter().filter(|(_, label)| label == CYCCNT_LABEL).map(|(cycle, _)| *cycle).collect()
+}
+
+// Most calls to excluded functions on a single path.
+fn count_excluded_calls(results: &[PathLaps]) -> usize {
+    results
+        .iter()
+        .map(|r| r.cycle_laps.iter().filter(|(_, label)| label == EXCLUDED_LABEL).count())
+        .max()
+        .unwrap_or(0)
+}
+
+// WCET of a single function without any scheduling, the longest path of `symbol` as a task with its critical
+// sections in the trace. `None` if symex found no paths.
+pub fn wcet_only(
+    elf: &str,
+    symbol: &str,
+    cs_mechanism: CsMechanism,
+    exclude: &[Regex],
+    nvic: NvicAddresses,
+) -> Result<Option<Task>, AnalysisError> {
+    let task = InputTask {
+        name: symbol.to_owned(),
+        interrupt: symbol.to_owned(),
+        priority: 0,
+        deadline: u32::MAX,
+        activation: Activation::Periodic { period: u32::MAX },
+        cs_mechanism,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    // paths are sorted longest first.
+    let result = analyze_tasks(&task, elf, exclude, &UserHooks::default(), nvic, &[])?;
+    result.first().map(|r| create_task(&PathLaps::from(r), &task, 0)).transpose()
+}
+
+// Collects everything in the symex results of a task that makes its analysis less trustworthy.
+pub fn task_confidence(task: &InputTask, results: &[PathLaps]) -> TaskConfidence {
+    let mut confidence = TaskConfidence::new(&task.name);
+
+    let symbolic_paths = results
+        .iter()
+        .filter(|r| r.cycle_laps.iter().any(|(_, label)| label == SYMBOLIC_LABEL))
+        .count();
+    if symbolic_paths > 0 {
+        confidence.reduce(format!(
+            "symbolic critical section value on {symbolic_paths} of {} paths",
+            results.len()
+        ));
+    }
+
+    confidence
+}
+
+// Compares the worst path of a task against its deadline before any scheduling is done.
+pub fn check_wcet_against_deadline(
+    task: &InputTask,
+    results: &[PathLaps],
+) -> Option<WcetOverDeadline> {
+    let wcet = results.iter().map(|r| r.max_cycles).max()?;
+    if wcet > task.analysed_deadline() as usize {
+        Some(WcetOverDeadline {
+            task: task.name.to_owned(),
+            wcet,
+            deadline: task.analysed_deadline(),
+        })
+    } else {
+        None
+    }
+}
+
+// `exclude` are the functions whose calls are skipped, see `exclude_hooks`. `user_hooks` are added to the
+// built in hooks, `nvic` is where the NVIC hooks of the chip watch for writes. `preconditions` are the values
+// memory is known to hold, see `precondition_hooks`.
+pub fn analyze_tasks(
+    task: &InputTask,
+    path: &str,
+    exclude: &[Regex],
+    user_hooks: &UserHooks,
+    nvic: NvicAddresses,
+    preconditions: &[Precondition],
+) -> Result<Vec<VisualPathResult>, AnalysisError> {
+    // path to the elf file to analyse.
+    let path_to_elf_file = path;
+    // name of the task in the elf file (same as associated interrupt vector for HW tasks unless the task
+    // is measured from its body).
+    let function_name = task.entry_symbol();
+
+    // only register the hooks for the critical section mechanism the task uses.
+    let hooks = critical_section_hooks(task.cs_mechanism, nvic);
+
+    let mut pc_hooks = exclude_hooks(exclude);
+    pc_hooks.extend(
+        user_hooks
+            .pc_hooks
+            .iter()
+            .map(|(re, hook)| (re.clone(), PCHook::Intrinsic(*hook))),
+    );
+    let mut register_write_hooks = hooks.register_write_hooks;
+    register_write_hooks.push(stack_hook());
+    register_write_hooks.extend(user_hooks.register_write_hooks.iter().cloned());
+    let mut memory_write_hooks = hooks.memory_write_hooks;
+    let mut memory_read_hooks = precondition_hooks(preconditions);
+    memory_read_hooks.push(cyccnt_hook());
+    // spinlocks are only resources shared between the cores of a dual-core application.
+    if task.core.is_some() {
+        let spinlocks = spinlock_hooks();
+        memory_read_hooks.extend(spinlocks.memory_read_hooks);
+        memory_write_hooks.extend(spinlocks.memory_write_hooks);
+    }
+    memory_write_hooks.extend(user_hooks.memory_write_hooks.iter().cloned());
+    memory_read_hooks.extend(user_hooks.memory_read_hooks.iter().cloned());
+
+    // create a run configuration with the hooks associated with the correct addresses.
+    let config = RunConfig {
+        pc_hooks,
+        register_read_hooks: user_hooks.register_read_hooks.clone(),
+        register_write_hooks,
+        memory_write_hooks,
+        memory_read_hooks,
+        show_path_results: false,
+    };
+
+    // run the symbolic execution
+    check_symbol(&read_elf(path_to_elf_file)?, function_name)?;
+    let mut results = run_elf(path_to_elf_file, function_name, config)
+        .map_err(|e| AnalysisError::SymbolicExecution(function_name.to_owned(), format!("{e:?}")))?;
+
+    // symex does not promise an order for the paths it finds. Sort them, longest first, so path indices and
+    // everything derived from them are the same from run to run.
+    results.sort_by(|a, b| {
+        b.max_cycles
+            .cmp(&a.max_cycles)
+            .then_with(|| a.cycle_laps.cmp(&b.cycle_laps))
+    });
+    Ok(results)
+}
+
+fn read_elf(path: &str) -> Result<Vec<u8>, AnalysisError> {
+    let elf = fs::read(path).map_err(|e| AnalysisError::ElfRead(path.to_owned(), e.to_string()))?;
+    if !elf.starts_with(b"\x7fELF") {
+        return Err(AnalysisError::ElfParse(path.to_owned()));
+    }
+    Ok(elf)
+}
+
+// Symbol names are NUL terminated in the string table, a name that isn't there as a whole can't be a symbol.
+// Checked up front as symex doesn't say which symbol it didn't find.
+fn check_symbol(elf: &[u8], symbol: &str) -> Result<(), AnalysisError> {
+    let name = [b"\0", symbol.as_bytes(), b"\0"].concat();
+    if elf.windows(name.len()).any(|w| w == name) {
+        Ok(())
+    } else {
+        Err(AnalysisError::FunctionNotFound(symbol.to_owned()))
+    }
+}
+
+// The tasks whose entry symbol isn't in their elf (see `InputTask::elf`), so all misspelled symbols are
+// found before any symex run. An elf of a task of its own that can't be read is reported for that task, the
+// elf of the application is an error. Tasks from trace files are left out, nothing is checked when no elf is
+// needed (`replay_laps`).
+pub fn check_entry_symbols(config: &AnalysisConfig) -> Result<Vec<(String, AnalysisError)>, AnalysisError> {
+    if config.replay_laps.is_some() {
+        return Ok(vec![]);
+    }
+    let mut elfs: HashMap<&str, Result<Vec<u8>, AnalysisError>> = HashMap::new();
+    let mut missing = vec![];
+    for task in &config.tasks {
+        if config.trace_files.iter().any(|(name, _)| *name == task.name) {
+            continue;
+        }
+        let path = task.elf(&config.elf);
+        let elf = match elfs.entry(path).or_insert_with(|| read_elf(path)) {
+            Ok(elf) => elf,
+            Err(e) if task.elf.is_none() => return Err(e.clone()),
+            Err(e) => {
+                missing.push((task.name.to_owned(), e.clone()));
+                continue;
+            }
+        };
+        if let Err(e) = check_symbol(elf, task.entry_symbol()) {
+            missing.push((task.name.to_owned(), e));
+        }
+    }
+    Ok(missing)
+}
+
+// The interrupt and exception handlers in the elf at `path`, see `vector_symbols`.
+pub fn list_vectors(path: &str, table: &dyn InterruptTable) -> Result<Vec<Vector>, AnalysisError> {
+    vector_symbols(&read_elf(path)?, table).ok_or_else(|| AnalysisError::ElfParse(path.to_owned()))
+}
+
+// `TaskResult` of srp doesn't implement `Clone` and can't be given one here (it is a foreign type). The result
+// is taken apart without `..`, so a field srp adds stops the build here instead of being dropped on the copy.
+fn clone_result(result: &TaskResult) -> TaskResult {
+    let TaskResult {
+        task,
+        response_time,
+        wcet,
+        blocking,
+        interference,
+    } = result;
+    TaskResult {
+        task: task.clone(),
+        response_time: *response_time,
+        wcet: *wcet,
+        blocking: *blocking,
+        interference: *interference,
+    }
+}
+
+// True if `b` is a worse result than `a`: a later response time, no response time (no fixed point) being
+// later than any. Equal response times are broken by the larger WCET, then the larger blocking.
+fn worse_result(a: &TaskResult, b: &TaskResult) -> bool {
+    // `None` sorts below `Some`, so it is mapped past every response time.
+    let key = |r: &TaskResult| (r.response_time.map_or(u64::MAX, |rt| rt as u64), r.wcet, r.blocking);
+    key(b) > key(a)
+}
+
+// Index of the combination each task (by position) has its worst result in, see `worse_result`. The first
+// one if several are equally bad, so the same combinations always give the same worst case.
+pub fn worst_combinations(list_of_task_results: &[TasksResult]) -> Vec<usize> {
+    // no combinations, e.g. a task without any paths.
+    let Some(first) = list_of_task_results.first() else {
+        return vec![];
+    };
+
+    (0..first.0.len())
+        .map(|i| {
+            let mut worst = 0;
+            for (c, task) in list_of_task_results.iter().enumerate() {
+                if worse_result(&list_of_task_results[worst].0[i], &task.0[i]) {
+                    worst = c;
+                }
+            }
+            worst
+        })
+        .collect()
+}
+
+// The worst result of each task over all combinations, see `worse_result`. Results are matched by task id, so
+// the combinations can list the tasks in any order and leave some out. Tasks are in the order they are first
+// seen in, the configured order when the first combination has them all.
+pub fn find_worst(list_of_task_results: Vec<TasksResult>) -> TasksResult {
+    let mut worst: Vec<TaskResult> = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for results in &list_of_task_results {
+        for result in &results.0 {
+            match index.get(&result.task.id) {
+                Some(&i) if worse_result(&worst[i], result) => worst[i] = clone_result(result),
+                Some(_) => {}
+                None => {
+                    index.insert(result.task.id.to_owned(), worst.len());
+                    worst.push(clone_result(result));
+                }
+            }
+        }
+    }
+    TasksResult(worst)
+}
+
+// False if the combination of `(path index, task)` picks a path of a correlation group for one of its tasks
+// and a path outside of it for another.
+pub fn correlated(combination: &[(usize, Task)], correlations: &[Vec<(String, usize)>]) -> bool {
+    correlations.iter().all(|group| {
+        let picked = |(task, path): &(String, usize)| {
+            combination.iter().any(|(p, t)| t.id == *task && p == path)
+        };
+        // tasks of the group that aren't in the combination don't count either way.
+        let members: Vec<&(String, usize)> = group
+            .iter()
+            .filter(|(task, _)| combination.iter().any(|(_, t)| t.id == *task))
+            .collect();
+        members.iter().all(|m| picked(m)) || !members.iter().any(|m| picked(m))
+    })
+}
+
+// Every combination of one item from each of `tasks`. Collects `combinations`, which builds them one at a time
+// instead of recursing into (and cloning) the rest of the sets for every item, use that directly to go
+// through them without holding all of them.
+pub fn get_all_sets<T: Clone>(tasks: &[Vec<T>]) -> Vec<Vec<T>> {
+    combinations(tasks).collect()
+}
@@ -0,0 +1,79 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::analysis::AnalysisReport;
+
+// A task of a report written by `--format json`, the other fields are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BaselineTask {
+    pub id: String,
+    pub wcet: u32,
+    // `None` if the response time was unbounded.
+    pub response_time: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct BaselineReport {
+    tasks: Vec<BaselineTask>,
+}
+
+// The tasks of a JSON report from an earlier run, see `json_report`.
+pub fn load_baseline(path: &Path) -> Result<Vec<BaselineTask>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("could not read the baseline {}: {e}", path.display()))?;
+    let report: BaselineReport =
+        serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(report.tasks)
+}
+
+// The worst case of a task next to its worst case in the baseline.
+pub struct BaselineCheck {
+    pub task: String,
+    pub wcet: u32,
+    // `None` if it is unbounded.
+    pub response_time: Option<u32>,
+    // `None` for a task that isn't in the baseline, it can't regress.
+    pub baseline: Option<BaselineTask>,
+    // how far, in percent of the baseline, a value may grow.
+    pub tolerance_percent: u32,
+}
+
+impl BaselineCheck {
+    // True if the WCET or the response time grew by more than the tolerance, or the response time became
+    // unbounded. Getting faster is never a regression.
+    pub fn regressed(&self) -> bool {
+        let Some(baseline) = &self.baseline else {
+            return false;
+        };
+        let allowed = |value: u32| value as u64 + value as u64 * self.tolerance_percent as u64 / 100;
+        let response_time = match (baseline.response_time, self.response_time) {
+            (Some(before), Some(now)) => now as u64 > allowed(before),
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        self.wcet as u64 > allowed(baseline.wcet) || response_time
+    }
+}
+
+// Compares the worst case of every task of `report` against `baseline`, by task name. `tolerance_percent` of
+// the baseline value is allowed on top, rounded down to whole cycles, so 0 fails on any growth. Tasks only
+// in the baseline are left out.
+pub fn check_baseline(
+    report: &AnalysisReport,
+    baseline: &[BaselineTask],
+    tolerance_percent: u32,
+) -> Vec<BaselineCheck> {
+    report
+        .worst
+        .0
+        .iter()
+        .map(|r| BaselineCheck {
+            task: r.task.id.to_owned(),
+            wcet: r.wcet,
+            response_time: r.response_time,
+            baseline: baseline.iter().find(|b| b.id == r.task.id).cloned(),
+            tolerance_percent,
+        })
+        .collect()
+}
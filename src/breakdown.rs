@@ -0,0 +1,138 @@
+use srp::common::{Task, Tasks, Trace};
+
+use crate::rta::{self, RtaConfig};
+
+// Relative precision the breakdown factor is searched to.
+const PRECISION: f64 = 1e-3;
+// Stop looking for an upper bound here, the set is then treated as schedulable at any realistic factor.
+const MAX_FACTOR: f64 = 1e6;
+
+// True if every task in the set has a bounded response time within its deadline.
+pub fn schedulable(tasks: &Tasks, config: &RtaConfig) -> bool {
+    rta::response_time(tasks, config)
+        .results
+        .0
+        .iter()
+        .all(|r| matches!(r.response_time, Some(rt) if rt <= r.task.deadline))
+}
+
+// Scales all times in a trace, critical sections grow along with the execution time of the task. Starts are
+// rounded down and ends up so sections stay inside their parent.
+pub fn scale_trace(trace: &Trace, factor: f64) -> Trace {
+    Trace {
+        id: trace.id.to_owned(),
+        start: (trace.start as f64 * factor).floor() as u32,
+        end: (trace.end as f64 * factor).ceil() as u32,
+        inner: trace.inner.iter().map(|t| scale_trace(t, factor)).collect(),
+    }
+}
+
+fn scaled_schedulable<C>(combinations: C, config: &RtaConfig, factor: f64) -> bool
+where
+    C: IntoIterator,
+    C::Item: AsRef<[Task]>,
+{
+    combinations.into_iter().all(|tasks| {
+        let scaled = tasks
+            .as_ref()
+            .iter()
+            .map(|t| Task {
+                id: t.id.to_owned(),
+                prio: t.prio,
+                deadline: t.deadline,
+                inter_arrival: t.inter_arrival,
+                trace: scale_trace(&t.trace, factor),
+            })
+            .collect();
+        schedulable(&Tasks(scaled), config)
+    })
+}
+
+// The breakdown factor: the largest factor every WCET can be multiplied with while all combinations stay
+// schedulable. Below 1 the set is not schedulable as it is. Found by bisection using the same response time
+// analysis as the rest of the tool.
+pub fn breakdown_factor<C>(combinations: C, config: &RtaConfig) -> f64
+where
+    C: IntoIterator + Clone,
+    C::Item: AsRef<[Task]>,
+{
+    let mut low = 0.0;
+    let mut high = 1.0;
+
+    // double the factor until the set breaks down to get an upper bound.
+    while scaled_schedulable(combinations.clone(), config, high) {
+        low = high;
+        high *= 2.0;
+        if high > MAX_FACTOR {
+            return low;
+        }
+    }
+
+    while high - low > PRECISION * high {
+        let mid = (low + high) / 2.0;
+        if scaled_schedulable(combinations.clone(), config, mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    low
+}
+
+fn grown_schedulable<C>(combinations: C, config: &RtaConfig, task: &str, extra: u32) -> bool
+where
+    C: IntoIterator,
+    C::Item: AsRef<[Task]>,
+{
+    combinations.into_iter().all(|tasks| {
+        let grown = tasks
+            .as_ref()
+            .iter()
+            .map(|t| {
+                let mut t = t.clone();
+                if t.id == task {
+                    t.trace.end = t.trace.end.saturating_add(extra);
+                }
+                t
+            })
+            .collect();
+        schedulable(&Tasks(grown), config)
+    })
+}
+
+// How many cycles the WCET of `task` can grow by, with every other task as it is, while all combinations stay
+// schedulable. The cycles are added outside the critical sections of the task. `None` if the set is not
+// schedulable to begin with.
+pub fn sensitivity<C>(combinations: C, config: &RtaConfig, task: &str) -> Option<u32>
+where
+    C: IntoIterator + Clone,
+    C::Item: AsRef<[Task]>,
+{
+    if !grown_schedulable(combinations.clone(), config, task, 0) {
+        return None;
+    }
+
+    // the task misses its own deadline at the latest when it grows past it, so this terminates well before
+    // the cap for any realistic deadline.
+    let mut low = 0;
+    let mut high = 1;
+    while grown_schedulable(combinations.clone(), config, task, high) {
+        low = high;
+        if high >= u32::MAX / 4 {
+            return Some(low);
+        }
+        high *= 2;
+    }
+
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if grown_schedulable(combinations.clone(), config, task, mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some(low)
+}
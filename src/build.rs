@@ -0,0 +1,61 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde::Deserialize;
+
+// The parts of a line of `cargo build --message-format=json` needed to find the built example.
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    #[serde(default)]
+    target: Option<Target>,
+    #[serde(default)]
+    executable: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    name: String,
+}
+
+// The elf to analyse without `--elf`, `name` in `dir` (`rtic_full_example` in `test_bin` for the ones not
+// given). The binary takes them from `BIN_NAME` and `BIN_DIR`.
+pub fn default_elf(dir: Option<String>, name: Option<String>) -> String {
+    let dir = dir.unwrap_or_else(|| "test_bin".to_owned());
+    let name = name.unwrap_or_else(|| "rtic_full_example".to_owned());
+    Path::new(&dir).join(name).to_string_lossy().into_owned()
+}
+
+// The elf of `example` from the json messages cargo printed while building it. Cargo decides where the
+// target directory is, so the path is taken from its output instead of being put together here.
+pub fn example_elf(messages: &str, example: &str) -> Option<PathBuf> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Message>(line).ok())
+        .filter(|m| m.reason == "compiler-artifact")
+        .filter(|m| m.target.as_ref().is_some_and(|t| t.name == example))
+        .find_map(|m| m.executable)
+}
+
+// Builds `example` in release mode in the cargo project at `dir` and returns the path of its elf. The error
+// holds what cargo printed on failure.
+pub fn build_example(dir: &Path, example: &str) -> Result<PathBuf, String> {
+    let output = Command::new("cargo")
+        .args(["build", "--release", "--message-format=json", "--example", example])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("could not run cargo in {}: {e}", dir.display()))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "building example {example} in {} failed:\n{}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    example_elf(&String::from_utf8_lossy(&output.stdout), example)
+        .ok_or_else(|| format!("cargo built no executable named {example}"))
+}
@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use srp::common::{Task, Trace};
+
+use crate::{hooks::CsMechanism, interrupts::InterruptTable, task::InputTask};
+
+// A critical section that doesn't raise the system to the ceiling of its resource. A task above the locking
+// one and at most at the ceiling can then preempt the section and use the resource, a priority inversion
+// the blocking bounds of the analysis don't cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CeilingViolation {
+    pub task: String,
+    pub resource: String,
+    // as a preemption level.
+    pub ceiling: u8,
+    // the tasks between `task` and the ceiling whose interrupts the section leaves unmasked.
+    pub unmasked: Vec<String>,
+}
+
+// The interrupt mask of a resource id, ids that aren't masks mask nothing.
+fn mask(id: &str) -> u32 {
+    id.parse().unwrap_or(0)
+}
+
+// The mask of `section` and of the sections opened by the same write, those inside it starting with it.
+fn opened_with(section: &Trace) -> u32 {
+    let opened = section.inner.iter().filter(|s| s.start == section.start);
+    opened.fold(mask(&section.id), |m, s| m | opened_with(s))
+}
+
+// Every resource locked in `trace`, nested sections included, with the masks in effect in its sections. The
+// interrupts of a write are separate resources, see `mask_labels`, so the mask of a section is combined with
+// those of the sections around it and opened with it.
+fn locked<'a>(trace: &'a Trace, around: u32, masks: &mut BTreeMap<&'a str, BTreeSet<u32>>) {
+    for inner in &trace.inner {
+        masks.entry(&inner.id).or_default().insert(around | opened_with(inner));
+        locked(inner, around | mask(&inner.id), masks);
+    }
+}
+
+// Checks the NVIC critical sections of `tasks` against the `ceilings` the analysis used: each task on the same
+// core above the locking task and at most at the ceiling has to have its interrupt in the mask. PRIMASK masks
+// everything, and a BASEPRI value can't be related to a task priority without the priority bits of the chip,
+// so those sections aren't checked. `tasks` can hold several paths of the same task, `inputs` gives their
+// interrupts, tasks without one (servers) are left out.
+pub fn ceiling_violations(
+    tasks: &[Task],
+    inputs: &[InputTask],
+    ceilings: &BTreeMap<String, u8>,
+    table: &dyn InterruptTable,
+) -> Vec<CeilingViolation> {
+    let input = |id: &str| inputs.iter().find(|t| t.name == id);
+    let levels: BTreeMap<&str, u8> = tasks.iter().map(|t| (t.id.as_str(), t.prio)).collect();
+    let mut resources: BTreeMap<&str, BTreeMap<&str, BTreeSet<u32>>> = BTreeMap::new();
+    for task in tasks {
+        locked(&task.trace, 0, resources.entry(&task.id).or_default());
+    }
+
+    let mut violations = vec![];
+    for (id, resources) in resources {
+        let Some(locker) = input(id).filter(|t| t.cs_mechanism == CsMechanism::Nvic) else {
+            continue;
+        };
+        for (resource, masks) in resources {
+            let (Ok(_), Some(&ceiling)) = (resource.parse::<u32>(), ceilings.get(resource)) else {
+                continue;
+            };
+            // ISER/ICER hold the first 32 interrupts, the hooks don't see the others.
+            let unmasked: Vec<String> = levels
+                .iter()
+                .filter(|(_, level)| **level > levels[id] && **level <= ceiling)
+                .filter_map(|(other, _)| input(other))
+                .filter(|other| !other.background && other.core() == locker.core())
+                .filter(|other| {
+                    let Some(irq) = table.index(&other.interrupt) else {
+                        return false;
+                    };
+                    masks.iter().any(|m| m.checked_shr(irq as u32).is_some_and(|bits| bits & 1 == 0))
+                })
+                .map(|other| other.name.to_owned())
+                .collect();
+            if !unmasked.is_empty() {
+                violations.push(CeilingViolation {
+                    task: id.to_owned(),
+                    resource: resource.to_owned(),
+                    ceiling,
+                    unmasked,
+                });
+            }
+        }
+    }
+    violations
+}
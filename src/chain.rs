@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use srp::common::TasksResult;
+
+// Tasks where each one is released by the one before it, e.g. a button handler spawning the task that drives
+// an LED.
+#[derive(Debug, Clone)]
+pub struct Chain {
+    pub name: String,
+    pub tasks: Vec<String>,
+    // cycles from a task finishing until the next one is released, for every hop (0 when it is pended
+    // directly).
+    pub activation_delay: u32,
+    // cycles from the release of the first task until the last one has to finish, `None` if the latency is
+    // only reported.
+    pub deadline: Option<u32>,
+}
+
+// The end to end latency of a chain next to its deadline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLatency {
+    pub chain: String,
+    pub tasks: Vec<String>,
+    // `None` if it is unbounded, see `chain_latency`.
+    pub latency: Option<u64>,
+    pub deadline: Option<u32>,
+}
+
+impl ChainLatency {
+    pub fn new(results: &TasksResult, chain: &Chain) -> ChainLatency {
+        ChainLatency {
+            chain: chain.name.to_owned(),
+            tasks: chain.tasks.clone(),
+            latency: chain_latency(results, chain),
+            deadline: chain.deadline,
+        }
+    }
+
+    // True if the chain has a deadline and the latency is above it or unbounded.
+    pub fn missed(&self) -> bool {
+        match (self.deadline, self.latency) {
+            (Some(deadline), Some(latency)) => latency > deadline as u64,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+// Bound on the time from the release of the first task of the chain until the last one finishes: the sum of
+// their worst response times and the activation delays between them. `None` if a task has no bounded
+// response time or isn't in `results`.
+pub fn chain_latency(results: &TasksResult, chain: &Chain) -> Option<u64> {
+    let mut latency = 0u64;
+    for task in &chain.tasks {
+        let result = results.0.iter().find(|r| r.task.id == *task)?;
+        latency += result.response_time? as u64;
+    }
+    let hops = chain.tasks.len().saturating_sub(1) as u64;
+    Some(latency + hops * chain.activation_delay as u64)
+}
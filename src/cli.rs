@@ -0,0 +1,531 @@
+// Pieces of the command line tool that don't need its arguments, kept here so they can be tested.
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use srp::common::TaskResult;
+
+use crate::{
+    analysis::{run_analysis, AnalysisConfig, AnalysisReport, PathStats, Warning},
+    chain::Chain,
+    hooks::CsMechanism,
+    interrupts::resource_name,
+    rta::Reservation,
+    schedulability::{schedulability, Schedulability},
+    task::{validate_priorities, Activation, InputTask, TaskFilter},
+    units::{rescale, Rounding, TimeUse, TimeValue, UnitError},
+};
+
+// A bar `width` characters wide filled to `fraction`, marked with `!` past the end when the fraction is over 1.
+pub fn ascii_bar(fraction: f32, width: usize) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0) * width as f32).round() as usize).min(width);
+    let overflow = if fraction > 1.0 { "!" } else { "" };
+    format!("[{}{}]{overflow}", "#".repeat(filled), ".".repeat(width - filled))
+}
+
+// Files in `dir` to analyse as variants of the application, sorted by name. Files ending in `.elf` and files
+// without an extension (like the fixtures in `test_bin`) are taken.
+pub fn elf_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let elf = match path.extension() {
+            Some(ext) => ext == "elf",
+            None => true,
+        };
+        if path.is_file() && elf {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// The line of the variant `name` in the `--elf-dir` table, counting only the tasks `filter` matches.
+pub fn variant_line(name: &str, schedulability: &Schedulability, filter: &TaskFilter) -> String {
+    let results: Vec<_> = schedulability.tasks.iter().filter(|t| filter.matches(&t.task)).collect();
+    let missing = results.iter().filter(|t| !t.schedulable).count();
+    if missing == 0 {
+        format!("{name}: schedulable")
+    } else {
+        format!("{name}: {missing} of {} tasks miss their deadline", results.len())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Sort {
+    /// Highest priority first, ties by name, so the report reads like the preemption hierarchy.
+    Priority,
+    /// The order the tasks are configured in.
+    Config,
+    /// Least slack (deadline minus response time) first, unbounded response times before everything.
+    Slack,
+}
+
+// Orders the results for the text report.
+pub fn sort_results(results: &mut [&TaskResult], sort: Sort) {
+    match sort {
+        Sort::Priority => {
+            results.sort_by(|a, b| b.task.prio.cmp(&a.task.prio).then_with(|| a.task.id.cmp(&b.task.id)))
+        }
+        Sort::Config => {}
+        Sort::Slack => results.sort_by_key(|r| r.response_time.map(|rt| r.task.deadline as i64 - rt as i64)),
+    }
+}
+
+// The errors `--warn-as-error` fails the run with, one per warning, none without the flag.
+pub fn warning_errors(warnings: &[Warning], warn_as_error: bool) -> Vec<String> {
+    if !warn_as_error {
+        return vec![];
+    }
+    warnings.iter().map(|warning| format!("error: {warning}")).collect()
+}
+
+// The `START,END,STEP` of `--clock-sweep`, in Hz.
+pub fn parse_sweep(s: &str) -> Result<(u64, u64, u64), String> {
+    let number = |v: &str| v.parse().map_err(|_| format!("`{v}` is not a valid frequency"));
+    let parts: Vec<&str> = s.split(',').collect();
+    let [start, end, step] = parts[..] else {
+        return Err(format!("expected START,END,STEP, got `{s}`"));
+    };
+    let (start, end, step) = (number(start)?, number(end)?, number(step)?);
+    if step == 0 || start > end {
+        return Err(format!("`{s}` is an empty sweep, expected START <= END and a STEP above 0"));
+    }
+    Ok((start, end, step))
+}
+
+// `tasks` with their deadlines and inter arrival times moved from cycles at `from_hz` to cycles at `to_hz`.
+pub fn at_clock(tasks: &[InputTask], from_hz: u64, to_hz: u64) -> Result<Vec<InputTask>, UnitError> {
+    let limit = |cycles: u32| {
+        let cycles = rescale(cycles as u64, from_hz, to_hz, TimeUse::Limit)?;
+        u32::try_from(cycles).map_err(|_| UnitError::Overflow(format!("{cycles} cycles at {to_hz} Hz")))
+    };
+    tasks
+        .iter()
+        .map(|task| {
+            Ok(InputTask {
+                deadline: limit(task.deadline)?,
+                activation: task.activation.with_inter_arrival(limit(task.activation.inter_arrival())?),
+                ..task.clone()
+            })
+        })
+        .collect()
+}
+
+// A --set-period value, `TASK=PERIOD[/DEADLINE]`.
+pub fn parse_period(s: &str) -> Result<(String, TimeValue, Option<TimeValue>), String> {
+    let (task, values) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TASK=PERIOD[/DEADLINE], got `{s}`"))?;
+    let (period, deadline) = match values.split_once('/') {
+        Some((period, deadline)) => (period, Some(deadline)),
+        None => (values, None),
+    };
+    let period = period.parse().map_err(|e: UnitError| e.to_string())?;
+    let deadline = deadline
+        .map(|d| d.parse())
+        .transpose()
+        .map_err(|e: UnitError| e.to_string())?;
+    Ok((task.to_owned(), period, deadline))
+}
+
+// `value` as a deadline or inter arrival time in cycles at `clock_hz`.
+pub fn limit_cycles(value: &TimeValue, clock_hz: u64) -> Result<u32, String> {
+    let cycles = value
+        .to_cycles(Some(clock_hz), Rounding::Conservative, TimeUse::Limit)
+        .map_err(|e| e.to_string())?;
+    u32::try_from(cycles).map_err(|_| format!("{cycles} cycles is too large"))
+}
+
+// Applies the --set-period overrides to `tasks` and describes each one for the report.
+pub fn set_periods(
+    tasks: &mut [InputTask],
+    overrides: &[(String, TimeValue, Option<TimeValue>)],
+    clock_hz: u64,
+) -> Result<Vec<String>, String> {
+    let cycles = |value: &TimeValue| limit_cycles(value, clock_hz);
+    let mut applied = vec![];
+    for (name, period, deadline) in overrides {
+        let task = tasks
+            .iter_mut()
+            .find(|t| t.name == *name)
+            .ok_or_else(|| format!("--set-period: there is no task named {name}"))?;
+        task.activation = task.activation.with_inter_arrival(cycles(period)?);
+        let mut description = format!("{name} inter arrival time {} cycles", task.activation.inter_arrival());
+        if let Some(deadline) = deadline {
+            task.deadline = cycles(deadline)?;
+            description += &format!(", deadline {} cycles", task.deadline);
+        }
+        applied.push(description);
+    }
+    Ok(applied)
+}
+
+// Drops the tasks not named in `only` (none if it is empty), for --only. Returns the names of the tasks left
+// out, in the configured order.
+pub fn only_tasks(tasks: &mut Vec<InputTask>, only: &[String]) -> Result<Vec<String>, String> {
+    if let Some(name) = only.iter().find(|name| !tasks.iter().any(|t| t.name == **name)) {
+        return Err(format!("--only: there is no task named {name}"));
+    }
+    if only.is_empty() {
+        return Ok(vec![]);
+    }
+    let (kept, left_out): (Vec<InputTask>, Vec<InputTask>) =
+        tasks.drain(..).partition(|t| only.contains(&t.name));
+    *tasks = kept;
+    Ok(left_out.into_iter().map(|t| t.name).collect())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MatrixFormat {
+    /// Columns padded to line up.
+    Text,
+    Csv,
+}
+
+// The resources each task locks as a table, see `--resource-matrix`. Tasks are ordered highest priority
+// first, ties by name, and resources by id. Ceilings and priorities are preemption levels.
+pub fn resource_matrix(report: &AnalysisReport, format: MatrixFormat) -> String {
+    let mut tasks: Vec<(&str, u8)> =
+        report.worst.0.iter().map(|r| (r.task.id.as_str(), r.task.prio)).collect();
+    tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut rows = vec![];
+    let mut header = vec!["task".to_owned(), "priority".to_owned()];
+    header.extend(report.resources.keys().map(|r| resource_name(r, report.chip.table())));
+    rows.push(header);
+    for (task, prio) in &tasks {
+        let mut row = vec![task.to_string(), prio.to_string()];
+        row.extend(report.resources.values().map(|users| match users.contains(*task) {
+            true => "x".to_owned(),
+            false => String::new(),
+        }));
+        rows.push(row);
+    }
+    let mut ceilings = vec!["ceiling".to_owned(), String::new()];
+    ceilings.extend(
+        report
+            .resources
+            .keys()
+            .map(|r| report.ceilings.get(r).map_or(String::new(), |c| c.to_string())),
+    );
+    rows.push(ceilings);
+
+    let mut matrix = String::new();
+    match format {
+        MatrixFormat::Csv => {
+            // resource names list their interrupts separated by commas.
+            let quote = |c: &String| match c.contains(',') {
+                true => format!("\"{c}\""),
+                false => c.to_owned(),
+            };
+            for row in &rows {
+                let cells: Vec<String> = row.iter().map(quote).collect();
+                matrix += &format!("{}\n", cells.join(","));
+            }
+        }
+        MatrixFormat::Text => {
+            let mut widths = vec![0; rows[0].len()];
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.len());
+                }
+            }
+            for row in &rows {
+                let cells: Vec<String> = row.iter().zip(&widths).map(|(c, w)| format!("{c:w$}")).collect();
+                matrix += &format!("{}\n", cells.join("  ").trim_end());
+            }
+        }
+    }
+    matrix
+}
+
+// The task for --fit-task, the period and deadline still in the unit they were given in.
+#[derive(Debug, Clone)]
+pub struct FitTask {
+    pub name: String,
+    pub interrupt: String,
+    pub period: TimeValue,
+    pub deadline: Option<TimeValue>,
+}
+
+pub fn parse_fit_task(s: &str) -> Result<FitTask, String> {
+    let (task, values) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME@INTERRUPT=PERIOD[/DEADLINE], got `{s}`"))?;
+    let (name, interrupt) = task
+        .split_once('@')
+        .ok_or_else(|| format!("expected NAME@INTERRUPT=PERIOD[/DEADLINE], got `{s}`"))?;
+    let (period, deadline) = match values.split_once('/') {
+        Some((period, deadline)) => (period, Some(deadline)),
+        None => (values, None),
+    };
+    Ok(FitTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        period: period.parse().map_err(|e: UnitError| e.to_string())?,
+        deadline: deadline
+            .map(|d| d.parse())
+            .transpose()
+            .map_err(|e: UnitError| e.to_string())?,
+    })
+}
+
+// The outcome of the new task at one priority in `fit_priorities`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PriorityFit {
+    // the priority isn't valid with the other tasks, see `validate_priorities`.
+    Skipped(String),
+    Schedulable,
+    // `missing` of the `total` tasks (the new one included) miss their deadline.
+    Missing { missing: usize, total: usize },
+}
+
+// Runs the analysis with `fit` added at every candidate priority from 1 to one above the highest, lowest
+// first, for --fit-task. Symex only runs for the first priority and the others replay its laps (from
+// --record-laps or a temporary directory). `config.tasks` is left with the last candidate.
+pub fn fit_priorities(
+    config: &mut AnalysisConfig,
+    fit: &FitTask,
+    clock_hz: u64,
+) -> Result<Vec<(u32, PriorityFit)>, String> {
+    if config.tasks.iter().any(|t| t.name == fit.name) {
+        return Err(format!("--fit-task: there is already a task named {}", fit.name));
+    }
+    let period = limit_cycles(&fit.period, clock_hz)?;
+    let deadline = match &fit.deadline {
+        Some(deadline) => limit_cycles(deadline, clock_hz)?,
+        None => period,
+    };
+    let new_task = InputTask {
+        name: fit.name.to_owned(),
+        interrupt: fit.interrupt.to_owned(),
+        priority: 0,
+        deadline,
+        activation: Activation::Periodic { period },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+
+    let tasks = config.tasks.clone();
+    let temporary = config.replay_laps.is_none() && config.record_laps.is_none();
+    if temporary {
+        let dir = env::temp_dir().join(format!("easy-poc-fit-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("could not create a directory for the laps");
+        config.record_laps = Some(dir);
+    }
+
+    let highest = tasks.iter().map(|t| t.priority).max().unwrap_or(0);
+    let mut fits = vec![];
+    for priority in 1..=highest + 1 {
+        let mut candidate = tasks.clone();
+        candidate.push(InputTask {
+            priority,
+            ..new_task.clone()
+        });
+        if let Err(e) = validate_priorities(&candidate, config.equal_priority) {
+            fits.push((priority, PriorityFit::Skipped(e)));
+            continue;
+        }
+        config.tasks = candidate;
+        let schedulability = schedulability(&run_analysis(config).map_err(|e| e.to_string())?);
+        if let Some(dir) = config.record_laps.take() {
+            config.replay_laps = Some(dir);
+        }
+        let fit = match schedulability.schedulable {
+            true => PriorityFit::Schedulable,
+            false => PriorityFit::Missing {
+                missing: schedulability.tasks.iter().filter(|t| !t.schedulable).count(),
+                total: schedulability.tasks.len(),
+            },
+        };
+        fits.push((priority, fit));
+    }
+
+    if temporary {
+        if let Some(dir) = config.replay_laps.as_ref().or(config.record_laps.as_ref()) {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+    Ok(fits)
+}
+
+// A --ceiling value, `RESOURCE=PRIORITY`.
+pub fn parse_ceiling(s: &str) -> Result<(String, u32), String> {
+    let (resource, priority) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected RESOURCE=PRIORITY, got `{s}`"))?;
+    let priority = priority
+        .parse()
+        .map_err(|_| format!("`{priority}` is not a valid priority"))?;
+    Ok((resource.to_owned(), priority))
+}
+
+// A --server value, `BUDGET/PERIOD[@PRIORITY]` in cycles.
+pub fn parse_server(s: &str) -> Result<(u32, u32, Option<u32>), String> {
+    let number = |v: &str| v.parse().map_err(|_| format!("`{v}` is not a valid number"));
+    let (times, priority) = match s.split_once('@') {
+        Some((times, priority)) => (times, Some(number(priority)?)),
+        None => (s, None),
+    };
+    let (budget, period) = times
+        .split_once('/')
+        .ok_or_else(|| format!("expected BUDGET/PERIOD[@PRIORITY], got `{s}`"))?;
+    Ok((number(budget)?, number(period)?, priority))
+}
+
+// A --trace-file value, `TASK=FILE`.
+pub fn parse_trace_file(s: &str) -> Result<(String, PathBuf), String> {
+    let (task, file) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TASK=FILE, got `{s}`"))?;
+    Ok((task.to_owned(), PathBuf::from(file)))
+}
+
+// A --reservation value, `BUDGET/PERIOD` in cycles.
+pub fn parse_reservation(s: &str) -> Result<Reservation, String> {
+    let number = |v: &str| v.parse().map_err(|_| format!("`{v}` is not a valid number"));
+    let (budget, period) = s
+        .split_once('/')
+        .ok_or_else(|| format!("expected BUDGET/PERIOD, got `{s}`"))?;
+    Ok(Reservation {
+        budget: number(budget)?,
+        period: number(period)?,
+    })
+}
+
+// A --correlate value, `TASK:PATH,TASK:PATH...`.
+pub fn parse_correlation(s: &str) -> Result<Vec<(String, usize)>, String> {
+    s.split(',')
+        .map(|member| {
+            let (task, path) = member
+                .split_once(':')
+                .ok_or_else(|| format!("expected TASK:PATH,TASK:PATH..., got `{s}`"))?;
+            let path = path
+                .parse()
+                .map_err(|_| format!("`{path}` is not a valid path index"))?;
+            Ok((task.to_owned(), path))
+        })
+        .collect()
+}
+
+// A --chain value, `NAME=TASK,TASK...[+CYCLES][/DEADLINE]`.
+pub fn parse_chain(s: &str) -> Result<Chain, String> {
+    let (name, rest) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=TASK,TASK...[+CYCLES][/DEADLINE], got `{s}`"))?;
+    let (rest, deadline) = match rest.split_once('/') {
+        Some((rest, deadline)) => {
+            let deadline = deadline
+                .parse()
+                .map_err(|_| format!("`{deadline}` is not a valid number of cycles"))?;
+            (rest, Some(deadline))
+        }
+        None => (rest, None),
+    };
+    let (tasks, activation_delay) = match rest.split_once('+') {
+        Some((tasks, delay)) => {
+            let delay = delay
+                .parse()
+                .map_err(|_| format!("`{delay}` is not a valid number of cycles"))?;
+            (tasks, delay)
+        }
+        None => (rest, 0),
+    };
+    Ok(Chain {
+        name: name.to_owned(),
+        tasks: tasks.split(',').map(|t| t.to_owned()).collect(),
+        activation_delay,
+        deadline,
+    })
+}
+
+// A --tag value, `KEY=VALUE`.
+pub fn parse_tag(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))?;
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+// Whether --save-report and --load-report use the binary format for `path`, see `save_report_bin`.
+pub fn is_binary_report(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "bin")
+}
+
+// Exit status for the schedulability of `report`, the same whatever format it is printed in: 1 if a task
+// misses its deadline or has no bounded response time, 0 if all meet theirs.
+pub fn schedulability_exit_code(report: &AnalysisReport) -> i32 {
+    if schedulability(report).schedulable {
+        0
+    } else {
+        1
+    }
+}
+
+const HISTOGRAM_BUCKETS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+// The cycles of all paths of a task in up to `HISTOGRAM_BUCKETS` buckets of equal width from its shortest to
+// its longest path, a line per bucket for `--histogram`. The longest bar is `HISTOGRAM_WIDTH` wide, a bucket
+// with any path at least 1. No lines without paths.
+pub fn histogram(stats: &PathStats) -> Vec<String> {
+    if stats.cycles.is_empty() {
+        return vec![];
+    }
+    let spread = stats.max_cycles - stats.min_cycles;
+    let size = spread / HISTOGRAM_BUCKETS + 1;
+    let mut counts = vec![0; spread / size + 1];
+    for cycles in &stats.cycles {
+        counts[(cycles - stats.min_cycles) / size] += 1;
+    }
+    let top = counts.iter().copied().max().unwrap_or(1);
+    let width = (stats.min_cycles + counts.len() * size - 1).to_string().len();
+    let count_width = top.to_string().len();
+    counts
+        .iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let from = stats.min_cycles + i * size;
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(top));
+            let range = format!("{from:>width$}..={:>width$}", from + size - 1);
+            format!("{range} cycles  {count:>count_width$}  {bar}")
+        })
+        .collect()
+}
+
+// The line of the report listing the reads of the DWT cycle counter by `task`, each after the first with the
+// cycles since the one before, to hold against the difference of the same two reads on the target.
+pub fn cyccnt_line(task: &str, reads: &[usize]) -> String {
+    let mut cycles: Vec<String> = reads.first().map(|first| first.to_string()).into_iter().collect();
+    cycles.extend(reads.windows(2).map(|w| format!("{} (+{})", w[1], w[1] - w[0])));
+    format!("Task: {task} reads DWT CYCCNT at cycles {} on its longest path.", cycles.join(", "))
+}
+
+// The utilization of each task matching `filter`, largest share first to see where the headroom went, and
+// the total of all tasks.
+pub fn utilization_lines(report: &AnalysisReport, filter: &TaskFilter) -> Vec<String> {
+    let mut utilizations: Vec<&(String, f64)> = report.utilizations.iter().collect();
+    utilizations.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let mut lines: Vec<String> = utilizations
+        .iter()
+        .filter(|(task, _)| filter.matches(task))
+        .map(|(task, utilization)| format!("Utilization of {task}: {utilization:.3}"))
+        .collect();
+    let total: f64 = report.utilizations.iter().map(|(_, utilization)| utilization).sum();
+    lines.push(format!("Total utilization: {total:.3}"));
+    lines
+}
@@ -0,0 +1,80 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+use srp::common::TasksResult;
+
+// One line in the jsonl file, describing a single combination of paths and how it scheduled.
+#[derive(Serialize)]
+struct CombinationRecord {
+    combination: usize,
+    // index of the symex path each task was taken from.
+    paths: Vec<PathId>,
+    total_utilization: f64,
+    response_times: Vec<ResponseTime>,
+}
+
+#[derive(Serialize)]
+struct PathId {
+    task: String,
+    path: usize,
+}
+
+#[derive(Serialize)]
+struct ResponseTime {
+    task: String,
+    // None when the response time analysis found no fixed point.
+    response_time: Option<u32>,
+    deadline: u32,
+}
+
+// Writes the outcome of every analysed combination to a jsonl file (one json object per line).
+pub struct CombinationLog {
+    out: BufWriter<File>,
+}
+
+impl CombinationLog {
+    pub fn create(path: &Path) -> io::Result<CombinationLog> {
+        let file = File::create(path)?;
+        Ok(CombinationLog {
+            out: BufWriter::new(file),
+        })
+    }
+
+    pub fn record(
+        &mut self,
+        combination: usize,
+        path_ids: &[(String, usize)],
+        total_utilization: f64,
+        result: &TasksResult,
+    ) -> io::Result<()> {
+        let record = CombinationRecord {
+            combination,
+            paths: path_ids
+                .iter()
+                .map(|(task, path)| PathId {
+                    task: task.to_owned(),
+                    path: *path,
+                })
+                .collect(),
+            total_utilization,
+            response_times: result
+                .0
+                .iter()
+                .map(|r| ResponseTime {
+                    task: r.task.id.to_owned(),
+                    response_time: r.response_time,
+                    deadline: r.task.deadline,
+                })
+                .collect(),
+        };
+
+        serde_json::to_writer(&mut self.out, &record)?;
+        self.out.write_all(b"\n")?;
+        // flush every line so a crash mid sweep still leaves the finished combinations on disk.
+        self.out.flush()
+    }
+}
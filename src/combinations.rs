@@ -0,0 +1,83 @@
+// Every combination of one item from each of `sets`, built one at a time so they don't all have to be in
+// memory. The first set varies slowest and the last fastest, each combination lists its items from the last
+// set to the first.
+#[derive(Clone)]
+pub struct Combinations<'a, T> {
+    sets: &'a [Vec<T>],
+    // index into each set of the next combination, `None` once all are done.
+    next: Option<Vec<usize>>,
+}
+
+pub fn combinations<T>(sets: &[Vec<T>]) -> Combinations<'_, T> {
+    // a set without items leaves no combinations at all.
+    let next = sets.iter().all(|set| !set.is_empty()).then(|| vec![0; sets.len()]);
+    Combinations { sets, next }
+}
+
+impl<T: Clone> Iterator for Combinations<'_, T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let indices = self.next.as_mut()?;
+        let combination = indices
+            .iter()
+            .zip(self.sets)
+            .rev()
+            .map(|(i, set)| set[*i].clone())
+            .collect();
+
+        // advance like an odometer, the last set turning fastest.
+        let mut done = true;
+        for (i, set) in indices.iter_mut().zip(self.sets).rev() {
+            *i += 1;
+            if *i < set.len() {
+                done = false;
+                break;
+            }
+            *i = 0;
+        }
+        if done {
+            self.next = None;
+        }
+        Some(combination)
+    }
+}
+
+// SplitMix64, a small seeded generator so a sample is the same on every run and platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `0..bound`, the modulo bias is negligible for the bounds used here.
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+// `n` items drawn uniformly from `items` with reservoir sampling, so only `n` are held at a time, in the
+// order they came in. Also returns how many items there were. The same `seed` draws the same sample.
+pub fn reservoir_sample<T>(items: impl Iterator<Item = T>, n: usize, seed: u64) -> (Vec<T>, u64) {
+    let mut rng = SplitMix64(seed);
+    let mut reservoir: Vec<(u64, T)> = Vec::with_capacity(n);
+    let mut seen = 0u64;
+    for item in items {
+        if reservoir.len() < n {
+            reservoir.push((seen, item));
+        } else {
+            let slot = rng.below(seen + 1);
+            if slot < n as u64 {
+                reservoir[slot as usize] = (seen, item);
+            }
+        }
+        seen += 1;
+    }
+    reservoir.sort_by_key(|(index, _)| *index);
+    (reservoir.into_iter().map(|(_, item)| item).collect(), seen)
+}
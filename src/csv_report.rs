@@ -0,0 +1,53 @@
+use crate::{analysis::AnalysisReport, interrupts::resource_name, task::TaskFilter};
+
+// Written instead of a response time that has no fixed point, an empty cell would read as 0.
+const UNBOUNDED: &str = "unbounded";
+
+// `cell` quoted if it has to be, resource names list their interrupts separated by commas.
+fn cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+fn row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells.iter().map(|c| cell(c)).collect();
+    cells.join(",") + "\n"
+}
+
+// The worst case result of each task matching `filter` as CSV with a header row, in the configured order.
+// Times are in cycles, `schedulable` is `true` or `false`.
+pub fn csv_report(report: &AnalysisReport, filter: &TaskFilter) -> String {
+    let header = "id,priority,deadline,wcet,blocking,interference,response_time,schedulable";
+    let mut csv = header.to_owned() + "\n";
+    for r in report.worst.0.iter().filter(|r| filter.matches(&r.task.id)) {
+        let schedulable = matches!(r.response_time, Some(rt) if rt <= r.task.deadline);
+        csv += &row(&[
+            r.task.id.to_owned(),
+            r.task.prio.to_string(),
+            r.task.deadline.to_string(),
+            r.wcet.to_string(),
+            r.blocking.to_string(),
+            r.interference.to_string(),
+            r.response_time.map_or(UNBOUNDED.to_owned(), |rt| rt.to_string()),
+            schedulable.to_string(),
+        ]);
+    }
+    csv
+}
+
+// The longest critical section on each resource over all paths as CSV with a header row, by resource id. The
+// ceiling is a preemption level, `none` if the analysis used no ceiling for the resource.
+pub fn resource_csv(report: &AnalysisReport) -> String {
+    let mut csv = "resource,ceiling,longest_section\n".to_owned();
+    for (resource, cycles) in &report.longest_sections {
+        csv += &row(&[
+            resource_name(resource, report.chip.table()),
+            report.ceilings.get(resource).map_or("none".to_owned(), |c| c.to_string()),
+            cycles.to_string(),
+        ]);
+    }
+    csv
+}
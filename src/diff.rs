@@ -0,0 +1,81 @@
+use std::{collections::BTreeSet, slice};
+
+use crate::{analysis::AnalysisReport, task::longest_sections_by_resource};
+
+// How the worst case of a task changed from one report to another, as `b - a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskDiff {
+    pub task: String,
+    pub wcet: i64,
+    // the WCET in `a`, what the change is relative to.
+    pub base_wcet: u32,
+    // `None` if the task has no response time in one of the reports.
+    pub response_time: Option<i64>,
+}
+
+impl TaskDiff {
+    // The WCET change in percent of the WCET in `a`, `None` if that is 0.
+    pub fn wcet_percent(&self) -> Option<f64> {
+        (self.base_wcet > 0).then(|| self.wcet as f64 * 100.0 / self.base_wcet as f64)
+    }
+}
+
+// The longest critical section of a task on a resource in two reports, from the worst trace of the task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionDiff {
+    pub task: String,
+    pub resource: String,
+    // `None` if the task doesn't lock the resource in that report.
+    pub a: Option<u32>,
+    pub b: Option<u32>,
+}
+
+impl SectionDiff {
+    // True if the section is longer in `b`, or only there.
+    pub fn grew(&self) -> bool {
+        self.b > self.a
+    }
+}
+
+// Per task difference of the worst results of two reports. Tasks are matched by name, tasks that are only in
+// one of the reports are left out.
+pub fn diff_reports(a: &AnalysisReport, b: &AnalysisReport) -> Vec<TaskDiff> {
+    a.worst
+        .0
+        .iter()
+        .filter_map(|ra| {
+            let rb = b.worst.0.iter().find(|rb| rb.task.id == ra.task.id)?;
+            Some(TaskDiff {
+                task: ra.task.id.to_owned(),
+                wcet: rb.wcet as i64 - ra.wcet as i64,
+                base_wcet: ra.wcet,
+                response_time: ra
+                    .response_time
+                    .zip(rb.response_time)
+                    .map(|(a, b)| b as i64 - a as i64),
+            })
+        })
+        .collect()
+}
+
+// Per task and resource difference of the longest critical sections in the worst traces of two reports, by
+// task in the order of `a` and then by resource id. Tasks are matched like in `diff_reports`, a resource
+// locked in only one of the reports is given with `None` in the other.
+pub fn diff_sections(a: &AnalysisReport, b: &AnalysisReport) -> Vec<SectionDiff> {
+    let mut diffs = vec![];
+    for ra in &a.worst.0 {
+        let Some(rb) = b.worst.0.iter().find(|rb| rb.task.id == ra.task.id) else {
+            continue;
+        };
+        let sa = longest_sections_by_resource(slice::from_ref(&ra.task));
+        let sb = longest_sections_by_resource(slice::from_ref(&rb.task));
+        let resources: BTreeSet<&String> = sa.keys().chain(sb.keys()).collect();
+        diffs.extend(resources.into_iter().map(|resource| SectionDiff {
+            task: ra.task.id.to_owned(),
+            resource: resource.to_owned(),
+            a: sa.get(resource).copied(),
+            b: sb.get(resource).copied(),
+        }));
+    }
+    diffs
+}
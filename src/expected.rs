@@ -0,0 +1,48 @@
+use crate::{analysis::AnalysisReport, task::InputTask};
+
+// The WCET of a task next to the one given in its `expected_wcet`.
+pub struct ExpectedWcet {
+    pub task: String,
+    // `None` if symex found no path.
+    pub wcet: Option<u32>,
+    pub expected: u32,
+    // how far the WCET may be from `expected`, in cycles.
+    pub tolerance: u32,
+}
+
+impl ExpectedWcet {
+    // Cycles the WCET is above (positive) or below the expected one.
+    pub fn delta(&self) -> Option<i64> {
+        self.wcet.map(|wcet| wcet as i64 - self.expected as i64)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.delta()
+            .is_some_and(|delta| delta.unsigned_abs() <= self.tolerance as u64)
+    }
+}
+
+// Compares the WCET of every task with an `expected_wcet` against it. `tolerance_percent` of the expected
+// WCET is allowed either way, rounded down to whole cycles, so 0 asks for the exact count.
+pub fn check_expected(
+    tasks: &[InputTask],
+    report: &AnalysisReport,
+    tolerance_percent: u32,
+) -> Vec<ExpectedWcet> {
+    tasks
+        .iter()
+        .filter_map(|task| {
+            let expected = task.expected_wcet?;
+            Some(ExpectedWcet {
+                task: task.name.to_owned(),
+                wcet: report
+                    .wcets
+                    .iter()
+                    .find(|(name, _)| *name == task.name)
+                    .map(|(_, wcet)| *wcet),
+                expected,
+                tolerance: (expected as u64 * tolerance_percent as u64 / 100) as u32,
+            })
+        })
+        .collect()
+}
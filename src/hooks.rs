@@ -0,0 +1,450 @@
+use std::{cell::RefCell, cmp::Ordering, str::FromStr};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use symex::{
+    general_assembly::{
+        instruction::CycleCount, project::PCHook, state::GAState, Result as GAResult,
+    },
+    smt::DExpr,
+};
+
+use crate::interrupts::mask_labels;
+
+// Re-exported for registering memory hooks, see `UserHooks`.
+pub use symex::general_assembly::project::MemoryHookAddress;
+
+// Label of a lap where the written value was symbolic, so the resource it refers to is unknown.
+pub const SYMBOLIC_LABEL: &str = "<symbolic>";
+// Label of a lap marking a call to an excluded function, it is not a critical section.
+pub const EXCLUDED_LABEL: &str = "<excluded>";
+// Label of a lap marking a read of the DWT cycle counter, it is not a critical section.
+pub const CYCCNT_LABEL: &str = "<cyccnt>";
+// Labels of the laps recording the stack start with this, followed by the stack pointer the task was entered
+// with and the lowest one reached so far (`<stack> 537133056 537132984`). They are not critical sections.
+pub const STACK_LABEL: &str = "<stack>";
+// Labels of the laps of a NVIC lock of an interrupt that is already masked start with this, and those of the
+// unlock matching it with `NESTED_UNLOCK_LABEL`, followed by the resource (`<nested lock> 8192`). Only the
+// outermost lock and unlock of an interrupt open and close its section, these count the ones nested inside.
+// They are not critical sections.
+pub const NESTED_LOCK_LABEL: &str = "<nested lock>";
+pub const NESTED_UNLOCK_LABEL: &str = "<nested unlock>";
+// Labels of the laps of an RP2040 spinlock start with this, followed by its number (`spinlock31`). Unlike an
+// interrupt mask a spinlock is the same resource on both cores.
+pub const SPINLOCK_LABEL: &str = "spinlock";
+
+// DWT CYCCNT, the cycle counter of the Cortex-M3/M4/M7 debug unit.
+const DWT_CYCCNT: u64 = 0xe0001004;
+
+// SPINLOCK0 in the RP2040 SIO, the 32 spinlocks are the words from here on.
+const SPINLOCK_BASE: u64 = 0xd0000100;
+
+// Addresses of ISER0/ICER0 in the System Control Space of every ARMv6-M/ARMv7-M core.
+pub const NVIC_ISER: u64 = 0xe000e100;
+pub const NVIC_ICER: u64 = 0xe000e180;
+
+// Where the NVIC set/clear enable registers of a chip are, see `InterruptTable::nvic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NvicAddresses {
+    pub iser: u64,
+    pub icer: u64,
+}
+
+impl Default for NvicAddresses {
+    fn default() -> NvicAddresses {
+        NvicAddresses {
+            iser: NVIC_ISER,
+            icer: NVIC_ICER,
+        }
+    }
+}
+
+pub type MemoryWriteHook = fn(state: &mut GAState, addr: u64, value: DExpr, bits: u32) -> GAResult<()>;
+pub type MemoryReadHook = fn(state: &mut GAState, addr: u64) -> GAResult<DExpr>;
+pub type RegisterWriteHook = fn(state: &mut GAState, value: DExpr) -> GAResult<()>;
+pub type RegisterReadHook = fn(state: &mut GAState) -> GAResult<DExpr>;
+// Run instead of the instruction at a symbol, it has to set `PC` itself (e.g. to `LR` to return).
+pub type PcHook = fn(state: &mut GAState) -> GAResult<()>;
+
+// Extra hooks registered by a library user, they run along with the built in critical section hooks. Hooks
+// are plain functions, so anything they measure has to go to a static or to the `GAState`:
+// - `cycle_count` is the number of cycles executed on the path so far, not counting the current instruction.
+// - `cycle_laps` are `(cycle, label)` pairs returned with the path. Every label is taken as a resource, the
+//   first lap of a label locks it and the next unlocks it, so only push laps to add critical sections.
+// - `current_instruction` is the instruction being executed, `get_register`/`set_register` access registers.
+#[derive(Default)]
+pub struct UserHooks {
+    pub pc_hooks: Vec<(Regex, PcHook)>,
+    pub register_read_hooks: Vec<(String, RegisterReadHook)>,
+    pub register_write_hooks: Vec<(String, RegisterWriteHook)>,
+    pub memory_read_hooks: Vec<(MemoryHookAddress, MemoryReadHook)>,
+    pub memory_write_hooks: Vec<(MemoryHookAddress, MemoryWriteHook)>,
+}
+
+impl UserHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pc_hooks.is_empty()
+            && self.register_read_hooks.is_empty()
+            && self.register_write_hooks.is_empty()
+            && self.memory_read_hooks.is_empty()
+            && self.memory_write_hooks.is_empty()
+    }
+}
+
+// How a task implements its critical sections, decides which hooks are registered for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsMechanism {
+    // masking individual interrupts through the NVIC ISER/ICER registers (RTIC on ARMv6-M).
+    #[default]
+    Nvic,
+    // globally disabling interrupts with `cpsid i`/`cpsie i`.
+    Primask,
+    // raising the BASEPRI register to the resource ceiling (RTIC on ARMv7-M and later).
+    Basepri,
+}
+
+impl FromStr for CsMechanism {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CsMechanism, String> {
+        match s {
+            "nvic" => Ok(CsMechanism::Nvic),
+            "primask" => Ok(CsMechanism::Primask),
+            "basepri" => Ok(CsMechanism::Basepri),
+            _ => Err(format!("expected `nvic`, `primask` or `basepri`, got `{s}`")),
+        }
+    }
+}
+
+// A word of memory with a known value when a task starts, e.g. a configuration register written once at boot.
+// Symex otherwise starts with all memory unconstrained and explores branches on it that can't happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Precondition {
+    pub address: u32,
+    pub value: u32,
+}
+
+thread_local! {
+    // the preconditions of the symex run on this thread, for `precondition_hook` to look up. Hooks are plain
+    // functions, they can't carry the values themselves.
+    static PRECONDITIONS: RefCell<Vec<Precondition>> = const { RefCell::new(vec![]) };
+}
+
+// Memory read hooks making every word read at the address of a precondition return its value. They replace
+// the read, so a task writing the address doesn't change what it reads back: only use them for memory that
+// stays the same while tasks run. The values are kept for the current thread until the next call.
+pub fn precondition_hooks(preconditions: &[Precondition]) -> Vec<(MemoryHookAddress, MemoryReadHook)> {
+    PRECONDITIONS.with(|p| *p.borrow_mut() = preconditions.to_vec());
+    preconditions
+        .iter()
+        .map(|p| (MemoryHookAddress::Single(p.address as u64), precondition_hook as MemoryReadHook))
+        .collect()
+}
+
+fn precondition_hook(state: &mut GAState, addr: u64) -> GAResult<DExpr> {
+    let value = PRECONDITIONS.with(|p| {
+        p.borrow().iter().find(|p| p.address as u64 == addr).map_or(0, |p| p.value)
+    });
+    Ok(state.ctx.from_u64(value as u64, 32))
+}
+
+// Hook recording each read of the DWT cycle counter as a lap, see `CYCCNT_LABEL`, to line the cycles of the
+// model up with measurements on the target at the same points. The read returns the cycles of the model
+// (wrapping at 32 bits like the counter), so code taking the difference of two reads sees the modelled time.
+pub fn cyccnt_hook() -> (MemoryHookAddress, MemoryReadHook) {
+    (MemoryHookAddress::Single(DWT_CYCCNT), cyccnt_read_hook)
+}
+
+fn cyccnt_read_hook(state: &mut GAState, _addr: u64) -> GAResult<DExpr> {
+    state.cycle_laps.push((state.cycle_count, CYCCNT_LABEL.to_owned()));
+    Ok(state.ctx.from_u64(state.cycle_count as u64 & u32::MAX as u64, 32))
+}
+
+// Hook on writes of SP recording each new lowest stack pointer of a path as a lap, see `STACK_LABEL`, so the
+// last such lap of a path gives how deep its stack got. A symbolic stack pointer can't be measured and is
+// skipped. The exception frame the core pushes before the handler runs isn't written by the task.
+pub fn stack_hook() -> (String, RegisterWriteHook) {
+    ("SP".to_owned(), stack_pointer_hook)
+}
+
+fn stack_pointer_hook(state: &mut GAState, value: DExpr) -> GAResult<()> {
+    let Some(new) = value.get_constant() else {
+        return Ok(());
+    };
+    // the lowest stack pointer so far is in the last stack lap, before the first one the hook runs before the
+    // write, so the register still holds the stack pointer the task was entered with.
+    let last = state.cycle_laps.iter().rev().find_map(|(_, label)| stack_lap(label));
+    let (entry, lowest) = match last {
+        Some(last) => last,
+        None => match state.get_register("SP".to_owned())?.get_constant() {
+            Some(entry) => (entry, entry),
+            None => return Ok(()),
+        },
+    };
+    if new < lowest {
+        state.cycle_laps.push((state.cycle_count, format!("{STACK_LABEL} {entry} {new}")));
+    }
+    Ok(())
+}
+
+// The entry and lowest stack pointer of a lap labeled with `STACK_LABEL`, `None` for any other lap.
+pub fn stack_lap(label: &str) -> Option<(u64, u64)> {
+    let (entry, lowest) = label.strip_prefix(STACK_LABEL)?.trim().split_once(' ')?;
+    Some((entry.parse().ok()?, lowest.parse().ok()?))
+}
+
+pub struct SpinlockHooks {
+    pub memory_read_hooks: Vec<(MemoryHookAddress, MemoryReadHook)>,
+    pub memory_write_hooks: Vec<(MemoryHookAddress, MemoryWriteHook)>,
+}
+
+// Hooks tracing the RP2040 spinlocks as resources, see `SPINLOCK_LABEL`: reading a spinlock claims it and
+// writing it releases it. Every claim is taken to succeed on the first read, the time spent spinning while the
+// other core holds the lock is added as blocking by the response time analysis.
+pub fn spinlock_hooks() -> SpinlockHooks {
+    let addresses = (0..32).map(|n| MemoryHookAddress::Single(SPINLOCK_BASE + 4 * n));
+    SpinlockHooks {
+        memory_read_hooks: addresses.clone().map(|a| (a, spinlock_claim_hook as MemoryReadHook)).collect(),
+        memory_write_hooks: addresses.map(|a| (a, spinlock_release_hook as MemoryWriteHook)).collect(),
+    }
+}
+
+fn spinlock_number(addr: u64) -> u64 {
+    (addr - SPINLOCK_BASE) / 4
+}
+
+fn spinlock_claim_hook(state: &mut GAState, addr: u64) -> GAResult<DExpr> {
+    let n = spinlock_number(addr);
+    state.cycle_laps.push((state.cycle_count, format!("{SPINLOCK_LABEL}{n}")));
+    // a successful claim reads as the bit of the lock.
+    Ok(state.ctx.from_u64(1 << n, 32))
+}
+
+fn spinlock_release_hook(state: &mut GAState, addr: u64, _value: DExpr, _bits: u32) -> GAResult<()> {
+    let label = format!("{SPINLOCK_LABEL}{}", spinlock_number(addr));
+    let cycle_count = state.cycle_count + current_instruction_cycles(state);
+    state.cycle_laps.push((cycle_count, label));
+    Ok(())
+}
+
+pub struct CriticalSectionHooks {
+    pub memory_write_hooks: Vec<(MemoryHookAddress, MemoryWriteHook)>,
+    pub register_write_hooks: Vec<(String, RegisterWriteHook)>,
+}
+
+// Hooks needed to trace the critical sections of a task using the given mechanism. NVIC masks are only
+// seen as word writes to ISER/ICER, which is all there is: the NVIC sits in the private peripheral bus, which
+// has no bit-band alias (and the Cortex-M0+ has no bit-banding at all).
+pub fn critical_section_hooks(mechanism: CsMechanism, nvic: NvicAddresses) -> CriticalSectionHooks {
+    match mechanism {
+        CsMechanism::Nvic => CriticalSectionHooks {
+            memory_write_hooks: vec![
+                (MemoryHookAddress::Single(nvic.iser), unlock_hook),
+                (MemoryHookAddress::Single(nvic.icer), lock_hook),
+            ],
+            register_write_hooks: vec![],
+        },
+        CsMechanism::Primask => CriticalSectionHooks {
+            memory_write_hooks: vec![],
+            register_write_hooks: vec![("PRIMASK".to_owned(), primask_hook)],
+        },
+        CsMechanism::Basepri => CriticalSectionHooks {
+            memory_write_hooks: vec![],
+            register_write_hooks: vec![("BASEPRI".to_owned(), basepri_hook)],
+        },
+    }
+}
+
+// Cycles of the instruction being executed. Cycles are added after the instruction is executed but hooks run
+// during execution, so a hook closing a critical section adds these to include the closing instruction.
+fn current_instruction_cycles(state: &mut GAState) -> usize {
+    match state.current_instruction.as_ref().unwrap().max_cycle {
+        CycleCount::Value(v) => v,
+        CycleCount::Function(f) => f(state),
+    }
+}
+
+// The resource of a lap labeled with `NESTED_LOCK_LABEL` or `NESTED_UNLOCK_LABEL`, with `true` for an
+// unlock. `None` for any other lap.
+pub fn nested_lap(label: &str) -> Option<(&str, bool)> {
+    if let Some(resource) = label.strip_prefix(NESTED_LOCK_LABEL) {
+        return Some((resource.trim_start(), false));
+    }
+    label.strip_prefix(NESTED_UNLOCK_LABEL).map(|resource| (resource.trim_start(), true))
+}
+
+// How deep `label` is locked after `laps`: 0 if it isn't, 1 in its section and one more for each nested
+// lock still held.
+fn lock_depth(laps: &[(usize, String)], label: &str) -> usize {
+    laps.iter().fold(0, |depth, (_, lap)| match nested_lap(lap) {
+        Some((resource, false)) if resource == label => depth + 1,
+        Some((resource, true)) if resource == label => depth.saturating_sub(1),
+        Some(_) => depth,
+        None if lap == label => usize::from(depth == 0),
+        None => depth,
+    })
+}
+
+// Adds the laps of a lock (or, with `unlock`, an unlock) writing `mask` at `cycle` to `laps`, one per
+// interrupt it sets, see `mask_labels`. A lock of an interrupt that is already masked, by an enclosing lock
+// whose mask overlaps this one, and the unlock matching it get nested laps, so the section of the interrupt
+// lasts until the outermost unlock. An unlock closes the sections of one write in the reverse order, so they
+// stay nested.
+pub fn push_mask_laps(laps: &mut Vec<(usize, String)>, cycle: usize, mask: u32, unlock: bool) {
+    let mut labels = mask_labels(mask);
+    if unlock {
+        labels.reverse();
+    }
+    for label in labels {
+        let lap = match (unlock, lock_depth(laps, &label)) {
+            (false, 0) | (true, 0 | 1) => label,
+            (false, _) => format!("{NESTED_LOCK_LABEL} {label}"),
+            (true, _) => format!("{NESTED_UNLOCK_LABEL} {label}"),
+        };
+        laps.push((cycle, lap));
+    }
+}
+
+// Laps for a mask written at `cycle`, see `push_mask_laps`. Values that aren't constant can't be attributed
+// to a resource.
+fn mask_laps(state: &mut GAState, cycle: usize, value: &DExpr, unlock: bool) {
+    match value.get_constant() {
+        Some(mask) => push_mask_laps(&mut state.cycle_laps, cycle, mask as u32, unlock),
+        None => state.cycle_laps.push((cycle, SYMBOLIC_LABEL.to_owned())),
+    }
+}
+
+// The label of a written mask, values that aren't constant can't be attributed to a resource.
+fn lap_label(value: &DExpr) -> String {
+    match value.get_constant() {
+        Some(v) => v.to_string(),
+        None => SYMBOLIC_LABEL.to_owned(),
+    }
+}
+
+// Hook to run when the interrupt mask is reset (looked).
+fn lock_hook(state: &mut GAState, _addr: u64, value: DExpr, _bits: u32) -> GAResult<()> {
+    // save the current cycle count to the laps vector.
+    mask_laps(state, state.cycle_count, &value, false);
+    Ok(())
+}
+
+// Hook to run when the interrupt mask is set (unlocked).
+fn unlock_hook(state: &mut GAState, _addr: u64, value: DExpr, _bits: u32) -> GAResult<()> {
+    // save the current cycle count to the laps vector.
+    // add the current instruction to the cycle count to compensate for cycles added after instruction completed
+    let cycle_count = state.cycle_count + current_instruction_cycles(state);
+    mask_laps(state, cycle_count, &value, true);
+    Ok(())
+}
+
+// Memory write hooks recording every write to ISER, labeled with the written mask, to see which interrupts
+// some code enables.
+pub fn interrupt_enable_hooks(nvic: NvicAddresses) -> Vec<(MemoryHookAddress, MemoryWriteHook)> {
+    vec![(MemoryHookAddress::Single(nvic.iser), enable_hook)]
+}
+
+fn enable_hook(state: &mut GAState, _addr: u64, value: DExpr, _bits: u32) -> GAResult<()> {
+    let val = lap_label(&value);
+    state.cycle_laps.push((state.cycle_count, val));
+    Ok(())
+}
+
+// Lap for a write of `new` to PRIMASK while it holds `old`, 1 disables all interrupts (lock) and 0 enables
+// them again (unlock). A write that keeps the bit (a `cpsid` with interrupts already disabled) leaves the
+// section as it is. An unlock is at `end`, after the writing instruction.
+pub fn primask_lap(old: u64, new: u64, cycle: usize, end: usize) -> Option<(usize, String)> {
+    match (old & 1, new & 1) {
+        (0, 1) => Some((cycle, "primask".to_owned())),
+        (1, 0) => Some((end, "primask".to_owned())),
+        _ => None,
+    }
+}
+
+// Hook to run when PRIMASK is written.
+fn primask_hook(state: &mut GAState, value: DExpr) -> GAResult<()> {
+    let new = match value.get_constant() {
+        Some(v) => v,
+        None => {
+            state.cycle_laps.push((state.cycle_count, SYMBOLIC_LABEL.to_owned()));
+            return Ok(());
+        }
+    };
+    // the hook runs before the write so the register still holds the old value.
+    let old = state
+        .get_register("PRIMASK".to_owned())?
+        .get_constant()
+        .unwrap_or(0);
+
+    let end = state.cycle_count + current_instruction_cycles(state);
+    if let Some(lap) = primask_lap(old, new, state.cycle_count, end) {
+        state.cycle_laps.push(lap);
+    }
+    Ok(())
+}
+
+// BASEPRI masks all interrupts with a priority value greater or equal to it, a lower non zero value masks
+// more and zero masks nothing. Turns a value into a level where a larger level masks more.
+fn basepri_level(basepri: u64) -> u64 {
+    match basepri & 0xff {
+        0 => 0,
+        v => 0x100 - v,
+    }
+}
+
+// Lap for a write of `new` to BASEPRI while it holds `old`. Raising it locks at the new ceiling at `cycle`,
+// lowering it releases the section that was opened at the old ceiling at `end`, after the writing
+// instruction. Both are labeled with the ceiling so the laps pair up, a write keeping the level adds none.
+pub fn basepri_lap(old: u64, new: u64, cycle: usize, end: usize) -> Option<(usize, String)> {
+    match basepri_level(new).cmp(&basepri_level(old)) {
+        Ordering::Greater => Some((cycle, format!("basepri {new}"))),
+        Ordering::Less => Some((end, format!("basepri {old}"))),
+        Ordering::Equal => None,
+    }
+}
+
+// Hook to run when BASEPRI is written, see `basepri_lap`.
+fn basepri_hook(state: &mut GAState, value: DExpr) -> GAResult<()> {
+    let new = match value.get_constant() {
+        Some(v) => v,
+        None => {
+            state.cycle_laps.push((state.cycle_count, SYMBOLIC_LABEL.to_owned()));
+            return Ok(());
+        }
+    };
+    // the hook runs before the write so the register still holds the old value.
+    let old = state
+        .get_register("BASEPRI".to_owned())?
+        .get_constant()
+        .unwrap_or(0);
+
+    let end = state.cycle_count + current_instruction_cycles(state);
+    if let Some(lap) = basepri_lap(old, new, state.cycle_count, end) {
+        state.cycle_laps.push(lap);
+    }
+    Ok(())
+}
+
+// Pc hooks returning straight to the caller when a function matching one of `exclude` is called, so the
+// cycles of its body are not counted.
+pub fn exclude_hooks(exclude: &[Regex]) -> Vec<(Regex, PCHook)> {
+    exclude
+        .iter()
+        .map(|re| (re.clone(), PCHook::Intrinsic(skip_call_hook)))
+        .collect()
+}
+
+// Hook to run on entry of an excluded function, returns to the link register without executing the body.
+// Whatever the function would have written (including its return value) is not modeled.
+fn skip_call_hook(state: &mut GAState) -> GAResult<()> {
+    state.cycle_laps.push((state.cycle_count, EXCLUDED_LABEL.to_owned()));
+    let lr = state.get_register("LR".to_owned())?;
+    // clear the thumb bit like `bx lr` would.
+    let return_address = match lr.get_constant() {
+        Some(v) => state.ctx.from_u64(v & !1, 32),
+        None => lr,
+    };
+    state.set_register("PC".to_owned(), return_address)
+}
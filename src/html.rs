@@ -0,0 +1,115 @@
+use srp::common::Trace;
+
+use crate::{
+    analysis::AnalysisReport,
+    interrupts::{resource_name, InterruptTable},
+    schedulability,
+    task::TaskFilter,
+};
+
+// Width of a trace drawing and height of one nesting level, in pixels.
+const SVG_WIDTH: f64 = 800.0;
+const ROW_HEIGHT: u32 = 20;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn depth(trace: &Trace) -> u32 {
+    1 + trace.inner.iter().map(depth).max().unwrap_or(0)
+}
+
+// The sections of `trace` as bars, nested sections on the row below the one they are in.
+fn bars(trace: &Trace, level: u32, origin: u32, scale: f64, table: &dyn InterruptTable, svg: &mut String) {
+    let name = match level {
+        0 => trace.id.to_owned(),
+        _ => resource_name(&trace.id, table),
+    };
+    let x = (trace.start - origin) as f64 * scale;
+    let width = ((trace.end - trace.start) as f64 * scale).max(1.0);
+    let fill = if level == 0 { "#9ecae1" } else { "#fdae6b" };
+    svg.push_str(&format!(
+        "<g><title>{} ({}..{}, {} cycles)</title>\
+         <rect x=\"{x:.1}\" y=\"{}\" width=\"{width:.1}\" height=\"{}\" fill=\"{fill}\" stroke=\"#555\"/>\
+         <text x=\"{:.1}\" y=\"{}\" font-size=\"12\">{}</text></g>\n",
+        escape(&name),
+        trace.start,
+        trace.end,
+        trace.end - trace.start,
+        level * ROW_HEIGHT,
+        ROW_HEIGHT - 2,
+        x + 3.0,
+        level * ROW_HEIGHT + 14,
+        escape(&name),
+    ));
+    for inner in &trace.inner {
+        bars(inner, level + 1, origin, scale, table, svg);
+    }
+}
+
+// `trace` as an inline SVG flame graph: the whole task on top and each critical section below the one it is
+// nested in, the width in proportion to the cycles. Sections are named after the interrupts of `table` they
+// mask.
+pub fn trace_svg(trace: &Trace, table: &dyn InterruptTable) -> String {
+    let scale = SVG_WIDTH / (trace.end - trace.start).max(1) as f64;
+    let height = depth(trace) * ROW_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SVG_WIDTH}\" height=\"{height}\" \
+         font-family=\"sans-serif\">\n"
+    );
+    bars(trace, 0, trace.start, scale, table, &mut svg);
+    svg + "</svg>\n"
+}
+
+// The report as a single HTML page without external assets, so it can be sent around as a file: a summary,
+// a table with the worst case of each task matching `filter` and the worst trace of each as a flame graph.
+pub fn html_report(report: &AnalysisReport, filter: &TaskFilter) -> String {
+    let summary = schedulability(report);
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>WCET analysis</title>\n<style>\n\
+         body { font-family: sans-serif; }\n\
+         table { border-collapse: collapse; }\n\
+         td, th { border: 1px solid #999; padding: 2px 8px; text-align: right; }\n\
+         </style>\n</head>\n<body>\n<h1>WCET analysis</h1>\n",
+    );
+    html += &format!(
+        "<p>{}, max utilization {:.3}.</p>\n",
+        if summary.schedulable { "Schedulable" } else { "Not schedulable" },
+        report.max_utilization
+    );
+    for warning in &report.warnings {
+        html += &format!("<p>Warning: {}</p>\n", escape(&warning.to_string()));
+    }
+
+    html += "<table>\n<tr><th>Task</th><th>Priority</th><th>WCET</th><th>Blocking</th><th>Interference</th>\
+             <th>Response time</th><th>Deadline</th><th>Schedulable</th></tr>\n";
+    let results: Vec<_> = report.worst.0.iter().filter(|r| filter.matches(&r.task.id)).collect();
+    for r in &results {
+        let schedulable = matches!(r.response_time, Some(rt) if rt <= r.task.deadline);
+        let cells = [
+            escape(&r.task.id),
+            r.task.prio.to_string(),
+            r.wcet.to_string(),
+            r.blocking.to_string(),
+            r.interference.to_string(),
+            r.response_time.map_or("unbounded".to_owned(), |rt| rt.to_string()),
+            r.task.deadline.to_string(),
+            if schedulable { "yes" } else { "no" }.to_owned(),
+        ];
+        html += "<tr>";
+        for cell in cells {
+            html += &format!("<td>{cell}</td>");
+        }
+        html += "</tr>\n";
+    }
+    html += "</table>\n";
+
+    for r in &results {
+        let svg = trace_svg(&r.task.trace, report.chip.table());
+        html += &format!("<h2>{}</h2>\n{svg}", escape(&r.task.id));
+    }
+    html + "</body>\n</html>\n"
+}
@@ -0,0 +1,313 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{hooks::NvicAddresses, task::InputTask};
+
+// The interrupt vector table of a chip, `idx` being the IRQ number (the bit of the interrupt in the NVIC
+// registers).
+pub trait InterruptTable {
+    fn name(&self, idx: u8) -> Option<&str>;
+
+    fn index(&self, name: &str) -> Option<u8> {
+        (0..=u8::MAX).find(|i| self.name(*i) == Some(name))
+    }
+
+    // Where the critical section and interrupt enable hooks watch for NVIC writes. The architectural
+    // addresses unless the chip puts them somewhere else (a core with the NVIC remapped).
+    fn nvic(&self) -> NvicAddresses {
+        NvicAddresses::default()
+    }
+}
+
+const RP2040_INTERRUPTS: [&str; 26] = [
+    "TIMER_IRQ_0",
+    "TIMER_IRQ_1",
+    "TIMER_IRQ_2",
+    "TIMER_IRQ_3",
+    "PWM_IRQ_WRAP",
+    "USBCTRL_IRQ",
+    "XIP_IRQ",
+    "PIO0_IRQ_0",
+    "PIO0_IRQ_1",
+    "PIO1_IRQ_0",
+    "PIO1_IRQ_1",
+    "DMA_IRQ_0",
+    "DMA_IRQ_1",
+    "IO_IRQ_BANK0",
+    "IO_IRQ_QSPI",
+    "SIO_IRQ_PROC0",
+    "SIO_IRQ_PROC1",
+    "CLOCKS_IRQ",
+    "SPI0_IRQ",
+    "SPI1_IRQ",
+    "UART0_IRQ",
+    "UART1_IRQ",
+    "ADC_IRQ_FIFO",
+    "I2C0_IRQ",
+    "I2C1_IRQ",
+    "RTC_IRQ",
+];
+
+// STM32F405/407/415/417, named as in the stm32f4 PAC.
+const STM32F4_INTERRUPTS: [&str; 82] = [
+    "WWDG",
+    "PVD",
+    "TAMP_STAMP",
+    "RTC_WKUP",
+    "FLASH",
+    "RCC",
+    "EXTI0",
+    "EXTI1",
+    "EXTI2",
+    "EXTI3",
+    "EXTI4",
+    "DMA1_STREAM0",
+    "DMA1_STREAM1",
+    "DMA1_STREAM2",
+    "DMA1_STREAM3",
+    "DMA1_STREAM4",
+    "DMA1_STREAM5",
+    "DMA1_STREAM6",
+    "ADC",
+    "CAN1_TX",
+    "CAN1_RX0",
+    "CAN1_RX1",
+    "CAN1_SCE",
+    "EXTI9_5",
+    "TIM1_BRK_TIM9",
+    "TIM1_UP_TIM10",
+    "TIM1_TRG_COM_TIM11",
+    "TIM1_CC",
+    "TIM2",
+    "TIM3",
+    "TIM4",
+    "I2C1_EV",
+    "I2C1_ER",
+    "I2C2_EV",
+    "I2C2_ER",
+    "SPI1",
+    "SPI2",
+    "USART1",
+    "USART2",
+    "USART3",
+    "EXTI15_10",
+    "RTC_ALARM",
+    "OTG_FS_WKUP",
+    "TIM8_BRK_TIM12",
+    "TIM8_UP_TIM13",
+    "TIM8_TRG_COM_TIM14",
+    "TIM8_CC",
+    "DMA1_STREAM7",
+    "FSMC",
+    "SDIO",
+    "TIM5",
+    "SPI3",
+    "UART4",
+    "UART5",
+    "TIM6_DAC",
+    "TIM7",
+    "DMA2_STREAM0",
+    "DMA2_STREAM1",
+    "DMA2_STREAM2",
+    "DMA2_STREAM3",
+    "DMA2_STREAM4",
+    "ETH",
+    "ETH_WKUP",
+    "CAN2_TX",
+    "CAN2_RX0",
+    "CAN2_RX1",
+    "CAN2_SCE",
+    "OTG_FS",
+    "DMA2_STREAM5",
+    "DMA2_STREAM6",
+    "DMA2_STREAM7",
+    "USART6",
+    "I2C3_EV",
+    "I2C3_ER",
+    "OTG_HS_EP1_OUT",
+    "OTG_HS_EP1_IN",
+    "OTG_HS_WKUP",
+    "OTG_HS",
+    "DCMI",
+    "CRYP",
+    "HASH_RNG",
+    "FPU",
+];
+
+pub struct Rp2040;
+
+impl InterruptTable for Rp2040 {
+    fn name(&self, idx: u8) -> Option<&str> {
+        RP2040_INTERRUPTS.get(idx as usize).copied()
+    }
+}
+
+pub struct Stm32f4;
+
+impl InterruptTable for Stm32f4 {
+    fn name(&self, idx: u8) -> Option<&str> {
+        STM32F4_INTERRUPTS.get(idx as usize).copied()
+    }
+}
+
+// The chips with an interrupt table, see `--chip`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Chip {
+    #[default]
+    Rp2040,
+    Stm32f4,
+}
+
+impl Chip {
+    // The name `--chip` takes.
+    pub fn name(self) -> &'static str {
+        match self {
+            Chip::Rp2040 => "rp2040",
+            Chip::Stm32f4 => "stm32f4",
+        }
+    }
+
+    pub fn table(self) -> &'static dyn InterruptTable {
+        match self {
+            Chip::Rp2040 => &Rp2040,
+            Chip::Stm32f4 => &Stm32f4,
+        }
+    }
+
+    // The CPU core of the chip, to check against the cycle model of symex.
+    pub fn core(self) -> &'static str {
+        match self {
+            Chip::Rp2040 => "Cortex-M0+",
+            Chip::Stm32f4 => "Cortex-M4",
+        }
+    }
+}
+
+impl FromStr for Chip {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Chip, String> {
+        match s {
+            "rp2040" => Ok(Chip::Rp2040),
+            "stm32f4" => Ok(Chip::Stm32f4),
+            _ => Err(format!("expected `rp2040` or `stm32f4`, got `{s}`")),
+        }
+    }
+}
+
+// Decodes a NVIC register value into the interrupts it refers to. Bits that don't map to an interrupt in
+// `table` (reserved bits) are returned separately so the caller can warn about them instead of crashing. Only
+// ISER0/ICER0 are traced, so interrupts from 32 up never show up in a mask.
+pub fn irq_from_bit_vector(bit_vector: u32, table: &dyn InterruptTable) -> (Vec<&str>, Vec<u8>) {
+    let mut ret = vec![];
+    let mut unknown = vec![];
+
+    for i in mask_bits(bit_vector) {
+        match table.name(i) {
+            Some(irq) => ret.push(irq),
+            None => unknown.push(i),
+        }
+    }
+
+    (ret, unknown)
+}
+
+// Every interrupt of `table` with its number, lowest first, as listed by `--list-interrupts`.
+pub fn interrupt_list(table: &dyn InterruptTable) -> Vec<(u8, &str)> {
+    (0..=u8::MAX).filter_map(|i| table.name(i).map(|irq| (i, irq))).collect()
+}
+
+// The interrupt numbers set in a NVIC register value, lowest first.
+pub fn mask_bits(bit_vector: u32) -> impl Iterator<Item = u8> {
+    (0..32).filter(move |i| bit_vector & 1 << i != 0)
+}
+
+// A readable name for a resource id. NVIC resources are labeled with the mask of their interrupt alone (see
+// `mask_labels`, a name given by the user can be a mask of several), these are named after the interrupts of
+// `table` they mask, any other id is returned as it is.
+pub fn resource_name(id: &str, table: &dyn InterruptTable) -> String {
+    let mask = match id.parse::<u32>() {
+        Ok(mask) => mask,
+        Err(_) => return id.to_owned(),
+    };
+
+    let (irqs, unknown) = irq_from_bit_vector(mask, table);
+    let mut names: Vec<String> = irqs.iter().map(|irq| irq.to_string()).collect();
+    names.extend(unknown.iter().map(|i| format!("IRQ{i}")));
+    format!("{mask:#x} ({})", names.join(", "))
+}
+
+// The resource ids of a mask written to ISER/ICER, one per interrupt it sets, so a write masking two
+// interrupts at once opens (or closes) a section on each and they are measured apart. An id is the mask of
+// its interrupt alone (`8192` for interrupt 13).
+pub fn mask_labels(mask: u32) -> Vec<String> {
+    mask_bits(mask).map(|i| (1u32 << i).to_string()).collect()
+}
+
+// The ids a resource is traced with from a name given by the user. Masks can be written in hex (`0x102000`)
+// and name the resource of each interrupt they set.
+pub fn resource_ids(name: &str) -> Vec<String> {
+    let mask = match name.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => name.parse().ok(),
+    };
+    match mask {
+        Some(mask) => mask_labels(mask),
+        None => vec![name.to_owned()],
+    }
+}
+
+// Names of all interrupts in `table`, as used in the task `interrupt` field.
+pub fn interrupt_names(table: &dyn InterruptTable) -> Vec<String> {
+    (0..=u8::MAX).filter_map(|i| table.name(i)).map(str::to_owned).collect()
+}
+
+// Edit distance between two names (insertions, deletions and substitutions).
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// Checks that `name` is an interrupt in `table`, the error suggests the closest name for typos.
+pub fn check_interrupt(name: &str, table: &dyn InterruptTable) -> Result<(), String> {
+    let names = interrupt_names(table);
+    if names.iter().any(|n| n == name) {
+        return Ok(());
+    }
+
+    let closest = names
+        .iter()
+        .map(|n| (levenshtein(name, n), n))
+        .min()
+        .filter(|(distance, _)| *distance <= 3);
+    match closest {
+        Some((_, suggestion)) => Err(format!(
+            "unknown interrupt `{name}`, did you mean `{suggestion}`?"
+        )),
+        None => Err(format!(
+            "unknown interrupt `{name}`, see --list-interrupts for the interrupts of the target"
+        )),
+    }
+}
+
+// Every task whose interrupt isn't in `table` with the error of `check_interrupt`, in the order of `tasks`.
+// All of them are found at once, before symex runs on a name that isn't a handler. Background tasks have no
+// interrupt and are left out.
+pub fn check_interrupts(tasks: &[InputTask], table: &dyn InterruptTable) -> Vec<(String, String)> {
+    tasks
+        .iter()
+        .filter(|task| !task.background)
+        .filter_map(|task| check_interrupt(&task.interrupt, table).err().map(|e| (task.name.to_owned(), e)))
+        .collect()
+}
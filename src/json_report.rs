@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    analysis::AnalysisReport,
+    interrupts::resource_name,
+    schedulability,
+    task::{Activation, TaskFilter},
+};
+
+// The report as written by `--format json`. srp's result types don't implement `Serialize`, so the fields
+// are copied into these.
+#[derive(Serialize)]
+struct JsonReport {
+    schedulable: bool,
+    max_utilization: f64,
+    tasks: Vec<JsonTask>,
+    resources: Vec<JsonResource>,
+}
+
+#[derive(Serialize)]
+struct JsonTask {
+    id: String,
+    priority: u8,
+    deadline: u32,
+    // None when the response time analysis found no fixed point.
+    response_time: Option<u32>,
+    wcet: u32,
+    blocking: u32,
+    interference: u32,
+    // release jitter, included in the response time.
+    jitter: u32,
+    // released by events at least `inter_arrival` apart rather than periodically, analysed the same.
+    sporadic: bool,
+    schedulable: bool,
+    // the symex path the worst result is from, numbered longest first. None for a task without paths.
+    path: Option<usize>,
+    // deepest stack over all paths in bytes, without the exception frame. None for a task from a trace file.
+    stack: Option<u64>,
+    // labels from the task list, see `InputTask::tags`.
+    tags: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct JsonResource {
+    // named after the interrupts it masks, see `resource_name`.
+    resource: String,
+    // as a preemption level, None if the analysis used no ceiling for it.
+    ceiling: Option<u8>,
+    // the longest critical section on it over all paths, in cycles.
+    longest_section: u32,
+}
+
+// The worst case result of each task matching `filter` as a JSON object, in the configured order, with the
+// max utilization, whether every task is schedulable (not only the ones matching `filter`) and the longest
+// critical section on each resource. Keys always come in the same order.
+pub fn json_report(report: &AnalysisReport, filter: &TaskFilter, pretty: bool) -> String {
+    let json = JsonReport {
+        schedulable: schedulability(report).schedulable,
+        max_utilization: report.max_utilization,
+        tasks: report
+            .worst
+            .0
+            .iter()
+            .filter(|r| filter.matches(&r.task.id))
+            .map(|r| {
+                let input = report.manifest.tasks.iter().find(|t| t.name == r.task.id);
+                JsonTask {
+                    id: r.task.id.to_owned(),
+                    priority: r.task.prio,
+                    deadline: r.task.deadline,
+                    response_time: r.response_time,
+                    wcet: r.wcet,
+                    blocking: r.blocking,
+                    interference: r.interference,
+                    jitter: input.map_or(0, |t| t.jitter),
+                    sporadic: matches!(input.map(|t| t.activation), Some(Activation::Sporadic { .. })),
+                    schedulable: matches!(r.response_time, Some(rt) if rt <= r.task.deadline),
+                    path: report.worst_paths.iter().find(|(task, _)| *task == r.task.id).map(|(_, p)| *p),
+                    stack: report.max_stack.iter().find(|(task, _)| *task == r.task.id).map(|(_, s)| *s),
+                    tags: input.map_or(BTreeMap::new(), |t| t.tags.clone()),
+                }
+            })
+            .collect(),
+        resources: report
+            .longest_sections
+            .iter()
+            .map(|(resource, cycles)| JsonResource {
+                resource: resource_name(resource, report.chip.table()),
+                ceiling: report.ceilings.get(resource).copied(),
+                longest_section: *cycles,
+            })
+            .collect(),
+    };
+    if pretty {
+        serde_json::to_string_pretty(&json).unwrap()
+    } else {
+        serde_json::to_string(&json).unwrap()
+    }
+}
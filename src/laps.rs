@@ -0,0 +1,93 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use symex::elf_util::VisualPathResult;
+
+use crate::{
+    hooks::{NvicAddresses, Precondition},
+    task::InputTask,
+};
+
+// What the analysis uses of a symex path: its cycles and the laps the hooks recorded on it. Kept apart from
+// the symex result so it can be recorded and replayed without running symex again.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathLaps {
+    pub max_cycles: usize,
+    pub cycle_laps: Vec<(usize, String)>,
+}
+
+impl From<&VisualPathResult> for PathLaps {
+    fn from(result: &VisualPathResult) -> PathLaps {
+        PathLaps {
+            max_cycles: result.max_cycles,
+            cycle_laps: result.cycle_laps.clone(),
+        }
+    }
+}
+
+fn laps_file(dir: &Path, task: &str) -> PathBuf {
+    dir.join(format!("{task}.laps.json"))
+}
+
+// Writes the paths of `task` to `dir`, in the order they are numbered in.
+pub fn record_laps(dir: &Path, task: &str, paths: &[PathLaps]) -> io::Result<()> {
+    fs::write(laps_file(dir, task), serde_json::to_string(paths)?)
+}
+
+// Reads the paths of `task` written by `record_laps`. A laps file can also be written by hand, so the paths
+// are sorted longest first like the ones symex gives back.
+pub fn replay_laps(dir: &Path, task: &str) -> io::Result<Vec<PathLaps>> {
+    let mut paths: Vec<PathLaps> = serde_json::from_slice(&fs::read(laps_file(dir, task))?)?;
+    paths.sort_by(|a, b| {
+        b.max_cycles
+            .cmp(&a.max_cycles)
+            .then_with(|| a.cycle_laps.cmp(&b.cycle_laps))
+    });
+    Ok(paths)
+}
+
+// Key of the paths of `task` in a cache: a hash of everything symex depends on, the elf, the entry symbol,
+// the critical section mechanism, the NVIC addresses hooked, whether spinlocks are traced, the excludes, the
+// preconditions and the version of this crate (a new one can come with another cycle model). Changing the elf
+// gives another key, so a stale entry is never read.
+pub fn cache_key(
+    elf: &[u8],
+    task: &InputTask,
+    exclude: &[String],
+    nvic: NvicAddresses,
+    preconditions: &[Precondition],
+) -> String {
+    let mut hasher = Sha256::new();
+    for part in [env!("CARGO_PKG_VERSION").as_bytes(), task.entry_symbol().as_bytes()] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.update([task.cs_mechanism as u8, task.core.is_some() as u8]);
+    hasher.update(nvic.iser.to_le_bytes());
+    hasher.update(nvic.icer.to_le_bytes());
+    hasher.update((preconditions.len() as u64).to_le_bytes());
+    for p in preconditions {
+        hasher.update(p.address.to_le_bytes());
+        hasher.update(p.value.to_le_bytes());
+    }
+    for re in exclude {
+        hasher.update((re.len() as u64).to_le_bytes());
+        hasher.update(re.as_bytes());
+    }
+    hasher.update(elf);
+    format!("{:x}", hasher.finalize())
+}
+
+// The cached paths under `key`, `None` if there are none or they can't be read (run symex again then).
+pub fn load_cached(dir: &Path, key: &str) -> Option<Vec<PathLaps>> {
+    serde_json::from_slice(&fs::read(dir.join(format!("{key}.json"))).ok()?).ok()
+}
+
+pub fn store_cached(dir: &Path, key: &str, paths: &[PathLaps]) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(format!("{key}.json")), serde_json::to_string(paths)?)
+}
@@ -0,0 +1,45 @@
+// WCET and SRP schedulability analysis of RTIC applications, the entry point is `run_analysis`.
+
+pub mod analysis;
+pub mod baseline;
+pub mod breakdown;
+pub mod build;
+pub mod ceiling_check;
+pub mod chain;
+pub mod cli;
+pub mod combination_log;
+pub mod combinations;
+pub mod csv_report;
+pub mod diff;
+pub mod expected;
+pub mod hooks;
+pub mod html;
+pub mod interrupts;
+pub mod json_report;
+pub mod laps;
+pub mod lock_order;
+pub mod manifest;
+pub mod markdown;
+pub mod rta;
+pub mod saved_report;
+pub mod schedulability;
+pub mod self_test;
+pub mod task;
+pub mod task_list;
+pub mod timeline;
+pub mod trace;
+pub mod trace_file;
+pub mod units;
+pub mod vectors;
+pub mod watch;
+
+pub use analysis::{
+    analyze_tasks, find_worst, run_analysis, run_analysis_streaming, task_paths, worst_combinations,
+    worst_path, AnalysisConfig, AnalysisEvent, AnalysisReport,
+};
+pub use hooks::CsMechanism;
+pub use schedulability::{schedulability, Schedulability};
+pub use task::{
+    create_task, discovered_resources, global_max_lock, lock_fraction, Activation, InputTask, TaskFilter,
+};
+pub use trace::{make_trace, trace_eq, trace_key, TraceBuilder};
@@ -0,0 +1,55 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use srp::common::{Task, Trace};
+
+// Two tasks nesting the same two resources in opposite orders. SRP with ceilings can't deadlock on it, but the
+// inconsistency usually points at a mistake and would deadlock under plain mutexes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOrderHazard {
+    // `task_a` locks `resources.1` while holding `resources.0`.
+    pub task_a: String,
+    // `task_b` locks `resources.0` while holding `resources.1`.
+    pub task_b: String,
+    pub resources: (String, String),
+}
+
+// Every (outer, inner) pair of resources where inner is locked while outer is held.
+fn nesting_pairs(trace: &Trace, held: &mut Vec<String>, pairs: &mut BTreeSet<(String, String)>) {
+    for inner in &trace.inner {
+        for outer in held.iter() {
+            if *outer != inner.id {
+                pairs.insert((outer.to_owned(), inner.id.to_owned()));
+            }
+        }
+        held.push(inner.id.to_owned());
+        nesting_pairs(inner, held, pairs);
+        held.pop();
+    }
+}
+
+// Finds pairs of tasks nesting two resources in opposite orders. `tasks` can hold several paths of the same
+// task (with the same id), the nesting of all of them is combined.
+pub fn lock_order_hazards(tasks: &[Task]) -> Vec<LockOrderHazard> {
+    let mut orders: BTreeMap<&str, BTreeSet<(String, String)>> = BTreeMap::new();
+    for task in tasks {
+        nesting_pairs(&task.trace, &mut vec![], orders.entry(&task.id).or_default());
+    }
+
+    let orders: Vec<_> = orders.into_iter().collect();
+    let mut hazards = vec![];
+    for (i, (task_a, pairs_a)) in orders.iter().enumerate() {
+        for (task_b, pairs_b) in &orders[i + 1..] {
+            for (outer, inner) in pairs_a {
+                if pairs_b.contains(&(inner.to_owned(), outer.to_owned())) {
+                    hazards.push(LockOrderHazard {
+                        task_a: task_a.to_string(),
+                        task_b: task_b.to_string(),
+                        resources: (outer.to_owned(), inner.to_owned()),
+                    });
+                }
+            }
+        }
+    }
+    hazards
+}
@@ -1,11 +1,54 @@
-use symex::{
-    elf_util::VisualPathResult,
-    general_assembly::{project::MemoryHookAddress, state::GAState, Result as GAResult, RunConfig},
-    run_elf::run_elf,
-    smt::DExpr,
+use std::{
+    env, fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
 
-use srp::common::{Task, Trace, Tasks, TaskResult, TasksResult};
+use clap::{Parser, ValueEnum};
+use log::{debug, info, log_enabled, warn, Level, LevelFilter};
+use regex::Regex;
+use srp::common::{TaskResult, Trace};
+
+use easy_poc::{
+    analysis::{check_entry_symbols, list_vectors, wcet_only, AnalysisEvent, Confidence, Server, Warning},
+    baseline::{check_baseline, load_baseline},
+    build::{build_example, default_elf},
+    chain::Chain,
+    cli::{
+        ascii_bar, at_clock, cyccnt_line, elf_files, fit_priorities, histogram, is_binary_report, only_tasks,
+        parse_ceiling, parse_chain, parse_correlation, parse_fit_task, parse_period, parse_reservation,
+        parse_server, parse_sweep, parse_tag, parse_trace_file, resource_matrix, schedulability_exit_code,
+        set_periods, sort_results, utilization_lines, variant_line, warning_errors, FitTask, MatrixFormat,
+        PriorityFit, Sort,
+    },
+    csv_report::{csv_report, resource_csv},
+    diff::{diff_reports, diff_sections},
+    expected::check_expected,
+    hooks::{nested_lap, stack_lap, UserHooks, CYCCNT_LABEL, EXCLUDED_LABEL, SYMBOLIC_LABEL},
+    html::{html_report, trace_svg},
+    interrupts::{interrupt_list, resource_name, Chip, InterruptTable},
+    json_report::json_report,
+    laps::{record_laps, PathLaps},
+    lock_fraction,
+    markdown::markdown_report,
+    rta::{self, Reservation},
+    run_analysis_streaming,
+    saved_report::{load_report, load_report_bin, save_report, save_report_bin},
+    schedulability::{failures, schedulability},
+    self_test::{self_test, SELF_TEST_ELF},
+    task::{deadline_monotonic, preemption_levels, validate_priorities, Activation, EqualPriority},
+    task_list::{load_task_list, TaskList},
+    task_paths,
+    timeline::{chrome_trace, perfetto_trace},
+    trace_file::dump_trace,
+    units::{cycles_to_ns, format_ns, TimeUse, TimeValue},
+    vectors::VectorKind,
+    watch::wait_for_change,
+    worst_path, AnalysisConfig, AnalysisReport, CsMechanism, InputTask, TaskFilter, TraceBuilder,
+};
 
 // This example show how hooks can be used to get at which cycle a resource is locked and unlocked in a simple
 // RTIC application. To keep in mind is that cycles are added after the instruction is executed and the hook
@@ -18,330 +61,1441 @@ use srp::common::{Task, Trace, Tasks, TaskResult, TasksResult};
 // cd ..
 //
 // Then run the analysis by: cargo run -p wcet-analasis-examples --release --example wcet_resource_times
+//
+// Or do both in one step with: cargo run --release -- --build rtic_simple_resourse
 
-fn make_trace(start: usize, end: usize, laps: &[(usize, String)], id: String) -> Trace {
-    let mut inner = vec![];
-
-    let mut current = "";
-    let mut inner_start = 0;
-    let mut in_inner = false;
-    let mut start_i = 0;
-    for i in 0..laps.len() {
-        if !in_inner {
-            current = &laps[i].1;
-            inner_start = laps[i].0;
-            start_i = i;
-            in_inner = true
-        } else {
-            if current == &laps[i].1 {
-                inner.push(make_trace(
-                    inner_start,
-                    laps[i].0,
-                    &laps[(start_i + 1)..i],
-                    laps[i].1.to_owned(),
-                ));
-                in_inner = false;
-            }
+// Prints the interrupt table of the chip as `number: name`.
+fn list_interrupts(table: &dyn InterruptTable) {
+    for (i, irq) in interrupt_list(table) {
+        println!("{i}: {irq}");
+    }
+}
+
+// The handlers in `elf`, see `--list-vectors`. Handlers only aliasing `DefaultHandler` are marked, a task on
+// one of them has no code of its own to analyse.
+fn print_vectors(elf: &str, table: &dyn InterruptTable) {
+    let vectors = list_vectors(elf, table).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    for vector in vectors {
+        let number = match vector.kind {
+            VectorKind::Interrupt(i) => i.to_string(),
+            VectorKind::Exception(n) => format!("exception {n}"),
+        };
+        let default = if vector.default { " (DefaultHandler)" } else { "" };
+        println!("{number}: {} at {:#010x}{default}", vector.name, vector.address);
+    }
+}
+
+// Logs the progress of an analysis, the paths of each combination only at debug level.
+fn log_event(event: AnalysisEvent<'_>) {
+    match event {
+        AnalysisEvent::TaskAnalyzed { task, paths } => info!("task {task}: {paths} paths"),
+        AnalysisEvent::CombinationEvaluated { index, paths, .. } => {
+            let paths: Vec<String> = paths.iter().map(|(task, path)| format!("{task}: {path}")).collect();
+            debug!("list {index}: [{}]", paths.join(", "));
         }
+        AnalysisEvent::Done(_) => {}
     }
+}
 
-    Trace {
-        id,
-        start: start as u32,
-        end: end as u32,
-        inner,
+// `run_analysis`, exiting with the error when the configuration doesn't hold together or an input can't be
+// read.
+fn analyse(config: &AnalysisConfig) -> AnalysisReport {
+    run_analysis_streaming(config, log_event).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    })
+}
+
+// Exits with an error naming every task whose entry symbol isn't in the elf, or if the elf can't be read,
+// before symex fails on the first one with less to go on.
+fn report_missing_symbols(config: &AnalysisConfig) {
+    match check_entry_symbols(config) {
+        Ok(missing) if missing.is_empty() => {}
+        Ok(missing) => {
+            for (task, e) in missing {
+                eprintln!("task {task}: {e}");
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
     }
 }
 
-fn create_task(symex_result: &VisualPathResult, task: &InputTask) -> Task {
-    let trace = make_trace(0, symex_result.max_cycles, &symex_result.cycle_laps, task.name.to_owned());
-    Task { id: task.name.to_owned(), prio: task.priority as u8, deadline: task.deadline, inter_arrival: task.interarival, trace }
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PriorityAssignment {
+    /// The priorities of the task list.
+    Configured,
+    /// Also analyse the tasks with deadline monotonic priorities, shortest deadline highest.
+    Dm,
 }
 
-fn analyze_tasks(task: &InputTask, path: &str) -> Vec<VisualPathResult> {
-    // path to the elf file to analyse.
-    let path_to_elf_file = path;
-    // name of the task in the elf file (same as associated interrupt vector for HW tasks).
-    let function_name = &task.interrupt;
-
-    // Hook to run when the interrupt mask is reset (looked).
-    let lock_hook: fn(state: &mut GAState, addr: u64, value: DExpr, bits: u32) -> GAResult<()> =
-        |state, _addr, value, _bits| {
-            // save the current cycle count to the laps vector.
-            let val = value.get_constant().unwrap().to_string();
-            state.cycle_laps.push((state.cycle_count, val));
-            Ok(())
-        };
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// The human readable report.
+    Text,
+    /// The worst trace of each task as Chrome trace events.
+    Chrome,
+    /// Chrome trace events with a named track per task, for Perfetto.
+    Perfetto,
+    /// A Markdown table of the worst case of each task, for pull requests and design docs.
+    Md,
+    /// A self-contained HTML page with the table and a flame graph of the worst trace of each task.
+    Html,
+    /// The worst case of each task, the max utilization and whether the tasks are schedulable as JSON.
+    Json,
+    /// The worst case of each task as CSV, a row per task.
+    Csv,
+    /// The longest critical section and the ceiling of each resource as CSV, a row per resource.
+    CsvResources,
+}
 
-    // Hook to run when the interrupt mask is set (unlocked).
-    let unlock_hook: fn(state: &mut GAState, addr: u64, value: DExpr, bits: u32) -> GAResult<()> =
-        |state, _addr, value, _bits| {
-            // save the current cycle count to the laps vector.
-            let val = value.get_constant().unwrap().to_string();
-            let current_instruction_cycle_count =
-                match state.current_instruction.as_ref().unwrap().max_cycle {
-                    symex::general_assembly::instruction::CycleCount::Value(v) => v,
-                    symex::general_assembly::instruction::CycleCount::Function(f) => f(state),
-                };
-
-            // add the current instruction to the cycle count to compensate for cycles added after instruction completed
-            let cycle_count = state.cycle_count + current_instruction_cycle_count;
-            state.cycle_laps.push((cycle_count, val));
-            Ok(())
-        };
+#[derive(Parser)]
+#[command(about = "Simple WCET and SRP schedulability analysis of RTIC applications")]
+struct Args {
+    /// The elf to analyse, `$BIN_DIR/$BIN_NAME` (test_bin/rtic_full_example by default) if not given.
+    #[arg(long, value_name = "FILE", conflicts_with = "build")]
+    elf: Option<String>,
 
-    // create a run configuration with the hooks associated with the correct addresses.
-    let config = RunConfig {
-        pc_hooks: vec![],
-        register_read_hooks: vec![],
-        register_write_hooks: vec![],
-        memory_write_hooks: vec![
-            (MemoryHookAddress::Single(0xe000e100), unlock_hook),
-            (MemoryHookAddress::Single(0xe000e180), lock_hook),
-        ],
-        memory_read_hooks: vec![],
-        show_path_results: false,
-    };
+    /// TOML file with the task list, a `[[task]]` table for each task.
+    #[arg(long, visible_alias = "tasks", value_name = "FILE", default_value = "tasks.toml")]
+    task_list: String,
+
+    /// Print nothing when every task is schedulable, only exit with status 1 when one isn't. Warnings are
+    /// still printed with --warn-as-error.
+    #[arg(long)]
+    quiet: bool,
+
+    /// Log progress to stderr (-v) and the path ids of every analysed combination (-vv). Warnings are logged
+    /// without it, RUST_LOG overrides the level. -v also breaks down the interference of failing tasks.
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Stream the outcome of every path combination to FILE, one json object per line.
+    #[arg(long, value_name = "FILE")]
+    jsonl_out: Option<PathBuf>,
+
+    /// NVIC PRIGROUP value, task priorities are split into preemption priority and subpriority accordingly.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=7))]
+    prigroup: Option<u8>,
+
+    /// Chip whose interrupt table task interrupts and NVIC masks are looked up in: rp2040 or stm32f4.
+    #[arg(long, default_value = "rp2040")]
+    chip: Chip,
+
+    /// Print the interrupt names of the target (as used in the task `interrupt` field) and exit.
+    #[arg(long)]
+    list_interrupts: bool,
+
+    /// Print the interrupt and exception handlers defined in the elf with the interrupt names of --chip, and
+    /// exit without running symex.
+    #[arg(long)]
+    list_vectors: bool,
+
+    /// Also search the breakdown factor, how much all WCETs can grow before a deadline is missed.
+    #[arg(long)]
+    breakdown: bool,
+
+    /// Also search how many cycles each task's WCET can grow by on its own before a deadline is missed.
+    #[arg(long)]
+    sensitivity: bool,
+
+    /// Leave calls to functions matching REGEX out of the measured cycles, can be given several times.
+    #[arg(long, value_name = "REGEX")]
+    exclude: Vec<String>,
+
+    /// Only report tasks whose name matches PATTERN (a regex or a plain name), can be given several times.
+    /// All tasks are still analysed and interfere with each other.
+    #[arg(long = "task", value_name = "PATTERN")]
+    tasks: Vec<String>,
+
+    /// Only analyse the task named NAME, can be given several times. The other tasks are left out entirely,
+    /// so the response times miss their interference and blocking.
+    #[arg(long, value_name = "NAME")]
+    only: Vec<String>,
+
+    /// Only report the tasks tagged KEY=VALUE. Can be given several times, tasks need all the tags.
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tag: Vec<(String, String)>,
+
+    /// Cycles to save or restore a context, every preemption is charged twice this.
+    #[arg(long, value_name = "CYCLES", default_value_t = 0)]
+    context_switch_cost: u32,
+
+    /// Cycles from an interrupt being raised until its handler runs, charged to every job (15 on the
+    /// Cortex-M0+).
+    #[arg(long, value_name = "CYCLES", default_value_t = 0)]
+    interrupt_latency: u32,
+
+    /// Keep at most N paths per task (the longest), to bound the number of combinations. 1 only analyses the
+    /// worst path of each task.
+    #[arg(long, alias = "top-k-paths", value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_paths: Option<u64>,
+
+    /// Analyse only N combinations of paths, drawn at random, when there are more. The report is then the
+    /// worst of the sample and not a bound.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    sample_combinations: Option<u64>,
+
+    /// Seed for --sample-combinations, the same seed draws the same combinations.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Use PRIORITY as the ceiling of RESOURCE (the traced id or mask, e.g. 0x102000) instead of the highest
+    /// priority of the tasks locking it. Can be given several times.
+    #[arg(long, value_name = "RESOURCE=PRIORITY", value_parser = parse_ceiling)]
+    ceiling: Vec<(String, u32)>,
+
+    /// Use PERIOD as the inter arrival time of TASK (and DEADLINE as its deadline) instead of the task list,
+    /// in cycles or with a time unit (s, ms, us, ns) at --clock-hz. Can be given several times.
+    #[arg(long, value_name = "TASK=PERIOD[/DEADLINE]", value_parser = parse_period)]
+    set_period: Vec<(String, TimeValue, Option<TimeValue>)>,
+
+    /// Leave RESOURCE (the traced id or mask) out of the blocking, its critical sections count as ordinary
+    /// execution. Meant for resources only one task locks, a shared one gets a warning. Can be given several
+    /// times.
+    #[arg(long, value_name = "RESOURCE")]
+    ignore_resource: Vec<String>,
+
+    /// Only print the tasks that miss their deadline, one line each, and exit with an error if there are any.
+    #[arg(long)]
+    only_failures: bool,
+
+    /// What to print, the text report or the worst trace of each task as a timeline for chrome://tracing or
+    /// Perfetto.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Write the output of a --format other than text to FILE instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    out: Option<PathBuf>,
+
+    /// Order of the tasks in the text report.
+    #[arg(long, value_enum, default_value_t = Sort::Priority)]
+    sort: Sort,
+
+    /// Also analyse the tasks with priorities assigned by the given policy and report if that is schedulable,
+    /// to see if a better assignment exists. The configured priorities are still the ones reported first.
+    #[arg(long, value_enum, default_value_t = PriorityAssignment::Configured)]
+    assign_priorities: PriorityAssignment,
+
+    /// Indent json output, e.g. to commit it as a golden file. Compact by default.
+    #[arg(long)]
+    json_pretty: bool,
+
+    /// Write the worst trace of each task to DIR/<task>.json, to be loaded again with --trace-file.
+    #[arg(long, value_name = "DIR")]
+    dump_traces: Option<PathBuf>,
+
+    /// Write the whole report, the worst case traces included, to FILE as json, to be rendered again with
+    /// --load-report. A FILE ending in .bin is written in a versioned binary format instead.
+    #[arg(long, value_name = "FILE")]
+    save_report: Option<PathBuf>,
+
+    /// Render a report written by --save-report instead of running the analysis. The elf and tasks are taken
+    /// from the report, the task list isn't read.
+    #[arg(long, value_name = "FILE", conflicts_with_all = [
+        "task_list", "elf", "build", "chip", "only", "set_period", "assign_priorities", "per_task_timeout",
+        "clock_sweep", "fit_task", "watch", "elf_dir", "dump_worst", "plan",
+    ])]
+    load_report: Option<PathBuf>,
+
+    /// Draw the worst trace of each task to DIR/<task>.svg, the critical sections nested below the section
+    /// they are in.
+    #[arg(long, value_name = "DIR")]
+    svg: Option<PathBuf>,
+
+    /// Use the trace in FILE (written by --dump-traces) for TASK instead of running symex on it. Can be given
+    /// several times.
+    #[arg(long, value_name = "TASK=FILE", value_parser = parse_trace_file)]
+    trace_file: Vec<(String, PathBuf)>,
+
+    /// Write the paths symex finds for each task to DIR/<task>.laps.json, to be read again with
+    /// --replay-laps.
+    #[arg(long, value_name = "DIR")]
+    record_laps: Option<PathBuf>,
+
+    /// Read the paths of each task from DIR (written by --record-laps) instead of running symex, e.g. to try
+    /// other scheduling options quickly. The elf is only needed for --init-symbol.
+    #[arg(long, value_name = "DIR", conflicts_with = "record_laps")]
+    replay_laps: Option<PathBuf>,
+
+    /// Run symex on every task instead of reading the paths of unchanged tasks from .wcet-cache.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Run symex on each task in a process of its own and stop it after SECS seconds. A task that runs out of
+    /// time is reported as timed out, its WCET unknown, and the analysis goes on with the others, but never
+    /// reports the tasks schedulable.
+    #[arg(long, value_name = "SECS", conflicts_with_all = ["replay_laps", "watch", "elf_dir"])]
+    per_task_timeout: Option<u64>,
+
+    /// Run symex on TASK, write its paths to DIR/<task>.laps.json and exit, see --per-task-timeout.
+    #[arg(long, value_name = "TASK", hide = true, requires = "worker_dir")]
+    symex_worker: Option<String>,
+
+    #[arg(long, value_name = "DIR", hide = true)]
+    worker_dir: Option<PathBuf>,
+
+    /// Clock in Hz the deadlines and inter arrival times of the task list are counted in.
+    #[arg(long, value_name = "HZ", default_value_t = 125_000_000)]
+    clock_hz: u64,
+
+    /// Analyse the task list at every clock from START to END Hz in steps of STEP and print the lowest one
+    /// that is schedulable. The deadlines and inter arrival times keep their time at --clock-hz, the WCETs
+    /// keep their cycles.
+    #[arg(long, value_name = "START,END,STEP", value_parser = parse_sweep)]
+    clock_sweep: Option<(u64, u64, u64)>,
+
+    /// Analyse the task list with a new task NAME on INTERRUPT at every priority from 1 to one above the
+    /// highest configured, and print the lowest priority keeping every task schedulable. PERIOD is the inter
+    /// arrival time and DEADLINE the deadline (PERIOD if left out), as for --set-period. Priorities the
+    /// --equal-priority policy rejects are skipped.
+    #[arg(long, value_name = "NAME@INTERRUPT=PERIOD[/DEADLINE]", value_parser = parse_fit_task)]
+    fit_task: Option<FitTask>,
+
+    /// Symbol of the code enabling the task interrupts, to warn about tasks whose interrupt is never enabled.
+    #[arg(long, value_name = "SYMBOL")]
+    init_symbol: Option<String>,
+
+    /// Extra cycles the first job of each task pays after reset, for flash wait states and a cold XIP cache.
+    #[arg(long, value_name = "CYCLES", default_value_t = 0)]
+    cold_start_penalty: u32,
+
+    /// Paths of different tasks that only happen together, combinations with some of them but not all are not
+    /// analysed. Path indices are the ones in the jsonl output. Can be given several times.
+    #[arg(long, value_name = "TASK:PATH,TASK:PATH...", value_parser = parse_correlation)]
+    correlate: Vec<Vec<(String, usize)>>,
+
+    /// Analyse the application as running in a reservation of BUDGET cycles every PERIOD instead of on the
+    /// whole CPU.
+    #[arg(long, value_name = "BUDGET/PERIOD", value_parser = parse_reservation)]
+    reservation: Option<Reservation>,
+
+    /// Whether tasks on different interrupts may share a priority: `error` rejects it, `fifo` analyses them
+    /// as a cooperative group that doesn't preempt itself.
+    #[arg(long, default_value = "error")]
+    equal_priority: EqualPriority,
+
+    /// Exit with status 1 after the report if there was any warning, whether the tasks are schedulable or not.
+    #[arg(long)]
+    warn_as_error: bool,
+
+    /// Fail on the first critical section whose locked value isn't constant, naming the task and where it is.
+    #[arg(long)]
+    require_concrete_locks: bool,
+
+    /// Fail the analysis on problems that are otherwise only warnings.
+    #[arg(long)]
+    strict: bool,
+
+    /// Check that every trace is well formed (sections within their parent, siblings not overlapping) before
+    /// the scheduling analysis, and fail on one that isn't.
+    #[arg(long)]
+    verify: bool,
+
+    /// Warn when every task is schedulable but the max utilization is below this, as a WCET that low usually
+    /// means the measurement went wrong. 0 turns the check off.
+    #[arg(long, default_value_t = 0.001)]
+    utilization_floor: f64,
 
-    // run the symbolic execution
-    run_elf(path_to_elf_file, function_name, config).unwrap()
+    /// Print the report again every time the elf (or a trace file) changes, until interrupted.
+    #[arg(long)]
+    watch: bool,
+
+    /// Print every resource locked by a task, with the tasks locking it, and exit.
+    #[arg(long)]
+    list_resources: bool,
+
+    /// Check the configuration and print what would be analysed, the chip, the NVIC addresses hooked and
+    /// each task with its resolved timing and entry symbol, then exit without running symex.
+    #[arg(long)]
+    plan: bool,
+
+    /// Print how the cycles of the paths of each task spread, a text histogram per task, and exit.
+    #[arg(long)]
+    histogram: bool,
+
+    /// Print which task locks which resource as a table, tasks by priority and a last row with the ceiling
+    /// of each resource, and exit. `csv` prints it comma separated.
+    #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "text")]
+    resource_matrix: Option<MatrixFormat>,
+
+    /// Build EXAMPLE with `cargo build --release --example EXAMPLE` in --examples-dir and analyse it, instead
+    /// of the elf from BIN_DIR and BIN_NAME.
+    #[arg(long, value_name = "EXAMPLE")]
+    build: Option<String>,
+
+    /// The cargo project --build builds the example in.
+    #[arg(long, value_name = "DIR", default_value = "armv6-m-examples")]
+    examples_dir: PathBuf,
+
+    /// Analyse every file in DIR against the task list and print a table of which variants are schedulable.
+    #[arg(long, value_name = "DIR")]
+    elf_dir: Option<PathBuf>,
+
+    /// Only measure the WCET of SYMBOL and print its longest path, skipping the task list and the scheduling
+    /// analysis.
+    #[arg(long, value_name = "SYMBOL")]
+    wcet_only: Option<String>,
+
+    /// Print the events on the longest path of TASK (locks, unlocks, calls to excluded functions and cycle
+    /// counter reads) with their cycles, and exit.
+    #[arg(long, value_name = "TASK")]
+    dump_worst: Option<String>,
+
+    /// How the critical sections of --wcet-only are made: nvic (masking single interrupts, Cortex-M0+),
+    /// basepri (the priority ceiling in BASEPRI, Cortex-M3 and up) or primask. Tasks in the task list set
+    /// their own `cs_mechanism`.
+    #[arg(long, default_value = "nvic")]
+    cs_mechanism: CsMechanism,
+
+    /// Measure functions with known cycle counts in the bundled fixture and check the cycle model against
+    /// them, then exit.
+    #[arg(long)]
+    self_test: bool,
+
+    /// Check the WCET of every task with an `expected_wcet` against it, print the difference and exit with
+    /// status 1 if one is off by more than --expected-tolerance.
+    #[arg(long)]
+    check_expected: bool,
+
+    /// Percentage of the expected WCET a task may be off by with --check-expected.
+    #[arg(long, value_name = "PERCENT", default_value_t = 0)]
+    expected_tolerance: u32,
+
+    /// Compare the WCET and response time of every task against a report written with --format json, print
+    /// the difference and exit with status 1 if one grew by more than --tolerance.
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Percentage of the baseline value a WCET or response time may grow by with --baseline.
+    #[arg(long, value_name = "PERCENT", default_value_t = 0)]
+    tolerance: u32,
+
+    /// Analyse ELF too, e.g. a debug build next to a release build, and print how the WCET and response time
+    /// of every task and its longest critical section on each resource changed from the elf to ELF. Tasks
+    /// with an elf of their own are analysed on it both times.
+    #[arg(long, value_name = "ELF", conflicts_with_all = [
+        "load_report", "record_laps", "replay_laps", "per_task_timeout", "watch", "elf_dir",
+    ])]
+    compare: Option<String>,
+
+    /// Collapse the paths of each task into one worst case right away, to analyse large task sets with little
+    /// memory. Nesting of critical sections is lost.
+    #[arg(long)]
+    collapse_paths: bool,
+
+    /// Analyse every combination of paths, also those with a path dominated by another path of the same task
+    /// (one at most as long without a longer critical section). They can't change the result.
+    #[arg(long)]
+    exhaustive: bool,
+
+    /// Bound the end to end latency of the tasks NAME, each released by the one before it, with CYCLES
+    /// between one finishing and the next being released, and check it against DEADLINE cycles if given.
+    /// Can be given several times, on top of the `[[chain]]` tables of the task list.
+    #[arg(long, value_name = "NAME=TASK,TASK...[+CYCLES][/DEADLINE]", value_parser = parse_chain)]
+    chain: Vec<Chain>,
+
+    /// Add a polling server reserving BUDGET cycles every PERIOD, above every task unless a PRIORITY is given.
+    /// Can be given several times.
+    #[arg(long, value_name = "BUDGET/PERIOD[@PRIORITY]", value_parser = parse_server)]
+    server: Vec<(u32, u32, Option<u32>)>,
+}
+
+// Critical sections of a trace, indented by nesting depth.
+fn print_sections(trace: &Trace, depth: usize, table: &dyn InterruptTable) {
+    for inner in &trace.inner {
+        println!(
+            "{:indent$}{}: cycle {} to {} ({} cycles)",
+            "",
+            resource_name(&inner.id, table),
+            inner.start,
+            inner.end,
+            inner.end - inner.start,
+            indent = 2 * depth
+        );
+        print_sections(inner, depth + 1, table);
+    }
+}
+
+// What `--plan` prints, a line per item in the configured order and nothing per run, so plans can be diffed.
+fn print_plan(config: &AnalysisConfig) {
+    let table = config.chip.table();
+    let nvic = table.nvic();
+    println!("Elf: {}", config.elf);
+    println!("Chip: {} ({})", config.chip.name(), config.chip.core());
+    println!("NVIC hooks: ISER {:#010x}, ICER {:#010x}", nvic.iser, nvic.icer);
+    let levels = preemption_levels(&config.tasks, config.prigroup);
+    for (task, level) in config.tasks.iter().zip(levels) {
+        print!("Task {}: ", task.name);
+        match task.background {
+            true => print!("background, "),
+            false => match table.index(&task.interrupt) {
+                Some(irq) => print!("interrupt {} (IRQ {irq}), ", task.interrupt),
+                None => print!("interrupt {}, ", task.interrupt),
+            },
+        }
+        print!("priority {} (level {level}), deadline {}, ", task.priority, task.deadline);
+        match task.activation {
+            Activation::Periodic { period } => print!("inter arrival {period}, "),
+            Activation::Sporadic { min_interarrival } => {
+                print!("inter arrival {min_interarrival} (sporadic), ")
+            }
+        }
+        if task.jitter > 0 {
+            print!("jitter {}, ", task.jitter);
+        }
+        if let Some(core) = task.core {
+            print!("core {core}, ");
+        }
+        let mechanism = match task.cs_mechanism {
+            CsMechanism::Nvic => "nvic",
+            CsMechanism::Primask => "primask",
+            CsMechanism::Basepri => "basepri",
+        };
+        print!("{mechanism}, ");
+        match config.trace_files.iter().find(|(name, _)| *name == task.name) {
+            Some((_, file)) => println!("trace file {}", file.display()),
+            None => println!("symbol {} in {}", task.entry_symbol(), task.elf(&config.elf)),
+        }
+    }
+    for chain in &config.chains {
+        let tasks = chain.tasks.join(" -> ");
+        print!("Chain {}: {tasks}, {} cycles per hop", chain.name, chain.activation_delay);
+        match chain.deadline {
+            Some(deadline) => println!(", deadline {deadline}"),
+            None => println!(),
+        }
+    }
+}
+
+// Result of the analysis with assigned priorities, printed after the report with the configured ones.
+fn print_assignment(report: &AnalysisReport, tasks: &[InputTask], filter: &TaskFilter) {
+    let schedulability = schedulability(report);
+    let results: Vec<_> = schedulability.tasks.iter().filter(|t| filter.matches(&t.task)).collect();
+    let missing = results.iter().filter(|t| !t.schedulable).count();
+    println!();
+    if missing == 0 {
+        println!("Deadline monotonic priorities: schedulable");
+    } else {
+        println!("Deadline monotonic priorities: {missing} of {} tasks miss their deadline", results.len());
+    }
+    for task in tasks.iter().filter(|t| filter.matches(&t.name)) {
+        println!("  {}: priority {}", task.name, task.priority);
+    }
 }
 
-struct InputTask {
-    name: String,
-    interrupt: String,
-    priority: u32,
-    deadline: u32,
-    interarival: u32,
+// Reads the task list again for the next --watch round, with the --set-period, --only and --chain of the
+// command line applied as on the first read. Returns the overrides applied and the tasks left out, the config
+// is only changed when all of it succeeds.
+fn reload_tasks(
+    config: &mut AnalysisConfig,
+    path: &str,
+    clock_hz: u64,
+    set_period: &[(String, TimeValue, Option<TimeValue>)],
+    only: &[String],
+    chains: &[Chain],
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let task_list = load_task_list(path, config.chip.table(), clock_hz).map_err(|e| e.to_string())?;
+    let mut tasks = task_list.tasks;
+    let overrides = set_periods(&mut tasks, set_period, clock_hz)?;
+    let left_out = only_tasks(&mut tasks, only)?;
+    config.tasks = tasks;
+    config.preconditions = task_list.preconditions;
+    config.chains = task_list.chains.into_iter().chain(chains.iter().cloned()).collect();
+    Ok((overrides, left_out))
 }
 
-#[allow(non_camel_case_types)]
-#[derive(Debug)]
-enum RP2040Interrupts {
-    TIMER_IRQ_0,
-    TIMER_IRQ_1,
-    TIMER_IRQ_2,
-    TIMER_IRQ_3,
-    PWM_IRQ_WRAP,
-    USBCTRL_IRQ,
-    XIP_IRQ,
-    PIO0_IRQ_0,
-    PIO0_IRQ_1,
-    PIO1_IRQ_0,
-    PIO1_IRQ_1,
-    DMA_IRQ_0,
-    DMA_IRQ_1,
-    IO_IRQ_BANK0,
-    IO_IRQ_QSPI,
-    SIO_IRQ_PROC0,
-    SIO_IRQ_PROC1,
-    CLOCKS_IRQ,
-    SPI0_IRQ,
-    SPI1_IRQ,
-    UART0_IRQ,
-    UART1_IRQ,
-    ADC_IRQ_FIFO,
-    I2C0_IRQ,
-    I2C1_IRQ,
-    RTC_IRQ,
+// Runs the analysis at every clock of the sweep. Symex only runs at the first clock, the others replay its
+// laps (from --record-laps or a temporary directory). Prints one line per clock and the lowest schedulable one.
+fn clock_sweep(config: &mut AnalysisConfig, (start, end, step): (u64, u64, u64), clock_hz: u64) {
+    let tasks = config.tasks.clone();
+    let temporary = config.replay_laps.is_none() && config.record_laps.is_none();
+    if temporary {
+        let dir = env::temp_dir().join(format!("easy-poc-sweep-{}", std::process::id()));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("could not create a directory for the laps: {e}");
+            std::process::exit(1);
+        }
+        config.record_laps = Some(dir);
+    }
+
+    let mut lowest = None;
+    for hz in (start..=end).step_by(step as usize) {
+        config.tasks = match at_clock(&tasks, clock_hz, hz) {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                eprintln!("{e}");
+                if temporary {
+                    for dir in config.record_laps.iter().chain(&config.replay_laps) {
+                        let _ = fs::remove_dir_all(dir);
+                    }
+                }
+                std::process::exit(1);
+            }
+        };
+        let schedulability = schedulability(&analyse(config));
+        if let Some(dir) = config.record_laps.take() {
+            config.replay_laps = Some(dir);
+        }
+        if schedulability.schedulable {
+            println!("{hz} Hz: schedulable");
+            lowest.get_or_insert(hz);
+        } else {
+            let missing = schedulability.tasks.iter().filter(|t| !t.schedulable).count();
+            println!("{hz} Hz: {missing} of {} tasks miss their deadline", schedulability.tasks.len());
+        }
+    }
+
+    if temporary {
+        if let Some(dir) = &config.replay_laps {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+    match lowest {
+        Some(hz) => println!("Lowest schedulable clock: {hz} Hz"),
+        None => println!("Not schedulable at any clock of the sweep"),
+    }
 }
 
-impl TryFrom<u8> for RP2040Interrupts {
-    type Error = &'static str;
-
-    fn try_from(value: u8) -> Result<RP2040Interrupts, &'static str> {
-        match value {
-            0 => Ok(RP2040Interrupts::TIMER_IRQ_0),
-            1 => Ok(RP2040Interrupts::TIMER_IRQ_1),
-            2 => Ok(RP2040Interrupts::TIMER_IRQ_2),
-            3 => Ok(RP2040Interrupts::TIMER_IRQ_3),
-            4 => Ok(RP2040Interrupts::PWM_IRQ_WRAP),
-            5 => Ok(RP2040Interrupts::USBCTRL_IRQ),
-            6 => Ok(RP2040Interrupts::XIP_IRQ),
-            7 => Ok(RP2040Interrupts::PIO0_IRQ_0),
-            8 => Ok(RP2040Interrupts::PIO0_IRQ_1),
-            9 => Ok(RP2040Interrupts::PIO1_IRQ_0),
-            10 => Ok(RP2040Interrupts::PIO1_IRQ_1),
-            11 => Ok(RP2040Interrupts::DMA_IRQ_0),
-            12 => Ok(RP2040Interrupts::DMA_IRQ_1),
-            13 => Ok(RP2040Interrupts::IO_IRQ_BANK0),
-            14 => Ok(RP2040Interrupts::IO_IRQ_QSPI),
-            15 => Ok(RP2040Interrupts::SIO_IRQ_PROC0),
-            16 => Ok(RP2040Interrupts::SIO_IRQ_PROC1),
-            17 => Ok(RP2040Interrupts::CLOCKS_IRQ),
-            18 => Ok(RP2040Interrupts::SPI0_IRQ),
-            19 => Ok(RP2040Interrupts::SPI1_IRQ),
-            20 => Ok(RP2040Interrupts::UART0_IRQ),
-            21 => Ok(RP2040Interrupts::UART1_IRQ),
-            22 => Ok(RP2040Interrupts::ADC_IRQ_FIFO),
-            23 => Ok(RP2040Interrupts::I2C0_IRQ),
-            24 => Ok(RP2040Interrupts::I2C1_IRQ),
-            25 => Ok(RP2040Interrupts::RTC_IRQ),
-            _ => Err("Invalid"),
+// Prints the outcome of `fit` at every candidate priority, see `fit_priorities`, and the lowest schedulable
+// one.
+fn fit_task(config: &mut AnalysisConfig, fit: &FitTask, clock_hz: u64) -> Result<(), String> {
+    let fits = fit_priorities(config, fit, clock_hz)?;
+    for (priority, outcome) in &fits {
+        match outcome {
+            PriorityFit::Skipped(e) => println!("priority {priority}: skipped, {e}"),
+            PriorityFit::Schedulable => println!("priority {priority}: schedulable"),
+            PriorityFit::Missing { missing, total } => {
+                println!("priority {priority}: {missing} of {total} tasks miss their deadline")
+            }
         }
     }
+    match fits.iter().find(|(_, outcome)| *outcome == PriorityFit::Schedulable) {
+        Some((priority, _)) => println!("Lowest schedulable priority for {}: {priority}", fit.name),
+        None => println!("{} doesn't fit at any priority", fit.name),
+    }
+    Ok(())
 }
 
-fn irq_from_bit_vector(bit_vector: u32) -> Vec<RP2040Interrupts> {
-    let mut ret = vec![];
+// The laps of `path` in cycle order with the cycles since the one before, see `--dump-worst`. A lap of a
+// resource that isn't locked locks it and the next one unlocks it, as in `trace_from_laps`.
+fn print_path(task: &str, path: &PathLaps, table: &dyn InterruptTable) {
+    println!("Longest path of {task}: {} cycles", path.max_cycles);
+    let width = path.max_cycles.to_string().len();
+    let mut builder = TraceBuilder::new();
+    let mut previous = 0;
+    for (cycle, label) in &path.cycle_laps {
+        let event = match label.as_str() {
+            EXCLUDED_LABEL => "call to an excluded function".to_owned(),
+            CYCCNT_LABEL => "read of DWT CYCCNT".to_owned(),
+            SYMBOLIC_LABEL => "write of a symbolic mask".to_owned(),
+            _ if stack_lap(label).is_some() => {
+                let (entry, lowest) = stack_lap(label).unwrap();
+                format!("stack {} bytes deep", entry.saturating_sub(lowest))
+            }
+            _ if nested_lap(label).is_some() => {
+                let (resource, unlock) = nested_lap(label).unwrap();
+                let event = if unlock { "nested unlock" } else { "nested lock" };
+                format!("{event} of {}, the section stays open", resource_name(resource, table))
+            }
+            _ if builder.is_locked(label) => {
+                builder.push_unlock(*cycle, label);
+                format!("unlock {}", resource_name(label, table))
+            }
+            _ => {
+                builder.push_lock(*cycle, label);
+                format!("lock {}", resource_name(label, table))
+            }
+        };
+        println!("  {cycle:>width$} (+{}) {event}", cycle.saturating_sub(previous));
+        previous = *cycle;
+    }
+    println!("  {:>width$} (+{}) end", path.max_cycles, path.max_cycles.saturating_sub(previous));
+}
 
-    for i in 0..32 {
-        let mask = 1 << i;
-        if mask & bit_vector != 0 {
-            ret.push(i.try_into().expect("error"));
+// How each task matching `filter` changed from `a` (analysed on `elf_a`) to `b`, see `--compare`. Every
+// resource the task locks gets a line with its longest section in both, marked if it grew.
+fn print_comparison(a: &AnalysisReport, b: &AnalysisReport, elf_a: &str, elf_b: &str, filter: &TaskFilter) {
+    let cycles = |c: Option<u32>| c.map_or("none".to_owned(), |c| c.to_string());
+    let sections = diff_sections(a, b);
+    println!("Compare {elf_a} -> {elf_b}:");
+    for diff in diff_reports(a, b).iter().filter(|d| filter.matches(&d.task)) {
+        let after = diff.base_wcet as i64 + diff.wcet;
+        print!("Task: {}: WCET {} -> {after} cycles ({:+}", diff.task, diff.base_wcet, diff.wcet);
+        match diff.wcet_percent() {
+            Some(percent) => print!(", {percent:+.1}%)"),
+            None => print!(")"),
+        }
+        match diff.response_time {
+            Some(delta) => println!(", response time {delta:+} cycles"),
+            None => println!(", response time unbounded in one or both"),
+        }
+        for section in sections.iter().filter(|s| s.task == diff.task) {
+            let grew = if section.grew() { " [GREW]" } else { "" };
+            println!(
+                "  Resource {}: longest section {} -> {} cycles{grew}",
+                resource_name(&section.resource, a.chip.table()),
+                cycles(section.a),
+                cycles(section.b)
+            );
         }
     }
+}
 
-    ret
+// The histogram of the path cycles of each task matching `filter`, see `--histogram`.
+fn print_histogram(report: &AnalysisReport, filter: &TaskFilter) {
+    for stats in report.path_stats.iter().filter(|s| filter.matches(&s.task)) {
+        println!("Task: {} ({} paths)", stats.task, stats.paths);
+        for line in histogram(stats) {
+            println!("  {line}");
+        }
+    }
 }
 
-fn get_task_list() -> Vec<InputTask> {
-    let mut list = vec![];
-    list.push(InputTask {
-        name: "button_handler".to_owned(),
-        interrupt: "IO_IRQ_BANK0".to_owned(),
-        priority: 2,
-        deadline: 125000,
-        interarival: 125000,
-    });
-    list.push(InputTask {
-        name: "debounce_button".to_owned(),
-        interrupt: "TIMER_IRQ_1".to_owned(),
-        priority: 3,
-        deadline: 1230000,
-        interarival: 37500000,
-    });
-    list.push(InputTask {
-        name: "alarm0_handler".to_owned(),
-        interrupt: "TIMER_IRQ_0".to_owned(),
-        priority: 1,
-        interarival: 62500000,
-        deadline: 1250000,
-    });
-    list.push(InputTask {
-        name: "alarm2_handler".to_owned(),
-        interrupt: "TIMER_IRQ_2".to_owned(),
-        priority: 4,
-        interarival: 125000000,
-        deadline: 125000,
-    });
-    list
+// Runs symex on each task in a child process (`--symex-worker`) writing its paths to `dir`, and kills a child
+// still running after `timeout`. Returns the tasks that timed out, the paths of the others can be replayed
+// from `dir`. A child failing (e.g. on an unknown entry symbol) ends the run with its exit status.
+fn run_symex_workers(config: &AnalysisConfig, dir: &Path, timeout: Duration) -> Vec<String> {
+    let exe = env::current_exe().expect("could not find the path of this program");
+    let mut timed_out = vec![];
+    for task in &config.tasks {
+        if config.trace_files.iter().any(|(name, _)| *name == task.name) {
+            continue;
+        }
+        let mut child = Command::new(&exe)
+            .args(env::args_os().skip(1))
+            .arg("--symex-worker")
+            .arg(&task.name)
+            .arg("--worker-dir")
+            .arg(dir)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("could not start symex");
+        let start = Instant::now();
+        loop {
+            match child.try_wait().expect("could not wait for symex") {
+                Some(status) if status.success() => break,
+                Some(status) => std::process::exit(status.code().unwrap_or(1)),
+                None if start.elapsed() >= timeout => {
+                    // waited for as well, so no process is left behind.
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    timed_out.push(task.name.to_owned());
+                    break;
+                }
+                None => thread::sleep(Duration::from_millis(20)),
+            }
+        }
+    }
+    timed_out
 }
 
+// Prints the `--only-failures` report, returns true if all tasks are schedulable.
+fn print_failures(report: &AnalysisReport, filter: &TaskFilter) -> bool {
+    let (schedulable, lines) = failures(report, filter);
+    for line in lines {
+        println!("{line}");
+    }
+    schedulable
+}
 
-fn main() {
+// The human readable report.
+// `overrides` are the --set-period overrides that were applied, see `set_periods`. `left_out` are the tasks
+// --only left out of the analysis.
+fn print_report(
+    report: &AnalysisReport,
+    filter: &TaskFilter,
+    sort: Sort,
+    overrides: &[String],
+    left_out: &[String],
+    clock_hz: u64,
+) {
     println!("Simple WCET analasis");
 
-    let task_list = get_task_list();
-
-    let path_to_elf_file = "test_bin/rtic_full_example";
+    for applied in overrides {
+        println!("Override: {applied}");
+    }
+    if !left_out.is_empty() {
+        println!(
+            "Partial analysis: {} left out, the response times miss their interference and blocking.",
+            left_out.join(", ")
+        );
+    }
 
-    let mut tasks = vec![];
+    for warning in &report.warnings {
+        warn!("{warning}");
+    }
 
-    for task in &task_list {
-        let result = analyze_tasks(task, path_to_elf_file);
-        let mut tasks_of_task = vec![];
-        for r in result {
-            let t = create_task(&r, task);
-            tasks_of_task.push(t);
-        }
-        tasks.push(tasks_of_task);
+    for over in &report.wcet_over_deadline {
+        warn!(
+            "Task: {} has a WCET of {} cycles on its own, more than its deadline of {}. It can not be scheduled.",
+            over.task, over.wcet, over.deadline
+        );
     }
 
-    let mut expected = 1;
-    for t in &tasks {
-        expected *= t.len();
+    for (task, symbol) in &report.not_enabled {
+        println!("Task: {task} is left out of the schedule, `{symbol}` never enables its interrupt.");
+    }
+    for task in &report.timed_out {
+        println!("Task: {task} analysis timed out, WCET unknown (unsafe).");
     }
 
-    println!("expected: {}", expected);
-    
-    let list_to_test = get_all_sets(&tasks[..]);
+    for (task, calls) in &report.excluded_calls {
+        if *calls > 0 {
+            println!("Task: {task} skipped up to {calls} calls to excluded functions on a path.");
+        }
+    }
 
-    println!("gotten: {}", list_to_test.len());
+    for (task, reads) in &report.cyccnt_reads {
+        println!("{}", cyccnt_line(task, reads));
+    }
 
-    for (i, list) in list_to_test.iter().enumerate() {
-        print!("list {i}: [");
-        for item in list {
-            print!("{}, ", item.id);
+    for (task, stack) in &report.max_stack {
+        println!("Task: {task} uses up to {stack} bytes of stack.");
+    }
+    for (core, stack) in &report.stack_required {
+        match report.stack_required.len() {
+            1 => println!("Stack: {stack} bytes with every level preempted at its deepest."),
+            _ => println!("Stack of core {core}: {stack} bytes with every level preempted at its deepest."),
         }
-        println!("]")
     }
 
-    let mut list_of_task_results = vec![];
+    for stats in &report.path_stats {
+        print!(
+            "Task: {} explored {} paths, {} to {} cycles (mean {:.1})",
+            stats.task, stats.paths, stats.min_cycles, stats.max_cycles, stats.mean_cycles
+        );
+        println!("{}", if stats.incomplete { ", INCOMPLETE" } else { "" });
+    }
+
+    // in u128 as the product grows quickly with the number of tasks.
+    let expected = report
+        .path_counts
+        .iter()
+        .fold(1u128, |expected, (_, paths)| expected.saturating_mul(*paths as u128));
+
+    info!("expected: {expected} combinations");
 
-    let mut max_utilization: f32 = 0.0;
-    for tasks in list_to_test {
-        let tasks = Tasks(tasks);
-        let result = tasks.response_time();
-        max_utilization = max_utilization.max(tasks.total_utilization());
-        list_of_task_results.push(result);
+    let dominated: usize = report.dominated_paths.iter().map(|(_, paths)| paths).sum();
+    if dominated > 0 {
+        let left = report.path_counts.iter().fold(1u128, |left, (task, paths)| {
+            let dominated = report.dominated_paths.iter().find(|(t, _)| t == task).map_or(0, |(_, d)| *d);
+            left.saturating_mul((paths - dominated) as u128)
+        });
+        info!("Left out {dominated} dominated paths, {left} of {expected} combinations to analyse.");
+    }
+
+    info!("gotten: {} combinations", report.combination_count);
+    if let Some((analysed, total)) = report.sampled {
+        println!("Sampled {analysed} of {total} combinations at random, the result is not exhaustive.");
     }
 
     println!("-------------------------------------------------------------------------------------------");
     
-    println!("Max utilization: {}", max_utilization);
-    let worst_result = find_worst(list_of_task_results);
+    println!("Max utilization: {}", report.max_utilization);
+    for line in utilization_lines(report, filter) {
+        println!("{line}");
+    }
+    let background = |task: &str| report.manifest.tasks.iter().any(|t| t.name == task && t.background);
+    if report.manifest.tasks.iter().any(|t| t.background) {
+        let foreground: f64 =
+            report.utilizations.iter().filter(|(task, _)| !background(task)).map(|(_, u)| u).sum();
+        println!("Background bandwidth: {:.3}", 1.0 - foreground);
+    }
+    if !report.chain_latencies.is_empty() {
+        println!("End to end latency:");
+    }
+    for chain in &report.chain_latencies {
+        print!("  Chain {} ({}): ", chain.chain, chain.tasks.join(" -> "));
+        match chain.latency {
+            Some(latency) => print!("{latency} cycles"),
+            None => print!("unbounded"),
+        }
+        match chain.deadline {
+            Some(deadline) if chain.missed() => println!(", deadline {deadline} [MISSED]"),
+            Some(deadline) => println!(", deadline {deadline}"),
+            None => println!(),
+        }
+    }
+    if let Some((cycles, task, resource)) = &report.max_lock {
+        let resource = resource_name(resource, report.chip.table());
+        println!("Longest critical section: {cycles} cycles in {task} on {resource}");
+    }
+    for (resource, cycles) in &report.longest_sections {
+        let ceiling = report.ceilings.get(resource).map_or("-".to_owned(), |c| c.to_string());
+        let resource = resource_name(resource, report.chip.table());
+        println!("Resource {resource}: longest critical section {cycles} cycles, ceiling {ceiling}");
+    }
+    if let Some(factor) = report.breakdown {
+        println!(
+            "Breakdown factor: {factor:.3} (breakdown utilization: {:.3})",
+            factor * report.max_utilization
+        );
+    }
+    if let Some(sensitivity) = &report.sensitivity {
+        for (task, extra) in sensitivity {
+            match extra {
+                Some(extra) => println!("Sensitivity: {task} can grow by {extra} cycles"),
+                None => println!("Sensitivity: {task} not schedulable as it is"),
+            }
+        }
+    }
+    if let Some(tightest) = schedulability(report).tightest {
+        println!("Tightest task: {tightest}");
+    }
+    let mut worst_result: Vec<&TaskResult> = report.worst.0.iter().collect();
+    sort_results(&mut worst_result, sort);
+    let bars = io::stdout().is_terminal();
+    let time = |cycles: u32, time_use| match cycles_to_ns(cycles as u64, clock_hz, time_use) {
+        Ok(ns) => format_ns(ns),
+        Err(e) => e.to_string(),
+    };
 
-    for result in worst_result.0 {
-        print!("Task: {}, max response time: {}, deadline: {}, ", result.task.id, result.response_time.unwrap(), result.task.deadline);
-        if result.response_time.unwrap() <= result.task.deadline {
-            println!("[SUCCESS]");
-        } else {
-            println!("[FAIL]");
+    for result in worst_result.into_iter().filter(|r| filter.matches(&r.task.id)) {
+        // an unbounded response time is reported, not unwrapped, so the other tasks are still printed.
+        let response_time = match result.response_time {
+            Some(rt) => format!("{rt} ({})", time(rt, TimeUse::Demand)),
+            None => "UNSCHEDULABLE".to_owned(),
+        };
+        let input = report.manifest.tasks.iter().find(|t| t.name == result.task.id);
+        if input.is_some_and(|t| t.background) {
+            let budget = report.background_budgets.iter().find(|(task, _)| *task == result.task.id);
+            let budget = budget.map_or("-".to_owned(), |(_, b)| b.to_string());
+            println!(
+                "Task: {}, background, WCET: {}, budget: {budget} cycles every {} cycles [BACKGROUND]",
+                result.task.id, result.wcet, result.task.inter_arrival
+            );
+            continue;
+        }
+        let deadline = format!("{} ({})", result.task.deadline, time(result.task.deadline, TimeUse::Limit));
+        print!("Task: {}, max response time: {response_time}, deadline: {deadline}, ", result.task.id);
+        if let Some(jitter) = input.map(|t| t.jitter).filter(|j| *j > 0) {
+            print!("jitter: {jitter}, ");
+        }
+        if let Some(Activation::Sporadic { .. }) = input.map(|t| t.activation) {
+            print!("sporadic, ");
+        }
+        print!("locked: {:.0}%, ", lock_fraction(&result.task) * 100.0);
+        let preemptions = report.max_preemptions.iter().find(|(task, _)| *task == result.task.id);
+        if let Some((_, Some(preemptions))) = preemptions {
+            print!("preemptions: {preemptions}, ");
+        }
+        if let Some((_, path)) = report.worst_paths.iter().find(|(task, _)| *task == result.task.id) {
+            print!("path: {path}, ");
+        }
+        if let Some(confidence) = report.confidence.iter().find(|c| c.task == result.task.id) {
+            if confidence.confidence == Confidence::Reduced {
+                print!("confidence: reduced ({}), ", confidence.reasons.join("; "));
+            }
+        }
+        match result.response_time {
+            None => print!("[FAIL] (response time did not converge within {} iterations)", rta::MAX_ITERATIONS),
+            Some(rt) if rt <= result.task.deadline => print!("[SUCCESS]"),
+            Some(_) if report.wcet_over_deadline.iter().any(|o| o.task == result.task.id) => {
+                print!("[FAIL] (WCET exceeds deadline)")
+            }
+            Some(_) => print!("[FAIL] (interference and blocking)"),
+        }
+        // response time as a share of the deadline, only on a terminal to keep logs clean.
+        if bars {
+            let fraction = match result.response_time {
+                Some(rt) => rt as f32 / result.task.deadline.max(1) as f32,
+                None => f32::INFINITY,
+            };
+            print!(" {}", ascii_bar(fraction, 20));
+        }
+        println!();
+        // where the interference of a failing task comes from, largest share first, with -v.
+        let failed = !matches!(result.response_time, Some(rt) if rt <= result.task.deadline);
+        if failed && log_enabled!(Level::Info) {
+            let interference = report.interference.iter().find(|(task, _)| *task == result.task.id);
+            let mut interferers: Vec<_> = interference.map_or(vec![], |(_, i)| i.iter().collect());
+            interferers.sort_by(|a, b| b.cycles.cmp(&a.cycles).then_with(|| a.task.cmp(&b.task)));
+            for i in interferers {
+                println!("    interference from {}: {} jobs, {} cycles", i.task, i.jobs, i.cycles);
+            }
         }
     }
 
+    // over all tasks, --tasks only picks what is printed above.
+    let schedulability = schedulability(report);
+    let missed = schedulability.tasks.iter().filter(|t| !t.schedulable).count();
+    let mut reasons = vec![];
+    match missed {
+        0 => {}
+        1 => reasons.push("1 task misses its deadline".to_owned()),
+        _ => reasons.push(format!("{missed} tasks miss their deadline")),
+    }
+    match report.timed_out.len() {
+        0 => {}
+        1 => reasons.push("1 task timed out".to_owned()),
+        n => reasons.push(format!("{n} tasks timed out")),
+    }
+    match schedulability.missed_chains.len() {
+        0 => {}
+        1 => reasons.push("1 chain misses its deadline".to_owned()),
+        n => reasons.push(format!("{n} chains miss their deadline")),
+    }
+    match reasons.is_empty() {
+        true => println!("SCHEDULABLE"),
+        false => println!("NOT SCHEDULABLE ({})", reasons.join(", ")),
+    }
 }
 
-fn cheap_clone(input: &TaskResult) -> TaskResult {
-    TaskResult {
-        task: input.task.to_owned(),
-        response_time: input.response_time,
-        wcet: input.wcet,
-        blocking: input.blocking,
-        interference: input.interference,
+// Exits with status 1 if the paths of a task are incomplete, see `PathStats::incomplete`.
+fn check_incomplete(report: &AnalysisReport) {
+    let incomplete: Vec<&str> = report
+        .path_stats
+        .iter()
+        .filter(|s| s.incomplete)
+        .map(|s| s.task.as_str())
+        .collect();
+    if !incomplete.is_empty() {
+        eprintln!("error: incomplete paths for {}, the WCET may be too low", incomplete.join(", "));
+        std::process::exit(1);
     }
 }
 
-fn find_worst(list_of_task_results: Vec<TasksResult>) -> TasksResult {
+// With --warn-as-error, prints the warnings of the report and exits with status 1 if there are any.
+fn check_warnings(warnings: &[Warning], warn_as_error: bool) {
+    let errors = warning_errors(warnings, warn_as_error);
+    if !errors.is_empty() {
+        for error in errors {
+            eprintln!("{error}");
+        }
+        std::process::exit(1);
+    }
+}
 
-    let mut worst_by_response_time = vec![];
-    for i in 0..list_of_task_results[0].0.len() {
-        let mut current_worst: Option<TaskResult> = None;
-        for task in &list_of_task_results {
-            match &current_worst {
-                Some(v) => {
-                    if v.response_time < task.0[i].response_time {
-                        current_worst = Some(cheap_clone(&task.0[i]))
-                    }
-                },
-                None => {
-                    current_worst = Some(cheap_clone(&task.0[i]))
-                },
+// Exits on warnings under --warn-as-error, incomplete paths or a task missing its deadline, the same for
+// every output format.
+fn finish(report: &AnalysisReport, warn_as_error: bool) {
+    check_warnings(&report.warnings, warn_as_error);
+    check_incomplete(report);
+    let code = schedulability_exit_code(report);
+    if code != 0 {
+        std::process::exit(code);
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Warn,
+        (false, 1) => LevelFilter::Info,
+        (false, _) => LevelFilter::Debug,
+    };
+    env_logger::Builder::new().filter_level(level).parse_default_env().init();
+
+    if args.list_interrupts {
+        list_interrupts(args.chip.table());
+        return;
+    }
+
+    if args.self_test {
+        let results = self_test(SELF_TEST_ELF);
+        for result in &results {
+            let cycles = result.cycles.map_or("no paths".to_owned(), |c| format!("{c} cycles"));
+            let status = if result.passed() { "PASS" } else { "FAIL" };
+            println!("{}: {cycles} (expected {:?}) [{status}]", result.name, result.expected);
+        }
+        if !results.iter().all(|r| r.passed()) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let elf = match &args.build {
+        Some(example) => match build_example(&args.examples_dir, example) {
+            Ok(elf) => elf.to_string_lossy().into_owned(),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        None => args
+            .elf
+            .clone()
+            .unwrap_or_else(|| default_elf(env::var("BIN_DIR").ok(), env::var("BIN_NAME").ok())),
+    };
+
+    if args.list_vectors {
+        print_vectors(&elf, args.chip.table());
+        return;
+    }
+
+    if let Some(symbol) = &args.wcet_only {
+        let exclude: Vec<Regex> = args
+            .exclude
+            .iter()
+            .map(|re| Regex::new(re).expect("invalid exclude regex"))
+            .collect();
+        match wcet_only(&elf, symbol, args.cs_mechanism, &exclude, args.chip.table().nvic()) {
+            Ok(Some(task)) => {
+                println!("{symbol}: WCET {} cycles", task.trace.end - task.trace.start);
+                print_sections(&task.trace, 1, args.chip.table());
+            }
+            Ok(None) => println!("{symbol}: no paths found"),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
             }
         }
-        worst_by_response_time.push(current_worst.unwrap())
+        return;
     }
 
-    TasksResult(worst_by_response_time)
-}
+    let loaded = args.load_report.as_ref().map(|path| {
+        let loaded = match is_binary_report(path) {
+            true => load_report_bin(path),
+            false => load_report(path),
+        };
+        loaded.unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        })
+    });
+    let (elf, task_list) = match &loaded {
+        Some(report) => (
+            report.manifest.elf.to_owned(),
+            TaskList {
+                tasks: report.manifest.tasks.clone(),
+                preconditions: report.manifest.preconditions.clone(),
+                chains: vec![],
+            },
+        ),
+        None => {
+            let task_list = load_task_list(&args.task_list, args.chip.table(), args.clock_hz);
+            let task_list = task_list.unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(1);
+            });
+            (elf, task_list)
+        }
+    };
+    let mut config = AnalysisConfig {
+        elf,
+        tasks: task_list.tasks,
+        prigroup: args.prigroup,
+        jsonl_out: args.jsonl_out,
+        breakdown: args.breakdown,
+        sensitivity: args.sensitivity,
+        exclude: args.exclude,
+        context_switch_cost: args.context_switch_cost,
+        interrupt_latency: args.interrupt_latency,
+        max_paths: args.max_paths.map(|n| n as usize),
+        sample_combinations: args.sample_combinations.map(|n| n as usize),
+        seed: args.seed,
+        ceilings: args.ceiling,
+        ignored_resources: args.ignore_resource,
+        hooks: UserHooks::default(),
+        preconditions: task_list.preconditions,
+        collapse_paths: args.collapse_paths,
+        exhaustive: args.exhaustive,
+        trace_files: args.trace_file,
+        record_laps: args.record_laps,
+        replay_laps: args.replay_laps,
+        cache_dir: (!args.no_cache).then(|| PathBuf::from(".wcet-cache")),
+        init_symbol: args.init_symbol,
+        cold_start_penalty: args.cold_start_penalty,
+        correlations: args.correlate,
+        reservation: args.reservation,
+        equal_priority: args.equal_priority,
+        require_concrete_locks: args.require_concrete_locks,
+        strict: args.strict,
+        verify: args.verify,
+        utilization_floor: args.utilization_floor,
+        timed_out: vec![],
+        chip: loaded.as_ref().map_or(args.chip, |report| report.chip),
+        chains: task_list.chains.into_iter().chain(args.chain.iter().cloned()).collect(),
+        servers: args
+            .server
+            .iter()
+            .enumerate()
+            .map(|(i, (budget, period, priority))| Server {
+                name: format!("server{i}"),
+                budget: *budget,
+                period: *period,
+                priority: *priority,
+            })
+            .collect(),
+    };
+    let overrides = set_periods(&mut config.tasks, &args.set_period, args.clock_hz).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let left_out = only_tasks(&mut config.tasks, &args.only).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
 
-fn get_all_sets(tasks: &[Vec<Task>]) -> Vec<Vec<Task>> {
-    if tasks.len() == 1 {
-        let mut ret = vec![];
-        for t in &tasks[0] {
-            ret.push(vec![t.to_owned()])
+    if let (Some(task), Some(dir)) = (&args.symex_worker, &args.worker_dir) {
+        let paths = task_paths(&config, task).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        if let Err(e) = record_laps(dir, task, &paths.unwrap_or_default()) {
+            eprintln!("could not write the laps of task {task}: {e}");
+            std::process::exit(1);
         }
-        ret
-    } else {
-        let mut ret = vec![];
-        for t in &tasks[0] {
-            let r = get_all_sets(&tasks[1..]);
-            for mut ta in r {
-                ta.push(t.clone());
-                ret.push(ta)
+        return;
+    }
+
+    if args.elf_dir.is_none() && loaded.is_none() {
+        report_missing_symbols(&config);
+    }
+
+    if args.plan {
+        validate_priorities(&config.tasks, config.equal_priority).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        print_plan(&config);
+        return;
+    }
+
+    let filter = TaskFilter::new(&args.tasks).with_tags(&args.tag, &config.tasks);
+
+    if let Some(dir) = &args.elf_dir {
+        let elfs = elf_files(dir).expect("could not read the elf directory");
+        for elf in elfs {
+            config.elf = elf.to_string_lossy().into_owned();
+            let report = analyse(&config);
+            let name = elf.file_name().unwrap_or_default().to_string_lossy();
+            println!("{}", variant_line(&name, &schedulability(&report), &filter));
+        }
+        return;
+    }
+
+    if let Some(task) = &args.dump_worst {
+        let path = worst_path(&config, task).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        let Some(path) = path else {
+            eprintln!("no task named {task} with a path");
+            std::process::exit(1);
+        };
+        print_path(task, &path, config.chip.table());
+        return;
+    }
+
+    // the laps the workers write, removed again once the analysis is done with them unless they are recorded.
+    let mut worker_laps = None;
+    if let Some(secs) = args.per_task_timeout {
+        let dir = match config.record_laps.take() {
+            Some(dir) => dir,
+            None => {
+                let dir = env::temp_dir().join(format!("easy-poc-workers-{}", std::process::id()));
+                worker_laps = Some(dir.clone());
+                dir
+            }
+        };
+        fs::create_dir_all(&dir).expect("could not create a directory for the laps");
+        config.timed_out = run_symex_workers(&config, &dir, Duration::from_secs(secs));
+        config.replay_laps = Some(dir);
+    }
+    let remove_worker_laps = || {
+        if let Some(dir) = &worker_laps {
+            let _ = fs::remove_dir_all(dir);
+        }
+    };
+
+    if let Some(sweep) = args.clock_sweep {
+        clock_sweep(&mut config, sweep, args.clock_hz);
+        remove_worker_laps();
+        return;
+    }
+
+    if let Some(fit) = &args.fit_task {
+        let result = fit_task(&mut config, fit, args.clock_hz);
+        remove_worker_laps();
+        result.unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        return;
+    }
+
+    if args.watch {
+        let mut watched = vec![PathBuf::from(&config.elf), PathBuf::from(&args.task_list)];
+        watched.extend(config.trace_files.iter().map(|(_, file)| file.to_owned()));
+        let (mut filter, mut overrides, mut left_out) = (filter, overrides, left_out);
+        loop {
+            let report = analyse(&config);
+            print_report(&report, &filter, args.sort, &overrides, &left_out, args.clock_hz);
+            wait_for_change(&watched);
+            println!();
+            // a broken task list is reported and the last one that loaded is analysed again.
+            let (set_period, only, chains) = (&args.set_period, &args.only, &args.chain);
+            match reload_tasks(&mut config, &args.task_list, args.clock_hz, set_period, only, chains) {
+                Ok((applied, omitted)) => {
+                    filter = TaskFilter::new(&args.tasks).with_tags(&args.tag, &config.tasks);
+                    (overrides, left_out) = (applied, omitted);
+                }
+                Err(e) => eprintln!("{e}"),
             }
         }
-        ret
     }
+
+    let report = match loaded {
+        Some(report) => report,
+        None => analyse(&config),
+    };
+    // analysed right away, with the laps of the workers still there, and printed after the report.
+    let assignment = (args.assign_priorities == PriorityAssignment::Dm).then(|| {
+        let assigned = deadline_monotonic(&config.tasks, config.prigroup);
+        let configured = std::mem::replace(&mut config.tasks, assigned);
+        let report = analyse(&config);
+        (report, std::mem::replace(&mut config.tasks, configured))
+    });
+    remove_worker_laps();
+
+    if let Some(path) = &args.save_report {
+        let saved = match is_binary_report(path) {
+            true => save_report_bin(&report, path),
+            false => save_report(&report, path),
+        };
+        saved.expect("could not write the report");
+    }
+
+    if let Some(other) = &args.compare {
+        let elf = std::mem::replace(&mut config.elf, other.to_owned());
+        let other_report = analyse(&config);
+        print_comparison(&report, &other_report, &elf, other, &filter);
+        return;
+    }
+
+    if args.check_expected {
+        let results = check_expected(&config.tasks, &report, args.expected_tolerance);
+        for result in &results {
+            let status = if result.passed() { "PASS" } else { "FAIL" };
+            match (result.wcet, result.delta()) {
+                (Some(wcet), Some(delta)) => println!(
+                    "{}: WCET {wcet} cycles, expected {} ({delta:+}) [{status}]",
+                    result.task, result.expected
+                ),
+                _ => println!("{}: no paths, expected {} [{status}]", result.task, result.expected),
+            }
+        }
+        if !results.iter().all(|r| r.passed()) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = &args.baseline {
+        let baseline = load_baseline(path).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        let checks = check_baseline(&report, &baseline, args.tolerance);
+        let response_time = |rt: Option<u32>| rt.map_or("unbounded".to_owned(), |rt| rt.to_string());
+        for check in &checks {
+            let Some(before) = &check.baseline else {
+                println!("{}: WCET {} cycles, not in the baseline [NEW]", check.task, check.wcet);
+                continue;
+            };
+            let status = if check.regressed() { "FAIL" } else { "PASS" };
+            println!(
+                "{}: WCET {} cycles, baseline {} ({:+}), response time {}, baseline {} [{status}]",
+                check.task,
+                check.wcet,
+                before.wcet,
+                check.wcet as i64 - before.wcet as i64,
+                response_time(check.response_time),
+                response_time(before.response_time),
+            );
+        }
+        if checks.iter().any(|c| c.regressed()) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(format) = args.resource_matrix {
+        print!("{}", resource_matrix(&report, format));
+        return;
+    }
+
+    if args.histogram {
+        print_histogram(&report, &filter);
+        return;
+    }
+
+    if args.list_resources {
+        for (resource, users) in &report.resources {
+            let users: Vec<&str> = users.iter().map(|u| u.as_str()).collect();
+            println!("{}: {}", resource_name(resource, report.chip.table()), users.join(", "));
+        }
+        return;
+    }
+
+    if let Some(dir) = &args.dump_traces {
+        for result in &report.worst.0 {
+            let path = dir.join(format!("{}.json", result.task.id));
+            dump_trace(&result.task, &path).expect("could not write the trace file");
+        }
+    }
+
+    if let Some(dir) = &args.svg {
+        for result in &report.worst.0 {
+            let path = dir.join(format!("{}.svg", result.task.id));
+            fs::write(path, trace_svg(&result.task.trace, report.chip.table()))
+                .expect("could not write the svg file");
+        }
+    }
+
+    if args.only_failures {
+        let schedulable = print_failures(&report, &filter);
+        check_warnings(&report.warnings, args.warn_as_error);
+        check_incomplete(&report);
+        if !schedulable {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let output = match args.format {
+        Format::Text => None,
+        Format::Chrome => Some(format!(
+            "{}\n",
+            chrome_trace(&report.worst, report.chip.table(), args.json_pretty)
+        )),
+        Format::Perfetto => Some(format!(
+            "{}\n",
+            perfetto_trace(&report.worst, &config.elf, report.chip.table(), args.json_pretty)
+        )),
+        Format::Md => Some(markdown_report(&report, &filter)),
+        Format::Html => Some(html_report(&report, &filter)),
+        Format::Json => Some(format!("{}\n", json_report(&report, &filter, args.json_pretty))),
+        Format::Csv => Some(csv_report(&report, &filter)),
+        Format::CsvResources => Some(resource_csv(&report)),
+    };
+    if let Some(output) = output {
+        match &args.out {
+            Some(path) => fs::write(path, output).expect("could not write the output file"),
+            None => print!("{output}"),
+        }
+        finish(&report, args.warn_as_error);
+        return;
+    }
+
+    if args.quiet {
+        finish(&report, args.warn_as_error);
+        return;
+    }
+
+    print_report(&report, &filter, args.sort, &overrides, &left_out, args.clock_hz);
+    if let Some((report, tasks)) = &assignment {
+        print_assignment(report, tasks, &filter);
+    }
+    finish(&report, args.warn_as_error);
 }
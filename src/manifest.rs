@@ -0,0 +1,47 @@
+use std::{
+    fs, io,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{analysis::AnalysisConfig, hooks::Precondition, task::InputTask};
+
+// The inputs that produced a report, so a saved report can be tied to the exact binary and configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub crate_version: String,
+    pub elf: String,
+    pub elf_sha256: String,
+    pub target: String,
+    pub tasks: Vec<InputTask>,
+    pub preconditions: Vec<Precondition>,
+    // seconds since the unix epoch when the analysis was run.
+    pub timestamp: u64,
+}
+
+impl Manifest {
+    pub fn new(config: &AnalysisConfig) -> io::Result<Manifest> {
+        // without an elf, see `needs_elf`, its hash is left empty.
+        let elf_sha256 = match fs::read(&config.elf) {
+            Ok(elf) => format!("{:x}", Sha256::digest(&elf)),
+            Err(_) if !config.needs_elf() => String::new(),
+            Err(e) => return Err(e),
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Ok(Manifest {
+            crate_version: env!("CARGO_PKG_VERSION").to_owned(),
+            elf: config.elf.to_owned(),
+            elf_sha256,
+            target: config.chip.name().to_owned(),
+            tasks: config.tasks.clone(),
+            preconditions: config.preconditions.clone(),
+            timestamp,
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{analysis::AnalysisReport, task::TaskFilter};
+
+const HEADER: [&str; 8] = [
+    "Task",
+    "Priority",
+    "WCET",
+    "Blocking",
+    "Interference",
+    "Response time",
+    "Deadline",
+    "Schedulable",
+];
+
+// The worst case result of each task matching `filter` as a GitHub flavoured Markdown table, in the configured
+// order, followed by the max utilization. Columns are padded to line up in the source as well.
+pub fn markdown_report(report: &AnalysisReport, filter: &TaskFilter) -> String {
+    let rows: Vec<[String; 8]> = report
+        .worst
+        .0
+        .iter()
+        .filter(|r| filter.matches(&r.task.id))
+        .map(|r| {
+            let schedulable = matches!(r.response_time, Some(rt) if rt <= r.task.deadline);
+            [
+                r.task.id.to_owned(),
+                r.task.prio.to_string(),
+                r.wcet.to_string(),
+                r.blocking.to_string(),
+                r.interference.to_string(),
+                r.response_time.map_or("unbounded".to_owned(), |rt| rt.to_string()),
+                r.task.deadline.to_string(),
+                if schedulable { "✅" } else { "❌" }.to_owned(),
+            ]
+        })
+        .collect();
+
+    // in chars, the check marks are one char but several bytes.
+    let mut widths = HEADER.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let pad = |cell: &str, width: usize| format!("{cell}{}", " ".repeat(width - cell.chars().count()));
+
+    let mut table = line(HEADER.iter().zip(widths).map(|(h, w)| pad(h, w)).collect());
+    table += &line(widths.iter().map(|w| "-".repeat(*w)).collect());
+    for row in &rows {
+        table += &line(row.iter().zip(widths).map(|(cell, w)| pad(cell, w)).collect());
+    }
+    table + &format!("\nMax utilization: {:.3}\n", report.max_utilization)
+}
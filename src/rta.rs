@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use srp::common::{Task, TaskResult, Tasks, TasksResult, Trace};
+
+use crate::{hooks::SPINLOCK_LABEL, task::EqualPriority};
+
+// Fixed point iterations before the response time of a task is given up on. Each iteration that doesn't
+// converge adds at least one more job of a higher priority task, so a set needing this many is overloaded.
+pub const MAX_ITERATIONS: u32 = 1000;
+
+// Properties of the system the response time analysis takes into account besides the tasks.
+#[derive(Debug, Clone, Default)]
+pub struct RtaConfig {
+    // cycles to save or restore a context. Every preemption pays it twice, once entering the preempting
+    // task and once returning from it.
+    pub context_switch_cost: u32,
+    // cycles from an interrupt being raised until its handler runs (15 on the Cortex-M0+ with zero wait state
+    // memory). Every job pays it, the task's own job once and each preempting job on top of its WCET.
+    // Tail-chaining makes the entry of a back to back interrupt shorter, a bound can't count on it.
+    pub interrupt_latency: u32,
+    // ceilings to use instead of the derived ones, by resource id.
+    pub ceilings: HashMap<String, u8>,
+    // with `Fifo` the other tasks at the same level interfere like higher priority ones, otherwise one of
+    // them that started first blocks for its whole WCET, see `PreemptionModel::runs_first`.
+    pub equal_priority: EqualPriority,
+    // extra cycles the first job of every task pays after reset (flash wait states and a cold XIP cache on the
+    // RP2040). The task's own job and the first job of each task interfering with it (see
+    // `PreemptionModel::interferes`) pay it once, later jobs don't, so it raises response times but not the
+    // utilization.
+    pub cold_start_penalty: u32,
+    // the application only gets `budget` cycles of every `period` (a time reservation in a larger system),
+    // `None` for the whole CPU.
+    pub reservation: Option<Reservation>,
+    // bus contention allowance of each task by task id, see `InputTask::bus_contention`. Every job pays it on
+    // top of its WCET, so it raises the response time of the task and of every task it preempts.
+    pub bus_contention: HashMap<String, u32>,
+    // release jitter of each task by task id, see `InputTask::jitter`.
+    pub jitter: HashMap<String, u32>,
+    // core of each task by task id, see `InputTask::core`. Tasks that aren't in it are on core 0.
+    pub cores: HashMap<String, u8>,
+}
+
+// A periodic reservation. In the worst case the budget is given at the start of one period and at the end of
+// the next, so the application can get nothing for `2 * (period - budget)` cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reservation {
+    pub budget: u32,
+    pub period: u32,
+}
+
+impl Reservation {
+    // The least time in which the reservation is guaranteed to supply `demand` cycles, the inverse of its
+    // supply bound function: one blackout of `period - budget` before the first budget and one between each.
+    pub fn time_to_supply(&self, demand: u64) -> u64 {
+        if demand == 0 {
+            return 0;
+        }
+        let gap = (self.period - self.budget) as u64;
+        let budgets = demand.div_ceil(self.budget as u64);
+        demand.saturating_add((budgets + 1).saturating_mul(gap))
+    }
+}
+
+// How the fixed point iteration for the response time of a task went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Convergence {
+    pub iterations: u32,
+    // false if the iteration cap was hit (or the response time overflowed) before a fixed point was found.
+    pub converged: bool,
+}
+
+pub struct RtaResult {
+    pub results: TasksResult,
+    // convergence of each task, in the same order as `results`.
+    pub convergence: Vec<Convergence>,
+    // most jobs of other tasks that can preempt each task before it finishes, in the same order as `results`.
+    // `None` if the response time is unbounded.
+    pub preemptions: Vec<Option<u64>>,
+    // what each interfering task adds to the response time of each task, in the same order as `results`. From
+    // the last window of the iteration, the response time without the own jitter if it converged.
+    pub interferers: Vec<Vec<Interferer>>,
+}
+
+// The share of one interfering task in the interference of another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interferer {
+    pub task: String,
+    // jobs released during the response time, each preempts once.
+    pub jobs: u64,
+    // execution of those jobs, with their context switches and interrupt latency.
+    pub cycles: u64,
+}
+
+// Ceiling of each resource, the highest priority of the tasks locking it.
+pub fn resource_ceilings(tasks: &Tasks) -> HashMap<&str, u8> {
+    ceilings_of(tasks.0.iter())
+}
+
+fn ceilings_of<'a>(tasks: impl Iterator<Item = &'a Task>) -> HashMap<&'a str, u8> {
+    fn visit<'a>(trace: &'a Trace, prio: u8, ceilings: &mut HashMap<&'a str, u8>) {
+        for inner in &trace.inner {
+            let ceiling = ceilings.entry(&inner.id).or_insert(prio);
+            *ceiling = (*ceiling).max(prio);
+            visit(inner, prio, ceilings);
+        }
+    }
+
+    let mut ceilings = HashMap::new();
+    for task in tasks {
+        visit(&task.trace, task.prio, &mut ceilings);
+    }
+    ceilings
+}
+
+// Every critical section in `trace`, nested ones included.
+fn sections<'a>(trace: &'a Trace, sections: &mut Vec<&'a Trace>) {
+    for inner in &trace.inner {
+        sections.push(inner);
+        self::sections(inner, sections);
+    }
+}
+
+// Longest critical section in `trace` on a resource with a ceiling of at least `prio`.
+fn longest_section(trace: &Trace, prio: u8, ceilings: &HashMap<&str, u8>) -> u32 {
+    trace
+        .inner
+        .iter()
+        .map(|inner| {
+            let own = if ceilings[inner.id.as_str()] >= prio {
+                inner.end.saturating_sub(inner.start)
+            } else {
+                0
+            };
+            own.max(longest_section(inner, prio, ceilings))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn wcet(task: &Task) -> u32 {
+    task.trace.end.saturating_sub(task.trace.start)
+}
+
+// Cycles a job of `task` runs for including its bus contention allowance.
+fn demand(task: &Task, config: &RtaConfig) -> u64 {
+    wcet(task) as u64 + config.bus_contention.get(&task.id).copied().unwrap_or(0) as u64
+}
+
+// Total utilization of the set, in f64 as the cycle counts and periods are too large for the precision of
+// an f32. A task with an inter arrival time of 0 gives an infinite utilization.
+pub fn utilization(tasks: &Tasks) -> f64 {
+    tasks.0.iter().map(task_utilization).sum()
+}
+
+// Share of the processor `task` takes, its WCET over its inter arrival time.
+pub fn task_utilization(task: &Task) -> f64 {
+    wcet(task) as f64 / task.inter_arrival as f64
+}
+
+// Which tasks delay which in the response time analysis. PRIGROUP and tasks sharing a vector are already
+// folded into the preemption levels the tasks are created with (see `preemption_levels`), so a task preempts
+// every task on a lower level of its core. Tasks on the same level are the rule added here, see
+// `RtaConfig::equal_priority`. Each core of a dual-core chip schedules its own tasks, see `RtaConfig::cores`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreemptionModel<'a> {
+    equal_priority: EqualPriority,
+    cores: &'a HashMap<String, u8>,
+}
+
+impl<'a> PreemptionModel<'a> {
+    pub fn new(config: &'a RtaConfig) -> PreemptionModel<'a> {
+        PreemptionModel {
+            equal_priority: config.equal_priority,
+            cores: &config.cores,
+        }
+    }
+
+    pub fn core(&self, task: &Task) -> u8 {
+        self.cores.get(&task.id).copied().unwrap_or(0)
+    }
+
+    // True if `a` can start running while `b` is running.
+    pub fn can_preempt(&self, a: &Task, b: &Task) -> bool {
+        a.prio > b.prio && self.core(a) == self.core(b)
+    }
+
+    // True if jobs of `a` can run before `b` finishes once `b` is released: the tasks preempting it and, in a
+    // FIFO group, the other tasks on its level.
+    pub fn interferes(&self, a: &Task, b: &Task) -> bool {
+        self.can_preempt(a, b)
+            || (self.equal_priority == EqualPriority::Fifo
+                && a.prio == b.prio
+                && a.id != b.id
+                && self.core(a) == self.core(b))
+    }
+
+    // True if a job of `a` that started first runs to completion before `b` can start, without `b` being
+    // able to preempt it: another task on its level outside a FIFO group (sharing a vector, or differing in a
+    // subpriority below PRIGROUP). Only one such job can run first, so it is blocking, not interference.
+    pub fn runs_first(&self, a: &Task, b: &Task) -> bool {
+        self.equal_priority != EqualPriority::Fifo
+            && a.prio == b.prio
+            && a.id != b.id
+            && self.core(a) == self.core(b)
+    }
+
+    // The tasks of `tasks` interfering with `task`, see `interferes`.
+    pub fn interferers<'b>(
+        &'b self,
+        task: &'b Task,
+        tasks: &'b Tasks,
+    ) -> impl Iterator<Item = &'b Task> + 'b {
+        tasks.0.iter().filter(move |t| self.interferes(t, task))
+    }
+}
+
+// Under SRP a task is blocked at most once, by the longest critical section of a lower priority task on its
+// core on a resource with a ceiling of at least its priority, or by the longest job of a task on its level
+// that started first (see `PreemptionModel::runs_first`). `ceilings` are the ceilings on its core.
+fn blocking(task: &Task, tasks: &Tasks, model: &PreemptionModel, ceilings: &HashMap<&str, u8>) -> u32 {
+    let lower = tasks
+        .0
+        .iter()
+        .filter(|t| model.can_preempt(task, t))
+        .map(|t| longest_section(&t.trace, task.prio, ceilings));
+    let same_level = tasks.0.iter().filter(|t| model.runs_first(t, task)).map(wcet);
+    lower.chain(same_level).max().unwrap_or(0)
+}
+
+// Time `task` spins on spinlocks held by the other core: each of its sections on a spinlock can wait once for
+// the longest section on that spinlock of any task on another core. SRP doesn't bound this, the other core
+// doesn't see the ceilings, so it comes on top of the blocking on the own core. Interrupt masks, PRIMASK and
+// BASEPRI only act on their own core and are never waited for.
+fn remote_blocking(task: &Task, tasks: &Tasks, model: &PreemptionModel) -> u32 {
+    let mut own = vec![];
+    sections(&task.trace, &mut own);
+    let remote: Vec<&Task> = tasks.0.iter().filter(|t| model.core(t) != model.core(task)).collect();
+    own.iter()
+        .filter(|section| section.id.starts_with(SPINLOCK_LABEL))
+        .map(|section| {
+            let mut longest = 0;
+            for t in &remote {
+                let mut theirs = vec![];
+                sections(&t.trace, &mut theirs);
+                for s in theirs.iter().filter(|s| s.id == section.id) {
+                    longest = longest.max(s.end.saturating_sub(s.start));
+                }
+            }
+            longest
+        })
+        .fold(0, u32::saturating_add)
+}
+
+// Jobs of `task` released in a window of `window` cycles. A job released late by its jitter and the next one
+// on time are closer than the inter arrival time, so the jitter widens the window.
+fn jobs(task: &Task, config: &RtaConfig, window: u64) -> u64 {
+    let jitter = config.jitter.get(&task.id).copied().unwrap_or(0) as u64;
+    window.saturating_add(jitter).div_ceil(task.inter_arrival.max(1) as u64)
+}
+
+// Jobs of the interfering tasks released during `response_time`, each is one preemption.
+fn preempting_jobs(
+    task: &Task,
+    tasks: &Tasks,
+    config: &RtaConfig,
+    model: &PreemptionModel,
+    response_time: u64,
+) -> u64 {
+    model
+        .interferers(task, tasks)
+        .map(|t| jobs(t, config, response_time))
+        .fold(0, u64::saturating_add)
+}
+
+// Execution of the jobs of `interferer` released during `response_time`, including the context switches into
+// and out of each of them and their interrupt latency.
+fn interferer_cycles(interferer: &Task, config: &RtaConfig, response_time: u64) -> u64 {
+    let switch = 2 * config.context_switch_cost as u64 + config.interrupt_latency as u64;
+    jobs(interferer, config, response_time).saturating_mul(demand(interferer, config) + switch)
+}
+
+// Execution of higher priority tasks released during `response_time`, see `interferer_cycles`. In a FIFO
+// group the other tasks at the same level count as well.
+fn interference(
+    task: &Task,
+    tasks: &Tasks,
+    config: &RtaConfig,
+    model: &PreemptionModel,
+    response_time: u64,
+) -> u64 {
+    // saturating, a saturated sum is far past any u32 response time and ends the iteration.
+    model
+        .interferers(task, tasks)
+        .map(|t| interferer_cycles(t, config, response_time))
+        .fold(0, u64::saturating_add)
+}
+
+// Cycles a background task (on the lowest level of its core) gets in every window of its inter arrival time:
+// the window less what the jobs of the other tasks released in it can take, the time left when they are all
+// released at its start, see `interferer_cycles`. Its own WCET and blocking don't count, it is what the
+// background work can use.
+pub fn background_budget(task: &Task, tasks: &Tasks, config: &RtaConfig) -> u64 {
+    let model = PreemptionModel::new(config);
+    let window = task.inter_arrival as u64;
+    window.saturating_sub(interference(task, tasks, config, &model, window))
+}
+
+// `interference` by interfering task, in the order of `tasks`.
+fn interference_breakdown(
+    task: &Task,
+    tasks: &Tasks,
+    config: &RtaConfig,
+    model: &PreemptionModel,
+    response_time: u64,
+) -> Vec<Interferer> {
+    model
+        .interferers(task, tasks)
+        .map(|t| Interferer {
+            task: t.id.to_owned(),
+            jobs: jobs(t, config, response_time),
+            cycles: interferer_cycles(t, config, response_time),
+        })
+        .collect()
+}
+
+// Response time analysis of every task in the set, `R = C + B + L + I(R)` (C including the bus contention
+// allowance, L the interrupt latency, plus the cold start penalties if there are any) iterated to a fixed
+// point starting from `C + B + L`. Tasks of equal priority don't preempt each other, see
+// `RtaConfig::equal_priority`, outside a FIFO group one of them blocks the others. With a reservation the
+// response time is the time the reservation takes to supply that demand, the interference then includes the
+// time without supply. Releases have no offsets, so
+// the worst case of every task is at the synchronous critical instant: released together with all higher
+// priority tasks, right after the blocking task locked. With release jitter the iteration gives the time from
+// the release of a job, the jitter of the task is added on top so the response time counts from the start of
+// its period like the deadline. A task that doesn't converge within `MAX_ITERATIONS` has no response time and
+// is unschedulable.
+pub fn response_time(tasks: &Tasks, config: &RtaConfig) -> RtaResult {
+    let model = PreemptionModel::new(config);
+    // ceilings are per core, a task on the other core locking a resource doesn't raise it here.
+    let mut ceilings_by_core: HashMap<u8, HashMap<&str, u8>> = HashMap::new();
+    for task in &tasks.0 {
+        let core = model.core(task);
+        ceilings_by_core.entry(core).or_insert_with(|| {
+            let mut ceilings = ceilings_of(tasks.0.iter().filter(|t| model.core(t) == core));
+            for (id, ceiling) in &config.ceilings {
+                if let Some(derived) = ceilings.get_mut(id.as_str()) {
+                    *derived = *ceiling;
+                }
+            }
+            ceilings
+        });
+    }
+    let mut results = vec![];
+    let mut convergence = vec![];
+    let mut preemptions = vec![];
+    let mut interferers = vec![];
+
+    for task in &tasks.0 {
+        let wcet = wcet(task);
+        let blocking = blocking(task, tasks, &model, &ceilings_by_core[&model.core(task)])
+            .saturating_add(remote_blocking(task, tasks, &model));
+        // the first job of every interfering task, the FIFO group included, runs before the task finishes.
+        let interfering = model.interferers(task, tasks).count() as u64;
+        let cold_start = config.cold_start_penalty as u64 * (interfering + 1);
+        let base = demand(task, config) + blocking as u64 + config.interrupt_latency as u64 + cold_start;
+        let supply = |demand: u64| match config.reservation {
+            Some(reservation) => reservation.time_to_supply(demand),
+            None => demand,
+        };
+
+        let mut current = supply(base);
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < MAX_ITERATIONS && current <= u32::MAX as u64 {
+            iterations += 1;
+            let next = supply(base.saturating_add(interference(task, tasks, config, &model, current)));
+            if next == current {
+                converged = true;
+                break;
+            }
+            current = next;
+        }
+
+        let interference = current.saturating_sub(base).min(u32::MAX as u64) as u32;
+        let jitter = config.jitter.get(&task.id).copied().unwrap_or(0);
+        results.push(TaskResult {
+            task: task.clone(),
+            response_time: converged.then(|| (current as u32).checked_add(jitter)).flatten(),
+            wcet,
+            blocking,
+            interference,
+        });
+        convergence.push(Convergence {
+            iterations,
+            converged,
+        });
+        preemptions.push(converged.then(|| preempting_jobs(task, tasks, config, &model, current)));
+        interferers.push(interference_breakdown(task, tasks, config, &model, current));
+    }
+
+    RtaResult {
+        results: TasksResult(results),
+        convergence,
+        preemptions,
+        interferers,
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use srp::common::{Task, TaskResult};
+
+use crate::{analysis::AnalysisReport, trace_file::TraceFile};
+
+// A worst case result as stored in a saved report, srp doesn't derive serde for its types.
+#[derive(Serialize, Deserialize)]
+struct TaskResultFile {
+    id: String,
+    prio: u8,
+    deadline: u32,
+    inter_arrival: u32,
+    trace: TraceFile,
+    response_time: Option<u32>,
+    wcet: u32,
+    blocking: u32,
+    interference: u32,
+}
+
+impl TaskResultFile {
+    fn new(result: &TaskResult) -> TaskResultFile {
+        TaskResultFile {
+            id: result.task.id.to_owned(),
+            prio: result.task.prio,
+            deadline: result.task.deadline,
+            inter_arrival: result.task.inter_arrival,
+            trace: TraceFile::new(&result.task.trace),
+            response_time: result.response_time,
+            wcet: result.wcet,
+            blocking: result.blocking,
+            interference: result.interference,
+        }
+    }
+
+    fn into_result(self) -> TaskResult {
+        TaskResult {
+            task: Task {
+                id: self.id,
+                prio: self.prio,
+                deadline: self.deadline,
+                inter_arrival: self.inter_arrival,
+                trace: self.trace.into_trace(),
+            },
+            response_time: self.response_time,
+            wcet: self.wcet,
+            blocking: self.blocking,
+            interference: self.interference,
+        }
+    }
+}
+
+// `#[serde(with)]` for the `worst` results of a report.
+pub(crate) mod tasks_result {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use srp::common::TasksResult;
+
+    use super::TaskResultFile;
+
+    pub fn serialize<S: Serializer>(results: &TasksResult, serializer: S) -> Result<S::Ok, S::Error> {
+        let results: Vec<TaskResultFile> = results.0.iter().map(TaskResultFile::new).collect();
+        results.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TasksResult, D::Error> {
+        let results = Vec::<TaskResultFile>::deserialize(deserializer)?;
+        Ok(TasksResult(results.into_iter().map(TaskResultFile::into_result).collect()))
+    }
+}
+
+// Writes all of `report` to `path` as json, the worst case traces with their nested sections included, so a
+// run can be archived and rendered again with `load_report` without running symex.
+pub fn save_report(report: &AnalysisReport, path: &Path) -> io::Result<()> {
+    fs::write(path, serde_json::to_string(report)?)
+}
+
+// Reads a report written by `save_report`. A report saved by another version of this crate may not load.
+pub fn load_report(path: &Path) -> Result<AnalysisReport, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("could not read the report {}: {e}", path.display()))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+// Start of a report written by `save_report_bin`, followed by `BINARY_VERSION` as a little endian u32.
+const BINARY_MAGIC: &[u8; 8] = b"EPOCRPT\0";
+
+// Version of the binary report format. Bincode has no field names to notice a changed report type by, so
+// this is bumped with every change to the report types and a report of another version is rejected rather
+// than misread.
+pub const BINARY_VERSION: u32 = 1;
+
+// `save_report` in bincode, smaller and faster to load than json for large reports kept as a cache. The json
+// report stays the one for people and golden files.
+pub fn save_report_bin(report: &AnalysisReport, path: &Path) -> io::Result<()> {
+    let mut bytes = BINARY_MAGIC.to_vec();
+    bytes.extend(BINARY_VERSION.to_le_bytes());
+    bytes.extend(bincode::serialize(report).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+    fs::write(path, bytes)
+}
+
+// Reads a report written by `save_report_bin`, a file without the magic or of another `BINARY_VERSION` is an
+// error.
+pub fn load_report_bin(path: &Path) -> Result<AnalysisReport, String> {
+    let bytes = fs::read(path).map_err(|e| format!("could not read the report {}: {e}", path.display()))?;
+    let rest = bytes
+        .strip_prefix(BINARY_MAGIC)
+        .ok_or_else(|| format!("{} is not a binary report", path.display()))?;
+    let (version, report) = rest
+        .split_first_chunk::<4>()
+        .ok_or_else(|| format!("{} is not a binary report", path.display()))?;
+    let version = u32::from_le_bytes(*version);
+    if version != BINARY_VERSION {
+        return Err(format!(
+            "{} is a binary report of version {version}, this version reads {BINARY_VERSION}",
+            path.display()
+        ));
+    }
+    bincode::deserialize(report).map_err(|e| format!("{}: {e}", path.display()))
+}
@@ -0,0 +1,112 @@
+use crate::{
+    analysis::{AnalysisReport, WarningKind},
+    task::TaskFilter,
+};
+
+// Whether the worst case of one task meets its deadline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSchedulability {
+    pub task: String,
+    // `None` if the response time is unbounded.
+    pub response_time: Option<u32>,
+    pub deadline: u32,
+    pub schedulable: bool,
+}
+
+// The schedulability verdict of a report, for printing or exporting it without going through the results
+// again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedulability {
+    // true if every task is schedulable, none timed out (see `AnalysisConfig::timed_out`) and every chain
+    // meets its deadline.
+    pub schedulable: bool,
+    // in the same order as the worst results of the report.
+    pub tasks: Vec<TaskSchedulability>,
+    // the chains missing their deadline, see `ChainLatency::missed`.
+    pub missed_chains: Vec<String>,
+    // the task with the highest response time relative to its deadline, unbounded ones first. `None` without
+    // tasks.
+    pub tightest: Option<String>,
+    pub max_utilization: f64,
+    // the tasks are schedulable but the utilization is so low the WCETs are suspect, see
+    // `AnalysisConfig::utilization_floor`.
+    pub low_utilization: bool,
+}
+
+pub fn schedulability(report: &AnalysisReport) -> Schedulability {
+    let tasks: Vec<TaskSchedulability> = report
+        .worst
+        .0
+        .iter()
+        .map(|r| TaskSchedulability {
+            task: r.task.id.to_owned(),
+            response_time: r.response_time,
+            deadline: r.task.deadline,
+            schedulable: matches!(r.response_time, Some(rt) if rt <= r.task.deadline),
+        })
+        .collect();
+
+    let load = |t: &TaskSchedulability| match t.response_time {
+        Some(rt) => rt as f64 / t.deadline.max(1) as f64,
+        None => f64::INFINITY,
+    };
+    // the first of equally tight tasks, as `max_by` would pick the last.
+    let tightest = tasks
+        .iter()
+        .rev()
+        .max_by(|a, b| load(a).total_cmp(&load(b)))
+        .map(|t| t.task.to_owned());
+
+    let missed_chains: Vec<String> = report
+        .chain_latencies
+        .iter()
+        .filter(|c| c.missed())
+        .map(|c| c.chain.to_owned())
+        .collect();
+
+    Schedulability {
+        schedulable: tasks.iter().all(|t| t.schedulable)
+            && report.timed_out.is_empty()
+            && missed_chains.is_empty(),
+        tasks,
+        missed_chains,
+        tightest,
+        max_utilization: report.max_utilization,
+        low_utilization: report
+            .warnings
+            .iter()
+            .any(|w| w.kind == WarningKind::LowUtilization),
+    }
+}
+
+// The terse report of `--only-failures`: one line per task timing out, chain missing its deadline or task
+// (matching `filter`) missing its deadline, or `all N tasks schedulable` when nothing fails. The bool is
+// false if anything fails.
+pub fn failures(report: &AnalysisReport, filter: &TaskFilter) -> (bool, Vec<String>) {
+    let schedulability = schedulability(report);
+    let results: Vec<_> = schedulability.tasks.iter().filter(|t| filter.matches(&t.task)).collect();
+    let mut schedulable = report.timed_out.is_empty() && schedulability.missed_chains.is_empty();
+    let mut lines = vec![];
+    for task in &report.timed_out {
+        lines.push(format!("task {task} timed out, its WCET is unknown"));
+    }
+    for chain in report.chain_latencies.iter().filter(|c| c.missed()) {
+        match (chain.latency, chain.deadline) {
+            (Some(latency), Some(deadline)) => {
+                lines.push(format!("chain {} misses by {} cycles", chain.chain, latency - deadline as u64))
+            }
+            _ => lines.push(format!("chain {} has no bounded end to end latency", chain.chain)),
+        }
+    }
+    for result in results.iter().filter(|t| !t.schedulable) {
+        match result.response_time {
+            None => lines.push(format!("task {} has no bounded response time", result.task)),
+            Some(rt) => lines.push(format!("task {} misses by {} cycles", result.task, rt - result.deadline)),
+        }
+        schedulable = false;
+    }
+    if schedulable {
+        lines.push(format!("all {} tasks schedulable", results.len()));
+    }
+    (schedulable, lines)
+}
@@ -0,0 +1,69 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    analysis::wcet_only,
+    hooks::{CsMechanism, NvicAddresses},
+};
+
+// The elf the benchmarks are measured in, the functions are part of every RP2040 binary.
+pub const SELF_TEST_ELF: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test_bin/rtic_simple_resourse");
+
+// A function in `SELF_TEST_ELF` with a known cycle count for its longest path.
+pub struct Benchmark {
+    pub name: &'static str,
+    pub symbol: &'static str,
+    // counted by hand from the Cortex-M0+ instruction timings (loads and stores 2 cycles, taken branches 2,
+    // `bl` 3, `push`/`pop` 1 + registers with 2 more for a `pop` to PC), with a little slack.
+    pub expected: RangeInclusive<u32>,
+}
+
+// Functions depending on their arguments for the number of iterations (`__delay`, `__aeabi_memcpy`) have no
+// bounded WCET with unconstrained arguments, so they can't be benchmarks. memcpy also calls into the boot ROM,
+// which isn't in the elf.
+pub const BENCHMARKS: &[Benchmark] = &[
+    // `nop` and a return.
+    Benchmark {
+        name: "call overhead",
+        symbol: "__nop",
+        expected: 3..=4,
+    },
+    // the path releasing the spinlock and enabling interrupts, straight line with two stores and a call.
+    Benchmark {
+        name: "critical section release",
+        symbol: "_critical_section_1_0_release",
+        expected: 28..=32,
+    },
+    // the hardware divider, the longest path saves and restores a division in progress around its own.
+    Benchmark {
+        name: "divide",
+        symbol: "_rphal_unsigned_divmod",
+        expected: 52..=60,
+    },
+];
+
+pub struct BenchmarkResult {
+    pub name: &'static str,
+    // `None` if symex found no path.
+    pub cycles: Option<u32>,
+    pub expected: RangeInclusive<u32>,
+}
+
+impl BenchmarkResult {
+    pub fn passed(&self) -> bool {
+        self.cycles.is_some_and(|cycles| self.expected.contains(&cycles))
+    }
+}
+
+// Measures every benchmark in `elf`, to catch changes in the cycle model of symex.
+pub fn self_test(elf: &str) -> Vec<BenchmarkResult> {
+    BENCHMARKS
+        .iter()
+        .map(|benchmark| BenchmarkResult {
+            name: benchmark.name,
+            cycles: wcet_only(elf, benchmark.symbol, CsMechanism::Nvic, &[], NvicAddresses::default())
+                .unwrap_or_else(|e| panic!("{e}"))
+                .map(|task| task.trace.end - task.trace.start),
+            expected: benchmark.expected.clone(),
+        })
+        .collect()
+}
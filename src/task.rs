@@ -0,0 +1,505 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use srp::common::{Task, Trace};
+
+use crate::{
+    analysis::AnalysisError,
+    hooks::{nested_lap, stack_lap, CsMechanism, CYCCNT_LABEL, EXCLUDED_LABEL},
+    laps::PathLaps,
+    trace::{make_trace, overflowing_cycle},
+};
+
+// A task of the application as configured by the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputTask {
+    pub name: String,
+    pub interrupt: String,
+    pub priority: u32,
+    pub deadline: u32,
+    pub activation: Activation,
+    pub cs_mechanism: CsMechanism,
+    // Symbol to start symbolic execution from, defaults to the interrupt vector. Setting it to the body of
+    // a task that is reached through a dispatcher leaves the dispatcher overhead out of the measured cycles
+    // and critical sections, the body is then entered with unconstrained arguments.
+    pub entry_symbol: Option<String>,
+    // elf to run symbolic execution of the task on instead of the elf of the application, for handlers built
+    // as firmware images of their own.
+    pub elf: Option<String>,
+    // symbol of the code that enables the interrupt of the task (an init phase run before it can fire). The
+    // task is only scheduled if an ISER write on some path of the symbol enables it, see `interrupt_enables`.
+    pub enabled_after: Option<String>,
+    // cycles added to every job of the task for the CPU stalling on the bus while DMA or another master
+    // uses it, which the cycle model doesn't see. An engineering margin set from measurements or the worst
+    // DMA load, it can't be derived from the elf.
+    pub bus_contention: u32,
+    // cycles a job of the task can be released late after the start of its period, e.g. a timer interrupt
+    // waiting for a masked section or the latency of the timer itself varying. The response time counts from
+    // the start of the period and a late job followed by one on time interferes twice within less than the
+    // inter arrival time, see `RtaConfig::jitter`.
+    pub jitter: u32,
+    // core of a dual-core chip the task runs on (RP2040 core 0 or 1), each core with its own interrupts and
+    // priorities. Tasks only interfere with and block tasks on the same core, except through spinlocks,
+    // which are only traced for tasks with a core set. `None` is core 0 of a single-core application.
+    pub core: Option<u8>,
+    // background work below every interrupt handler, like the RTIC idle loop. It runs at preemption level 0
+    // and its deadline isn't checked, the report gives the cycles it gets instead, see
+    // `rta::background_budget`. Its interrupt only names the default entry symbol and isn't checked.
+    pub background: bool,
+    // WCET in cycles someone verified by hand, checked by `check_expected` to catch regressions and changes
+    // in the cycle model.
+    pub expected_wcet: Option<u32>,
+    // labels to organise tasks by (`subsystem=motor`), carried into the manifest and usable with `TaskFilter`.
+    // Ordered so the output is the same every run.
+    pub tags: BTreeMap<String, String>,
+}
+
+// How the jobs of a task are released, in cycles. Both are analysed at the shortest time between two
+// releases, the worst case of a sporadic task is to arrive as often as it can, so for now they only differ in
+// the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Activation {
+    // released at a fixed rate, e.g. by a timer.
+    Periodic { period: u32 },
+    // released by events with only a guaranteed minimum separation, e.g. a button or a bus message.
+    Sporadic { min_interarrival: u32 },
+}
+
+impl Activation {
+    // The shortest time between two releases, what the response time analysis uses.
+    pub fn inter_arrival(self) -> u32 {
+        match self {
+            Activation::Periodic { period } => period,
+            Activation::Sporadic { min_interarrival } => min_interarrival,
+        }
+    }
+
+    // The same kind of activation with `cycles` between releases.
+    pub fn with_inter_arrival(self, cycles: u32) -> Activation {
+        match self {
+            Activation::Periodic { .. } => Activation::Periodic { period: cycles },
+            Activation::Sporadic { .. } => Activation::Sporadic { min_interarrival: cycles },
+        }
+    }
+}
+
+impl InputTask {
+    // The symbol symbolic execution starts from.
+    pub fn entry_symbol(&self) -> &str {
+        self.entry_symbol.as_deref().unwrap_or(&self.interrupt)
+    }
+
+    // The elf the task is analysed in: its own if it has one, otherwise `default`, the elf of the
+    // application. The entry symbol and the `enabled_after` symbol are looked up in it.
+    pub fn elf<'a>(&'a self, default: &'a str) -> &'a str {
+        self.elf.as_deref().unwrap_or(default)
+    }
+
+    pub fn core(&self) -> u8 {
+        self.core.unwrap_or(0)
+    }
+
+    // The deadline of the task in the response time analysis, the largest one for a background task so it
+    // never misses it.
+    pub fn analysed_deadline(&self) -> u32 {
+        match self.background {
+            true => u32::MAX,
+            false => self.deadline,
+        }
+    }
+}
+
+// The NVIC PRIGROUP field splits a priority into preemption (group) priority and subpriority, with the
+// subpriority in the low `prigroup + 1` bits. Only the preemption priority decides if one interrupt can
+// preempt another, so tasks that only differ in subpriority end up on the same level and do not interfere.
+pub fn preemption_level(priority: u32, prigroup: Option<u8>) -> u32 {
+    match prigroup {
+        Some(prigroup) => priority.checked_shr(prigroup as u32 + 1).unwrap_or(0),
+        None => priority,
+    }
+}
+
+// Preemption level of each task, in the same order as `tasks`. Several tasks can share an interrupt vector
+// (software tasks behind one dispatcher, each with its own `entry_symbol`). They run at the priority of that
+// vector and can't preempt each other, so they all get the highest level of the tasks on the vector.
+// Background tasks have no vector and are on level 0.
+pub fn preemption_levels(tasks: &[InputTask], prigroup: Option<u8>) -> Vec<u32> {
+    let mut vector_levels: HashMap<&str, u32> = HashMap::new();
+    for task in tasks.iter().filter(|t| !t.background) {
+        let level = preemption_level(task.priority, prigroup);
+        let vector_level = vector_levels.entry(&task.interrupt).or_insert(level);
+        *vector_level = (*vector_level).max(level);
+    }
+
+    tasks
+        .iter()
+        .map(|task| match task.background {
+            true => 0,
+            false => vector_levels[task.interrupt.as_str()],
+        })
+        .collect()
+}
+
+// Bytes the core pushes on exception entry before a handler runs: R0-R3, R12, LR, PC and xPSR. A Cortex-M4F
+// using the FPU pushes 72 more, which isn't counted.
+pub const EXCEPTION_FRAME: u64 = 32;
+
+// Stack needed on each core under SRP, where the tasks of a core share one stack and only a task on a higher
+// level can preempt another: the deepest task of each level, with the exception frame of each level of
+// interrupt handlers, summed over the levels. `max_stack` is the deepest stack of each task in bytes, tasks
+// that aren't in it (from a trace file) are left out.
+pub fn stack_required(
+    tasks: &[InputTask],
+    prigroup: Option<u8>,
+    max_stack: &[(String, u64)],
+) -> BTreeMap<u8, u64> {
+    let mut deepest: BTreeMap<(u8, u32), u64> = BTreeMap::new();
+    for (task, level) in tasks.iter().zip(preemption_levels(tasks, prigroup)) {
+        let Some((_, stack)) = max_stack.iter().find(|(name, _)| *name == task.name) else {
+            continue;
+        };
+        // background tasks run in thread mode, nothing is pushed to start them.
+        let frame = if task.background { 0 } else { EXCEPTION_FRAME };
+        let level = deepest.entry((task.core(), level)).or_insert(0);
+        *level = (*level).max(stack + frame);
+    }
+
+    let mut required = BTreeMap::new();
+    for ((core, _), stack) in deepest {
+        *required.entry(core).or_insert(0) += stack;
+    }
+    required
+}
+
+// What to do with tasks on different interrupt vectors configured at the same priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EqualPriority {
+    // reject them, the priorities are most likely a mistake.
+    #[default]
+    Error,
+    // a cooperative group: tasks at the same preemption level don't preempt each other but run one after the
+    // other, so each job of the others released while a task waits delays it like a higher priority job.
+    Fifo,
+}
+
+impl FromStr for EqualPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<EqualPriority, String> {
+        match s {
+            "error" => Ok(EqualPriority::Error),
+            "fifo" => Ok(EqualPriority::Fifo),
+            _ => Err(format!("expected `error` or `fifo`, got `{s}`")),
+        }
+    }
+}
+
+// Checks the configured priorities against `equal_priority`. Tasks sharing a vector always share its priority
+// and tasks only differing in subpriority are separated by PRIGROUP, neither is an error. A vector has one
+// NVIC priority, so tasks on it with different priorities are an error unless they are a dispatcher group
+// (every one of them has an `entry_symbol`), which runs at the highest priority of the group. Priorities
+// above 255 don't fit the NVIC priority (or srp's `u8`) and are an error as well. Background tasks have no
+// vector and their priority isn't used, they are left out.
+pub fn validate_priorities(tasks: &[InputTask], equal_priority: EqualPriority) -> Result<(), String> {
+    let tasks: Vec<&InputTask> = tasks.iter().filter(|t| !t.background).collect();
+    if let Some(task) = tasks.iter().find(|t| u8::try_from(t.priority).is_err()) {
+        return Err(format!(
+            "task {} has priority {}, priorities go up to {}",
+            task.name,
+            task.priority,
+            u8::MAX
+        ));
+    }
+    for (i, a) in tasks.iter().enumerate() {
+        if let Some(b) = tasks[i + 1..]
+            .iter()
+            .find(|b| b.interrupt == a.interrupt && b.priority != a.priority)
+        {
+            let dispatched = tasks
+                .iter()
+                .filter(|t| t.interrupt == a.interrupt)
+                .all(|t| t.entry_symbol.is_some());
+            if !dispatched {
+                return Err(format!(
+                    "tasks {} and {} are both on {} but have priorities {} and {}, a vector has one priority \
+                     (give every task on it an entry symbol if it is a dispatcher)",
+                    a.name, b.name, a.interrupt, a.priority, b.priority
+                ));
+            }
+        }
+    }
+    if equal_priority == EqualPriority::Fifo {
+        return Ok(());
+    }
+    for (i, a) in tasks.iter().enumerate() {
+        if let Some(b) = tasks[i + 1..]
+            .iter()
+            .find(|b| b.priority == a.priority && b.interrupt != a.interrupt)
+        {
+            return Err(format!(
+                "tasks {} and {} both have priority {}, give them different priorities or group them with \
+                 equal priority fifo",
+                a.name, b.name, a.priority
+            ));
+        }
+    }
+    Ok(())
+}
+
+// `tasks` with deadline monotonic priorities: the shortest deadline gets the highest priority. Tasks sharing a
+// vector keep sharing it, at the priority of the shortest deadline among them, and vectors with the same
+// deadline are ordered as configured, so no two vectors get the same priority. The priorities are 1 and up,
+// shifted past the subpriority bits when a PRIGROUP is given so they stay distinct preemption levels.
+pub fn deadline_monotonic(tasks: &[InputTask], prigroup: Option<u8>) -> Vec<InputTask> {
+    let mut vectors: Vec<(&str, u32)> = vec![];
+    for task in tasks {
+        match vectors.iter_mut().find(|(interrupt, _)| *interrupt == task.interrupt) {
+            Some((_, deadline)) => *deadline = (*deadline).min(task.deadline),
+            None => vectors.push((&task.interrupt, task.deadline)),
+        }
+    }
+    // stable, so equal deadlines stay in configuration order.
+    vectors.sort_by_key(|(_, deadline)| *deadline);
+
+    let shift = prigroup.map_or(0, |prigroup| prigroup as u32 + 1);
+    tasks
+        .iter()
+        .map(|task| {
+            let rank = vectors.iter().position(|(interrupt, _)| *interrupt == task.interrupt).unwrap();
+            InputTask {
+                priority: ((vectors.len() - rank) as u32) << shift,
+                ..task.clone()
+            }
+        })
+        .collect()
+}
+
+// The laps of a path opening and closing critical sections, laps marking excluded calls, cycle counter reads,
+// the stack and nested NVIC locks are left out.
+pub fn section_laps(symex_result: &PathLaps) -> Vec<(usize, String)> {
+    symex_result
+        .cycle_laps
+        .iter()
+        .filter(|(_, label)| label != EXCLUDED_LABEL && label != CYCCNT_LABEL)
+        .filter(|(_, label)| stack_lap(label).is_none() && nested_lap(label).is_none())
+        .cloned()
+        .collect()
+}
+
+// `level` is the preemption level of the task, see `preemption_levels`, at most 255 once the priorities are
+// checked by `validate_priorities`. A path without laps (a task that
+// never locks a resource) gets a trace without inner sections, so it never blocks other tasks. It can still be
+// blocked itself, by a lower priority task holding a resource that a higher priority task also uses. Errs on
+// a path too long for the cycles of a trace.
+pub fn create_task(symex_result: &PathLaps, task: &InputTask, level: u32) -> Result<Task, AnalysisError> {
+    let laps = section_laps(symex_result);
+    if let Some(cycle) = overflowing_cycle(symex_result.max_cycles, &laps) {
+        return Err(AnalysisError::CycleOverflow(task.name.to_owned(), cycle));
+    }
+    let trace = make_trace(0, symex_result.max_cycles, &laps, task.name.to_owned());
+    Ok(Task {
+        id: task.name.to_owned(),
+        prio: level as u8,
+        deadline: task.analysed_deadline(),
+        inter_arrival: task.activation.inter_arrival(),
+        trace,
+    })
+}
+
+// The longest critical section on each resource in `trace`, nested ones included, added to `longest`.
+fn longest_sections(trace: &Trace, longest: &mut BTreeMap<String, u32>) {
+    for inner in &trace.inner {
+        let duration = longest.entry(inner.id.to_owned()).or_insert(0);
+        *duration = (*duration).max(inner.end.saturating_sub(inner.start));
+        longest_sections(inner, longest);
+    }
+}
+
+// Collapses all paths of a task into one task at least as demanding as each of them: the longest WCET and,
+// for each resource, the longest critical section on it from any path. Sections are flattened and start at
+// 0, which gives the same blocking and interference in the response time analysis but loses the nesting.
+// `None` if there are no paths.
+pub fn collapse_paths(paths: &[Task]) -> Option<Task> {
+    let first = paths.first()?;
+    let mut longest = BTreeMap::new();
+    for path in paths {
+        longest_sections(&path.trace, &mut longest);
+    }
+    let wcet = paths.iter().map(|t| t.trace.end.saturating_sub(t.trace.start)).max()?;
+
+    Some(Task {
+        id: first.id.to_owned(),
+        prio: first.prio,
+        deadline: first.deadline,
+        inter_arrival: first.inter_arrival,
+        trace: Trace {
+            id: first.trace.id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: longest
+                .into_iter()
+                .map(|(id, duration)| Trace {
+                    id,
+                    start: 0,
+                    end: duration,
+                    inner: vec![],
+                })
+                .collect(),
+        },
+    })
+}
+
+// The paths of a task without those dominated by another path: one with a WCET at least as long and, on
+// every resource the dominated path locks, a critical section at least as long. Of paths dominating each
+// other the first is kept. Dropping them doesn't change any worst case. In a combination the dominating path
+// gives every other task at least the interference and blocking the dominated one does (it locks the same
+// resources, so the ceilings are at least as high), and the response time of a task doesn't depend on its own
+// critical sections. Keeping only the path with the longest WCET would not be sound, a shorter path can hold
+// a longer section.
+pub fn prune_dominated(paths: Vec<(usize, Task)>) -> Vec<(usize, Task)> {
+    let demands: Vec<(u32, BTreeMap<String, u32>)> = paths
+        .iter()
+        .map(|(_, t)| {
+            let mut longest = BTreeMap::new();
+            longest_sections(&t.trace, &mut longest);
+            (t.trace.end.saturating_sub(t.trace.start), longest)
+        })
+        .collect();
+    let dominates = |b: usize, a: usize| {
+        let ((wcet_a, sections_a), (wcet_b, sections_b)) = (&demands[a], &demands[b]);
+        wcet_b >= wcet_a
+            && sections_a
+                .iter()
+                .all(|(id, duration)| sections_b.get(id).is_some_and(|d| d >= duration))
+    };
+    let kept: Vec<bool> = (0..paths.len())
+        .map(|a| !(0..paths.len()).any(|b| b != a && dominates(b, a) && (b < a || !dominates(a, b))))
+        .collect();
+    paths.into_iter().zip(kept).filter(|(_, kept)| *kept).map(|(path, _)| path).collect()
+}
+
+// The longest critical section on each resource over all paths in `tasks`, nested ones included, by resource
+// id. The blocking of a task in the response time analysis is at most the largest of these over the resources
+// with a ceiling of at least its priority, only sections of lower priority tasks count.
+pub fn longest_sections_by_resource(tasks: &[Task]) -> BTreeMap<String, u32> {
+    let mut longest = BTreeMap::new();
+    for task in tasks {
+        longest_sections(&task.trace, &mut longest);
+    }
+    longest
+}
+
+// Fraction of the execution time of a task spent holding a resource, from the top level critical sections of
+// its trace (nested sections are already covered by the section around them). High values suggest locking
+// more than needed.
+pub fn lock_fraction(task: &Task) -> f32 {
+    let duration = task.trace.end.saturating_sub(task.trace.start);
+    if duration == 0 {
+        return 0.0;
+    }
+    let locked: u64 = task.trace.inner.iter().map(|t| t.end.saturating_sub(t.start) as u64).sum();
+    (locked as f64 / duration as f64) as f32
+}
+
+// The longest critical section of any task, as `(cycles, task, resource)`. Every section masks interrupts, so
+// this is the worst interrupt disable time of the system regardless of how the tasks are scheduled. Nested
+// sections are scanned too, they are never longer than the section around them but can be on a resource of
+// their own. The first found wins a tie, `None` if no task locks anything.
+pub fn global_max_lock(tasks: &[Task]) -> Option<(u32, String, String)> {
+    fn visit(trace: &Trace, task: &str, longest: &mut Option<(u32, String, String)>) {
+        for inner in &trace.inner {
+            let duration = inner.end.saturating_sub(inner.start);
+            if longest.as_ref().is_none_or(|(cycles, _, _)| duration > *cycles) {
+                *longest = Some((duration, task.to_owned(), inner.id.to_owned()));
+            }
+            visit(inner, task, longest);
+        }
+    }
+
+    let mut longest = None;
+    for task in tasks {
+        visit(&task.trace, &task.id, &mut longest);
+    }
+    longest
+}
+
+// Every resource id locked anywhere in the traces of `tasks`.
+pub fn discovered_resources(tasks: &[Task]) -> BTreeSet<String> {
+    resource_users(tasks).into_keys().collect()
+}
+
+// The tasks locking each resource, by resource id. Tasks are named by id, so several paths of one task count
+// once.
+pub fn resource_users(tasks: &[Task]) -> BTreeMap<String, BTreeSet<String>> {
+    fn visit(trace: &Trace, task: &str, users: &mut BTreeMap<String, BTreeSet<String>>) {
+        for inner in &trace.inner {
+            users.entry(inner.id.to_owned()).or_default().insert(task.to_owned());
+            visit(inner, task, users);
+        }
+    }
+
+    let mut users = BTreeMap::new();
+    for task in tasks {
+        visit(&task.trace, &task.id, &mut users);
+    }
+    users
+}
+
+enum TaskPattern {
+    Regex(Regex),
+    Name(String),
+}
+
+// Selects tasks by name and tags, e.g. for what to report. Patterns are regexes matched against the whole
+// name, a pattern that isn't a valid regex is compared as a plain name. No patterns selects every task.
+pub struct TaskFilter {
+    patterns: Vec<TaskPattern>,
+    // names of the tasks having all the required tags, `None` without required tags.
+    tagged: Option<Vec<String>>,
+}
+
+impl TaskFilter {
+    pub fn new(patterns: &[String]) -> TaskFilter {
+        let patterns = patterns
+            .iter()
+            .map(|p| match Regex::new(&format!("^(?:{p})$")) {
+                Ok(re) => TaskPattern::Regex(re),
+                Err(_) => TaskPattern::Name(p.to_owned()),
+            })
+            .collect();
+        TaskFilter {
+            patterns,
+            tagged: None,
+        }
+    }
+
+    // Only selects the tasks of `tasks` having every tag in `tags` with the given value. Tasks that aren't in
+    // `tasks` (like servers) have no tags.
+    pub fn with_tags(mut self, tags: &[(String, String)], tasks: &[InputTask]) -> TaskFilter {
+        if !tags.is_empty() {
+            let tagged = tasks
+                .iter()
+                .filter(|task| tags.iter().all(|(key, value)| task.tags.get(key) == Some(value)))
+                .map(|task| task.name.to_owned())
+                .collect();
+            self.tagged = Some(tagged);
+        }
+        self
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let tagged = self.tagged.as_ref().is_none_or(|tagged| tagged.iter().any(|t| t == name));
+        let named = self.patterns.is_empty()
+            || self.patterns.iter().any(|p| match p {
+                TaskPattern::Regex(re) => re.is_match(name),
+                TaskPattern::Name(n) => n == name,
+            });
+        tagged && named
+    }
+}
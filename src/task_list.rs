@@ -0,0 +1,283 @@
+use std::{collections::BTreeMap, fmt, fs};
+
+use serde::Deserialize;
+
+use crate::{
+    chain::Chain,
+    hooks::{CsMechanism, Precondition},
+    interrupts::{check_interrupt, InterruptTable},
+    task::{Activation, InputTask},
+    units::{Rounding, TimeUse, TimeValue},
+};
+
+// A deadline or inter arrival time, a number of cycles or a string with a time unit (`"500us"`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Duration {
+    Cycles(u32),
+    Time(String),
+}
+
+impl Duration {
+    // In cycles at `clock_hz`, a time rounded to the safe side for `time_use`.
+    fn to_cycles(&self, clock_hz: u64, time_use: TimeUse) -> Result<u32, String> {
+        let time = match self {
+            Duration::Cycles(cycles) => return Ok(*cycles),
+            Duration::Time(time) => time,
+        };
+        let cycles = time
+            .parse::<TimeValue>()
+            .and_then(|t| t.to_cycles(Some(clock_hz), Rounding::Conservative, time_use))
+            .map_err(|e| e.to_string())?;
+        u32::try_from(cycles).map_err(|_| format!("{cycles} cycles is too large"))
+    }
+}
+
+// A task as written in the task list, see `InputTask` for what the fields mean.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskEntry {
+    name: String,
+    interrupt: String,
+    priority: u32,
+    deadline: Duration,
+    inter_arrival: Duration,
+    // `inter_arrival` is a minimum separation of events instead of a period.
+    #[serde(default)]
+    sporadic: bool,
+    #[serde(default)]
+    cs_mechanism: CsMechanism,
+    entry_symbol: Option<String>,
+    elf: Option<String>,
+    enabled_after: Option<String>,
+    #[serde(default)]
+    bus_contention: u32,
+    jitter: Option<Duration>,
+    core: Option<u32>,
+    #[serde(default)]
+    background: bool,
+    expected_wcet: Option<u32>,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+}
+
+// A chain as written in the task list, see `Chain`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChainEntry {
+    name: String,
+    tasks: Vec<String>,
+    activation_delay: Option<Duration>,
+    deadline: Option<Duration>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskListFile {
+    task: Vec<TaskEntry>,
+    #[serde(default)]
+    precondition: Vec<Precondition>,
+    #[serde(default)]
+    chain: Vec<ChainEntry>,
+}
+
+// What a task list configures: the tasks, the memory they can assume (see `Precondition`) and the chains of
+// tasks to bound the end to end latency of.
+pub struct TaskList {
+    pub tasks: Vec<InputTask>,
+    pub preconditions: Vec<Precondition>,
+    pub chains: Vec<Chain>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    // the file could not be read, with the path.
+    Read(String, String),
+    // the file isn't TOML or a task is missing a field or has one of the wrong type, the message has the
+    // position.
+    Parse(String, String),
+    // a task has a value that doesn't make sense for the field.
+    InvalidField {
+        task: String,
+        field: &'static str,
+        message: String,
+    },
+    // tasks with an interrupt that isn't in the interrupt table, with the task and the error, all of them.
+    UnknownInterrupts(Vec<(String, String)>),
+    // a precondition that can't hold, with its address.
+    InvalidPrecondition(u32, String),
+    // a chain with a value that doesn't make sense for the field.
+    InvalidChain {
+        chain: String,
+        field: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, e) => write!(f, "could not read the task list {path}: {e}"),
+            ConfigError::Parse(path, e) => write!(f, "{path}: {e}"),
+            ConfigError::InvalidField { task, field, message } => {
+                write!(f, "task {task}: {field}: {message}")
+            }
+            ConfigError::UnknownInterrupts(unknown) => {
+                let errors: Vec<String> =
+                    unknown.iter().map(|(task, e)| format!("task {task}: interrupt: {e}")).collect();
+                write!(f, "{}", errors.join("\n"))
+            }
+            ConfigError::InvalidPrecondition(address, message) => {
+                write!(f, "precondition at {address:#010x}: {message}")
+            }
+            ConfigError::InvalidChain { chain, field, message } => {
+                write!(f, "chain {chain}: {field}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// The tasks of a TOML task list, a `[[task]]` table for each with at least `name`, `interrupt`, `priority`,
+// `deadline` and `inter_arrival`, in the order they are written. Interrupts are checked against `table`
+// first, all unknown ones are reported together. Deadlines and inter arrival times with a time unit are
+// converted to cycles at `clock_hz`.
+// `[[precondition]]` tables with an `address` and a `value` give memory words a known value, see
+// `Precondition`. `[[chain]]` tables with a `name`, the `tasks` and optionally an `activation_delay` and a
+// `deadline` declare chains, see `Chain`.
+// Tasks with equal priorities are checked by `run_analysis` against `AnalysisConfig::equal_priority`, as it
+// depends on the policy.
+pub fn load_task_list(
+    path: &str,
+    table: &dyn InterruptTable,
+    clock_hz: u64,
+) -> Result<TaskList, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|e| ConfigError::Read(path.to_owned(), e.to_string()))?;
+    let file: TaskListFile =
+        toml::from_str(&text).map_err(|e| ConfigError::Parse(path.to_owned(), e.to_string()))?;
+
+    let unknown: Vec<(String, String)> = file
+        .task
+        .iter()
+        .filter(|entry| !entry.background)
+        .filter_map(|entry| {
+            check_interrupt(&entry.interrupt, table).err().map(|e| (entry.name.to_owned(), e))
+        })
+        .collect();
+    if !unknown.is_empty() {
+        return Err(ConfigError::UnknownInterrupts(unknown));
+    }
+
+    let mut tasks: Vec<InputTask> = vec![];
+    for entry in file.task {
+        let invalid = |field, message: String| ConfigError::InvalidField {
+            task: entry.name.to_owned(),
+            field,
+            message,
+        };
+        if tasks.iter().any(|t| t.name == entry.name) {
+            return Err(invalid(
+                "name",
+                "there is already a task with this name".to_owned(),
+            ));
+        }
+        if u8::try_from(entry.priority).is_err() {
+            let message = format!("{} is over {}, the highest priority", entry.priority, u8::MAX);
+            return Err(invalid("priority", message));
+        }
+        let limit = |duration: &Duration, field| {
+            duration.to_cycles(clock_hz, TimeUse::Limit).map_err(|e| invalid(field, e))
+        };
+        let deadline = limit(&entry.deadline, "deadline")?;
+        if deadline == 0 {
+            return Err(invalid("deadline", "has to be at least 1 cycle".to_owned()));
+        }
+        let inter_arrival = limit(&entry.inter_arrival, "inter_arrival")?;
+        if inter_arrival == 0 {
+            return Err(invalid("inter_arrival", "has to be at least 1 cycle".to_owned()));
+        }
+        let jitter = match &entry.jitter {
+            Some(jitter) => jitter.to_cycles(clock_hz, TimeUse::Demand).map_err(|e| invalid("jitter", e))?,
+            None => 0,
+        };
+        let core = match entry.core {
+            Some(core @ (0 | 1)) => Some(core as u8),
+            Some(core) => return Err(invalid("core", format!("has to be 0 or 1, got {core}"))),
+            None => None,
+        };
+        tasks.push(InputTask {
+            name: entry.name,
+            interrupt: entry.interrupt,
+            priority: entry.priority,
+            deadline,
+            activation: match entry.sporadic {
+                true => Activation::Sporadic { min_interarrival: inter_arrival },
+                false => Activation::Periodic { period: inter_arrival },
+            },
+            cs_mechanism: entry.cs_mechanism,
+            entry_symbol: entry.entry_symbol,
+            elf: entry.elf,
+            enabled_after: entry.enabled_after,
+            bus_contention: entry.bus_contention,
+            jitter,
+            core,
+            background: entry.background,
+            expected_wcet: entry.expected_wcet,
+            tags: entry.tags,
+        });
+    }
+
+    for (i, precondition) in file.precondition.iter().enumerate() {
+        let invalid =
+            |message: &str| ConfigError::InvalidPrecondition(precondition.address, message.to_owned());
+        // the hooks only see word reads at the address.
+        if precondition.address % 4 != 0 {
+            return Err(invalid("is not word aligned"));
+        }
+        if file.precondition[..i].iter().any(|p| p.address == precondition.address) {
+            return Err(invalid("there is already a precondition for this address"));
+        }
+    }
+
+    let mut chains: Vec<Chain> = vec![];
+    for entry in file.chain {
+        let invalid = |field, message: String| ConfigError::InvalidChain {
+            chain: entry.name.to_owned(),
+            field,
+            message,
+        };
+        if chains.iter().any(|c| c.name == entry.name) {
+            return Err(invalid("name", "there is already a chain with this name".to_owned()));
+        }
+        if entry.tasks.is_empty() {
+            return Err(invalid("tasks", "has to name at least one task".to_owned()));
+        }
+        if let Some(task) = entry.tasks.iter().find(|name| !tasks.iter().any(|t| t.name == **name)) {
+            return Err(invalid("tasks", format!("no task named {task}")));
+        }
+        let activation_delay = match &entry.activation_delay {
+            Some(delay) => delay
+                .to_cycles(clock_hz, TimeUse::Demand)
+                .map_err(|e| invalid("activation_delay", e))?,
+            None => 0,
+        };
+        let deadline = match &entry.deadline {
+            Some(deadline) => {
+                Some(deadline.to_cycles(clock_hz, TimeUse::Limit).map_err(|e| invalid("deadline", e))?)
+            }
+            None => None,
+        };
+        chains.push(Chain {
+            name: entry.name,
+            tasks: entry.tasks,
+            activation_delay,
+            deadline,
+        });
+    }
+    Ok(TaskList {
+        tasks,
+        preconditions: file.precondition,
+        chains,
+    })
+}
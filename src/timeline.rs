@@ -0,0 +1,141 @@
+use serde::Serialize;
+use srp::common::{TasksResult, Trace};
+
+use crate::interrupts::{resource_name, InterruptTable};
+
+// One event of the Chrome trace event format, which Perfetto also reads. Times are in cycles.
+#[derive(Serialize)]
+struct Event {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cat: Option<&'static str>,
+    ph: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<u32>,
+    pid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    args: Option<NameArgs>,
+}
+
+#[derive(Serialize)]
+struct NameArgs {
+    name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFile {
+    trace_events: Vec<Event>,
+}
+
+const PID: u32 = 1;
+
+fn slice(name: String, cat: &'static str, start: u32, end: u32, tid: u32) -> Event {
+    Event {
+        name,
+        cat: Some(cat),
+        ph: "X",
+        ts: Some(start),
+        dur: Some(end - start),
+        pid: PID,
+        tid: Some(tid),
+        id: None,
+        args: None,
+    }
+}
+
+fn metadata(name: &str, value: String, tid: Option<u32>) -> Event {
+    Event {
+        name: name.to_owned(),
+        cat: None,
+        ph: "M",
+        ts: None,
+        dur: None,
+        pid: PID,
+        tid,
+        id: None,
+        args: Some(NameArgs { name: value }),
+    }
+}
+
+fn sections(trace: &Trace, tid: u32, table: &dyn InterruptTable, events: &mut Vec<Event>) {
+    for inner in &trace.inner {
+        events.push(slice(resource_name(&inner.id, table), "lock", inner.start, inner.end, tid));
+        sections(inner, tid, table, events);
+    }
+}
+
+fn async_event(name: String, ph: &'static str, ts: u32, tid: u32) -> Event {
+    Event {
+        name,
+        cat: Some("lock"),
+        ph,
+        ts: Some(ts),
+        dur: None,
+        pid: PID,
+        tid: Some(tid),
+        id: Some(tid),
+        args: None,
+    }
+}
+
+// Async begin and end events for the critical sections, nested sections share the id of their task so they
+// stack on its track.
+fn async_sections(trace: &Trace, tid: u32, table: &dyn InterruptTable, events: &mut Vec<Event>) {
+    for inner in &trace.inner {
+        events.push(async_event(resource_name(&inner.id, table), "b", inner.start, tid));
+        async_sections(inner, tid, table, events);
+        events.push(async_event(resource_name(&inner.id, table), "e", inner.end, tid));
+    }
+}
+
+// Everything is serialized from structs so keys always come in the same order and the output of the same
+// report is byte identical between runs.
+fn to_json(events: Vec<Event>, pretty: bool) -> String {
+    let file = TraceFile {
+        trace_events: events,
+    };
+    if pretty {
+        serde_json::to_string_pretty(&file).unwrap()
+    } else {
+        serde_json::to_string(&file).unwrap()
+    }
+}
+
+// The worst trace of each task as Chrome trace events, one thread per task with its critical sections as
+// nested slices named after the interrupts of `table` they mask. All tasks start at 0, times are in cycles.
+// `pretty` indents the json.
+pub fn chrome_trace(results: &TasksResult, table: &dyn InterruptTable, pretty: bool) -> String {
+    let mut events = vec![];
+    for (tid, result) in results.0.iter().enumerate() {
+        let trace = &result.task.trace;
+        events.push(slice(result.task.id.to_owned(), "task", trace.start, trace.end, tid as u32));
+        sections(trace, tid as u32, table, &mut events);
+    }
+    to_json(events, pretty)
+}
+
+// Like `chrome_trace` with process and thread names so Perfetto shows each task as its own named track, and
+// the critical sections as async slices on it.
+pub fn perfetto_trace(
+    results: &TasksResult,
+    process_name: &str,
+    table: &dyn InterruptTable,
+    pretty: bool,
+) -> String {
+    let mut events = vec![metadata("process_name", process_name.to_owned(), None)];
+    for (tid, result) in results.0.iter().enumerate() {
+        let tid = tid as u32;
+        let trace = &result.task.trace;
+        events.push(metadata("thread_name", result.task.id.to_owned(), Some(tid)));
+        events.push(slice(result.task.id.to_owned(), "task", trace.start, trace.end, tid));
+        async_sections(trace, tid, table, &mut events);
+    }
+    to_json(events, pretty)
+}
@@ -0,0 +1,335 @@
+use std::fmt;
+
+use srp::common::Trace;
+
+// Something in the lock/unlock events that doesn't form a properly nested trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceIssue {
+    // a resource was unlocked without being locked, the event is ignored.
+    UnmatchedUnlock { id: String, cycle: usize },
+    // a resource was unlocked while a section opened inside it was still held. It is kept locked until the
+    // inner section is released so the trace stays nested, which can only make the section longer. Not an
+    // issue if the inner section is released at the same cycle, as when one mask write unmasks both.
+    Interleaved { id: String, cycle: usize },
+    // a resource was still locked at the end, the section is closed at the end of the trace.
+    Unclosed { id: String, cycle: usize },
+}
+
+impl fmt::Display for TraceIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceIssue::UnmatchedUnlock { id, cycle } => {
+                write!(f, "`{id}` unlocked at cycle {cycle} without being locked")
+            }
+            TraceIssue::Interleaved { id, cycle } => write!(
+                f,
+                "`{id}` unlocked at cycle {cycle} while a section inside it was still locked"
+            ),
+            TraceIssue::Unclosed { id, cycle } => {
+                write!(f, "`{id}` locked at cycle {cycle} is never unlocked")
+            }
+        }
+    }
+}
+
+// The events did not form a clean trace. `trace` is the best effort trace built anyway.
+#[derive(Debug, Clone)]
+pub struct TraceError {
+    pub trace: Trace,
+    pub issues: Vec<TraceIssue>,
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "trace `{}` is not balanced: ", self.trace.id)?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+// A node of a trace that breaks the time order every trace should have, see `validate_trace`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceIntegrityError {
+    // the section ends before it starts.
+    Inverted { id: String, start: u32, end: u32 },
+    // the section is not within the section (or root) `parent` it is nested in.
+    OutsideParent { id: String, parent: String },
+    // the section starts before its sibling `previous` ends.
+    Overlap { id: String, previous: String },
+}
+
+impl fmt::Display for TraceIntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceIntegrityError::Inverted { id, start, end } => {
+                write!(f, "`{id}` ends at {end} before it starts at {start}")
+            }
+            TraceIntegrityError::OutsideParent { id, parent } => {
+                write!(f, "`{id}` is not within `{parent}` it is nested in")
+            }
+            TraceIntegrityError::Overlap { id, previous } => {
+                write!(f, "`{id}` starts before `{previous}` next to it ends")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TraceIntegrityError {}
+
+// Checks that every section of `trace` starts before it ends, lies within the section around it and doesn't
+// overlap the other sections next to it. `make_trace` only builds such traces, this guards against bugs there
+// and in traces from elsewhere (trace files, servers). Sections may touch, one can start at the cycle the
+// previous one ends.
+pub fn validate_trace(trace: &Trace) -> Result<(), TraceIntegrityError> {
+    if trace.start > trace.end {
+        return Err(TraceIntegrityError::Inverted {
+            id: trace.id.to_owned(),
+            start: trace.start,
+            end: trace.end,
+        });
+    }
+    let mut inner: Vec<&Trace> = trace.inner.iter().collect();
+    inner.sort_by_key(|t| t.start);
+    for (i, section) in inner.iter().enumerate() {
+        validate_trace(section)?;
+        if section.start < trace.start || section.end > trace.end {
+            return Err(TraceIntegrityError::OutsideParent {
+                id: section.id.to_owned(),
+                parent: trace.id.to_owned(),
+            });
+        }
+        if let Some(previous) = i.checked_sub(1).map(|p| inner[p]) {
+            if section.start < previous.end {
+                return Err(TraceIntegrityError::Overlap {
+                    id: section.id.to_owned(),
+                    previous: previous.id.to_owned(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+// Cycle counts are usize in symex but u32 in srp traces. A count too large for that saturates here instead of
+// wrapping around to a short trace, `overflowing_cycle` finds it before a trace is built for the analysis.
+fn cycles(cycle: usize) -> u32 {
+    u32::try_from(cycle).unwrap_or(u32::MAX)
+}
+
+// The first cycle count of a path ending at `end` with `laps` that doesn't fit in a trace, if there is one.
+pub fn overflowing_cycle(end: usize, laps: &[(usize, String)]) -> Option<usize> {
+    std::iter::once(end)
+        .chain(laps.iter().map(|(cycle, _)| *cycle))
+        .find(|cycle| u32::try_from(*cycle).is_err())
+}
+
+struct OpenSection {
+    id: String,
+    start: usize,
+    // the cycle it was unlocked at while sections inside it were still open, it closes when they do.
+    released: Option<usize>,
+    inner: Vec<Trace>,
+}
+
+// Builds a trace from lock and unlock events in cycle order. Sections are matched as a stack, an unlock closes
+// the most recently locked section of the resource and the closed section becomes a child of the section
+// that is open around it (or of the root).
+#[derive(Default)]
+pub struct TraceBuilder {
+    open: Vec<OpenSection>,
+    root: Vec<Trace>,
+    issues: Vec<TraceIssue>,
+}
+
+impl TraceBuilder {
+    pub fn new() -> TraceBuilder {
+        TraceBuilder::default()
+    }
+
+    // True if the resource is currently locked.
+    pub fn is_locked(&self, id: &str) -> bool {
+        self.open.iter().any(|s| s.id == id && s.released.is_none())
+    }
+
+    // True if the sections from `i` up were all locked at the same cycle (by one mask write) and nothing
+    // closed inside the one at `i` yet, so they can be nested in any order.
+    fn locked_together(&self, i: usize) -> bool {
+        let start = self.open[i].start;
+        let together = self.open[i..].iter().all(|s| s.start == start && s.released.is_none());
+        together && self.open[i].inner.is_empty()
+    }
+
+    pub fn push_lock(&mut self, cycle: usize, id: &str) {
+        self.open.push(OpenSection {
+            id: id.to_owned(),
+            start: cycle,
+            released: None,
+            inner: vec![],
+        });
+    }
+
+    pub fn push_unlock(&mut self, cycle: usize, id: &str) {
+        match self.open.iter().rposition(|s| s.id == id && s.released.is_none()) {
+            // sections locked together are taken as nested with the one unlocked first innermost, so they are
+            // measured apart even when they are unlocked one by one.
+            Some(i) if i == self.open.len() - 1 || self.locked_together(i) => {
+                let section = self.open.remove(i);
+                self.open.push(section);
+                self.close_top(cycle);
+                // sections released out of order close together with the last section inside them.
+                while self.open.last().is_some_and(|s| s.released.is_some()) {
+                    self.close_top(cycle);
+                }
+            }
+            Some(i) => self.open[i].released = Some(cycle),
+            None => self.issues.push(TraceIssue::UnmatchedUnlock {
+                id: id.to_owned(),
+                cycle,
+            }),
+        }
+    }
+
+    fn close_top(&mut self, cycle: usize) {
+        let section = self.open.pop().unwrap();
+        if let Some(released) = section.released.filter(|released| *released < cycle) {
+            self.issues.push(TraceIssue::Interleaved {
+                id: section.id.to_owned(),
+                cycle: released,
+            });
+        }
+        let trace = Trace {
+            id: section.id,
+            start: cycles(section.start),
+            end: cycles(cycle),
+            inner: section.inner,
+        };
+        match self.open.last_mut() {
+            Some(parent) => parent.inner.push(trace),
+            None => self.root.push(trace),
+        }
+    }
+
+    // Finishes the trace with a root section spanning `start` to `end`. Sections still locked are closed at the
+    // end of the trace.
+    pub fn finish(mut self, start: usize, end: usize, id: String) -> Result<Trace, TraceError> {
+        while let Some(section) = self.open.last() {
+            if section.released.is_none() {
+                self.issues.push(TraceIssue::Unclosed {
+                    id: section.id.to_owned(),
+                    cycle: section.start,
+                });
+            }
+            self.close_top(end);
+        }
+
+        let trace = Trace {
+            id,
+            start: cycles(start),
+            end: cycles(end),
+            inner: self.root,
+        };
+
+        if self.issues.is_empty() {
+            Ok(trace)
+        } else {
+            Err(TraceError {
+                trace,
+                issues: self.issues,
+            })
+        }
+    }
+}
+
+// Builds the trace of a path from its cycle laps. Laps only carry the resource label, the first lap of a
+// label locks the resource and the next lap with the same label unlocks it. Laps at the same cycle come from
+// one write, see `mask_labels`.
+pub fn trace_from_laps(
+    start: usize,
+    end: usize,
+    laps: &[(usize, String)],
+    id: String,
+) -> Result<Trace, TraceError> {
+    let mut builder = TraceBuilder::new();
+    for (cycle, label) in laps {
+        if builder.is_locked(label) {
+            builder.push_unlock(*cycle, label);
+        } else {
+            builder.push_lock(*cycle, label);
+        }
+    }
+    builder.finish(start, end, id)
+}
+
+pub fn make_trace(start: usize, end: usize, laps: &[(usize, String)], id: String) -> Trace {
+    trace_from_laps(start, end, laps, id).unwrap_or_else(|e| e.trace)
+}
+
+// `trace` without the sections of `resources`, their time counts as running outside of them and the sections
+// nested in them move up to the section (or root) around them.
+pub fn without_sections(trace: &Trace, resources: &[String]) -> Trace {
+    fn inner(trace: &Trace, resources: &[String]) -> Vec<Trace> {
+        trace
+            .inner
+            .iter()
+            .flat_map(|section| match resources.contains(&section.id) {
+                true => inner(section, resources),
+                false => vec![Trace {
+                    id: section.id.to_owned(),
+                    start: section.start,
+                    end: section.end,
+                    inner: inner(section, resources),
+                }],
+            })
+            .collect()
+    }
+
+    Trace { id: trace.id.to_owned(), start: trace.start, end: trace.end, inner: inner(trace, resources) }
+}
+
+// FNV-1a, a fixed hash so keys stay the same between runs and builds (unlike the std hasher).
+struct Fnv(u64);
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_trace(&mut self, trace: &Trace) {
+        self.write(&(trace.id.len() as u64).to_le_bytes());
+        self.write(trace.id.as_bytes());
+        self.write(&trace.start.to_le_bytes());
+        self.write(&trace.end.to_le_bytes());
+        self.write(&(trace.inner.len() as u64).to_le_bytes());
+        for inner in &trace.inner {
+            self.write_trace(inner);
+        }
+    }
+}
+
+// Stable hash of a trace covering the id, start, end and recursively the inner traces, for deduplication and
+// cache keys. `Trace` is defined in srp and doesn't implement `Hash`. The hash is sensitive to the order of
+// `inner`, the same sections in another order give another key.
+pub fn trace_key(trace: &Trace) -> u64 {
+    let mut hasher = Fnv(0xcbf29ce484222325);
+    hasher.write_trace(trace);
+    hasher.0
+}
+
+// Structural equality of two traces, see `trace_key`.
+pub fn trace_eq(a: &Trace, b: &Trace) -> bool {
+    a.id == b.id
+        && a.start == b.start
+        && a.end == b.end
+        && a.inner.len() == b.inner.len()
+        && a.inner.iter().zip(&b.inner).all(|(a, b)| trace_eq(a, b))
+}
@@ -0,0 +1,83 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use srp::common::{Task, Trace};
+
+use crate::trace::validate_trace;
+
+// A task with its trace as stored in a trace file, so the scheduling analysis can be rerun without the elf.
+#[derive(Serialize, Deserialize)]
+struct TaskFile {
+    id: String,
+    prio: u8,
+    deadline: u32,
+    inter_arrival: u32,
+    // the WCET is the length of the trace, it is written out to be readable and checked on load.
+    wcet: u32,
+    trace: TraceFile,
+}
+
+// A trace as stored in json, srp doesn't derive serde for its types. Also used by `save_report`.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TraceFile {
+    id: String,
+    start: u32,
+    end: u32,
+    inner: Vec<TraceFile>,
+}
+
+impl TraceFile {
+    pub(crate) fn new(trace: &Trace) -> TraceFile {
+        TraceFile {
+            id: trace.id.to_owned(),
+            start: trace.start,
+            end: trace.end,
+            inner: trace.inner.iter().map(TraceFile::new).collect(),
+        }
+    }
+
+    pub(crate) fn into_trace(self) -> Trace {
+        Trace {
+            id: self.id,
+            start: self.start,
+            end: self.end,
+            inner: self.inner.into_iter().map(TraceFile::into_trace).collect(),
+        }
+    }
+}
+
+// Writes `task` and its trace to `path` as json.
+pub fn dump_trace(task: &Task, path: &Path) -> io::Result<()> {
+    let file = TaskFile {
+        id: task.id.to_owned(),
+        prio: task.prio,
+        deadline: task.deadline,
+        inter_arrival: task.inter_arrival,
+        wcet: task.trace.end.saturating_sub(task.trace.start),
+        trace: TraceFile::new(&task.trace),
+    };
+    fs::write(path, serde_json::to_string_pretty(&file)?)
+}
+
+// Reads a task written by `dump_trace`. A trace that isn't well formed (see `validate_trace`) is invalid
+// data, the analysis would count a section ending before it starts as billions of cycles.
+pub fn load_trace(path: &Path) -> io::Result<Task> {
+    let file: TaskFile = serde_json::from_slice(&fs::read(path)?)?;
+    if file.wcet != file.trace.end.saturating_sub(file.trace.start) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: the wcet doesn't match the length of the trace", path.display()),
+        ));
+    }
+    let trace = file.trace.into_trace();
+    validate_trace(&trace).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: broken trace, {e}", path.display()))
+    })?;
+    Ok(Task {
+        id: file.id,
+        prio: file.prio,
+        deadline: file.deadline,
+        inter_arrival: file.inter_arrival,
+        trace,
+    })
+}
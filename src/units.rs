@@ -0,0 +1,203 @@
+use std::{fmt, str::FromStr};
+
+// How a time that doesn't land on a whole cycle is turned into cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    // round in the direction that is safe for what the value is used as, see `TimeUse`.
+    #[default]
+    Conservative,
+    Ceil,
+    Floor,
+    Nearest,
+}
+
+impl FromStr for Rounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Rounding, String> {
+        match s {
+            "conservative" => Ok(Rounding::Conservative),
+            "ceil" => Ok(Rounding::Ceil),
+            "floor" => Ok(Rounding::Floor),
+            "nearest" => Ok(Rounding::Nearest),
+            _ => Err(format!(
+                "unknown rounding `{s}`, expected one of conservative, ceil, floor or nearest"
+            )),
+        }
+    }
+}
+
+// What a converted time is used as, decides which rounding direction is safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUse {
+    // deadlines and inter arrival times. A shorter limit only makes the analysis stricter so these are
+    // rounded down (floor), rounding them up could accept a task that misses its deadline in real time.
+    Limit,
+    // execution times and other demand on the cpu. Over estimating demand is safe so these are rounded up
+    // (ceil), which is also the safe direction for budgets that have to cover an execution time.
+    Demand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnitError {
+    // the number part could not be parsed.
+    InvalidNumber(String),
+    // the value has a unit that is not known.
+    UnknownUnit(String),
+    // the value is a time but no clock frequency was given to convert it.
+    MissingClock(String),
+    // the value does not fit in a u64 number of cycles.
+    Overflow(String),
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::InvalidNumber(v) => write!(f, "`{v}` is not a valid number"),
+            UnitError::UnknownUnit(v) => {
+                write!(f, "`{v}` has an unknown unit, expected one of cycles, s, ms, us or ns")
+            }
+            UnitError::MissingClock(v) => {
+                write!(f, "`{v}` is a time, a clock frequency is needed to convert it to cycles")
+            }
+            UnitError::Overflow(v) => write!(f, "`{v}` is too large"),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+// A duration given either in cycles (no suffix or `cycles`) or as a time with the suffix s, ms, us or ns.
+// Times are kept as an exact decimal, `mantissa / 10^decimals` of the unit, so no precision is lost before
+// the conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeValue {
+    Cycles(u64),
+    Time {
+        mantissa: u64,
+        decimals: u32,
+        // number of this unit in one second.
+        per_second: u64,
+        text: String,
+    },
+}
+
+impl FromStr for TimeValue {
+    type Err = UnitError;
+
+    fn from_str(s: &str) -> Result<TimeValue, UnitError> {
+        let text = s.trim();
+        let split = text
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(text.len());
+        let (number, unit) = text.split_at(split);
+
+        let per_second = match unit.trim() {
+            "" | "cycles" => {
+                return number
+                    .parse()
+                    .map(TimeValue::Cycles)
+                    .map_err(|_| UnitError::InvalidNumber(text.to_owned()))
+            }
+            "s" => 1,
+            "ms" => 1_000,
+            "us" => 1_000_000,
+            "ns" => 1_000_000_000,
+            _ => return Err(UnitError::UnknownUnit(text.to_owned())),
+        };
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(UnitError::InvalidNumber(text.to_owned()));
+        }
+        let digits = format!("{whole}{fraction}");
+        let mantissa = digits
+            .parse()
+            .map_err(|_| UnitError::InvalidNumber(text.to_owned()))?;
+
+        Ok(TimeValue::Time {
+            mantissa,
+            decimals: fraction.len() as u32,
+            per_second,
+            text: text.to_owned(),
+        })
+    }
+}
+
+impl TimeValue {
+    // Converts the value to cycles at `clock_hz`, values already in cycles are returned as they are.
+    pub fn to_cycles(
+        &self,
+        clock_hz: Option<u64>,
+        rounding: Rounding,
+        time_use: TimeUse,
+    ) -> Result<u64, UnitError> {
+        let (mantissa, decimals, per_second, text) = match self {
+            TimeValue::Cycles(cycles) => return Ok(*cycles),
+            TimeValue::Time {
+                mantissa,
+                decimals,
+                per_second,
+                text,
+            } => (*mantissa, *decimals, *per_second, text),
+        };
+        let clock_hz = clock_hz.ok_or_else(|| UnitError::MissingClock(text.to_owned()))?;
+
+        // cycles = mantissa / 10^decimals / per_second * clock_hz, done in integers to round exactly.
+        let overflow = || UnitError::Overflow(text.to_owned());
+        let numerator = mantissa as u128 * clock_hz as u128;
+        let denominator = 10u128
+            .checked_pow(decimals)
+            .and_then(|d| d.checked_mul(per_second as u128))
+            .ok_or_else(overflow)?;
+
+        let rounding = match (rounding, time_use) {
+            (Rounding::Conservative, TimeUse::Limit) => Rounding::Floor,
+            (Rounding::Conservative, TimeUse::Demand) => Rounding::Ceil,
+            (rounding, _) => rounding,
+        };
+        let cycles = match rounding {
+            Rounding::Floor => numerator / denominator,
+            Rounding::Nearest => (numerator + denominator / 2) / denominator,
+            Rounding::Ceil | Rounding::Conservative => numerator.div_ceil(denominator),
+        };
+
+        u64::try_from(cycles).map_err(|_| overflow())
+    }
+}
+
+// `cycles` of a clock at `from_hz` as cycles of a clock at `to_hz`, the same time at another frequency.
+// Rounded the safe way for `time_use`.
+pub fn rescale(cycles: u64, from_hz: u64, to_hz: u64, time_use: TimeUse) -> Result<u64, UnitError> {
+    let time = TimeValue::Time {
+        mantissa: cycles,
+        decimals: 0,
+        per_second: from_hz,
+        text: format!("{cycles} cycles at {from_hz} Hz"),
+    };
+    time.to_cycles(Some(to_hz), Rounding::Conservative, time_use)
+}
+
+// `cycles` at `clock_hz` in nanoseconds, rounded the safe way for `time_use`: up for execution and response
+// times, down for deadlines.
+pub fn cycles_to_ns(cycles: u64, clock_hz: u64, time_use: TimeUse) -> Result<u64, UnitError> {
+    rescale(cycles, clock_hz, 1_000_000_000, time_use)
+}
+
+// `ns` in the largest unit it is at least one of, exact, like `12.5us` or `1.000125ms`.
+pub fn format_ns(ns: u64) -> String {
+    let (per_unit, digits, unit) = match ns {
+        0..=999 => return format!("{ns}ns"),
+        1_000..=999_999 => (1_000, 3, "us"),
+        1_000_000..=999_999_999 => (1_000_000, 6, "ms"),
+        _ => (1_000_000_000, 9, "s"),
+    };
+    let whole = ns / per_unit;
+    match ns % per_unit {
+        0 => format!("{whole}{unit}"),
+        fraction => {
+            let fraction = format!("{fraction:0digits$}");
+            format!("{whole}.{}{unit}", fraction.trim_end_matches('0'))
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::interrupts::InterruptTable;
+
+// Cortex-M exception handlers as cortex-m-rt names them, with their exception number.
+const EXCEPTIONS: [(u8, &str); 9] = [
+    (2, "NonMaskableInt"),
+    (3, "HardFault"),
+    (4, "MemoryManagement"),
+    (5, "BusFault"),
+    (6, "UsageFault"),
+    (11, "SVCall"),
+    (12, "DebugMonitor"),
+    (14, "PendSV"),
+    (15, "SysTick"),
+];
+
+// cortex-m-rt points every handler the application doesn't define at this one.
+const DEFAULT_HANDLER: &str = "DefaultHandler";
+
+// A handler symbol found in the elf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vector {
+    pub kind: VectorKind,
+    pub name: String,
+    pub address: u32,
+    // the symbol is an alias of `DefaultHandler`, the application doesn't handle it.
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorKind {
+    // an interrupt of the chip, with its number in the interrupt table.
+    Interrupt(u8),
+    // a Cortex-M exception, with its exception number.
+    Exception(u8),
+}
+
+fn u16_at(elf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(elf.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn u32_at(elf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(elf.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+// The NUL terminated string at `offset` in the string table at `table`.
+fn string_at(elf: &[u8], table: usize, offset: usize) -> Option<String> {
+    let bytes = elf.get(table + offset..)?;
+    let end = bytes.iter().position(|b| *b == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+// `(name, address)` of every symbol in the symbol tables of a 32 bit little endian elf, which every Cortex-M
+// build is. Empty for a stripped elf, `None` if the file isn't such an elf or is cut short.
+fn symbols(elf: &[u8]) -> Option<Vec<(String, u32)>> {
+    if !elf.starts_with(b"\x7fELF") || elf.get(4) != Some(&1) || elf.get(5) != Some(&1) {
+        return None;
+    }
+    let section_headers = u32_at(elf, 0x20)? as usize;
+    let header_size = u16_at(elf, 0x2e)? as usize;
+    let sections = u16_at(elf, 0x30)? as usize;
+
+    let mut symbols = vec![];
+    for i in 0..sections {
+        let header = section_headers + i * header_size;
+        // SHT_SYMTAB, its string table is the section in `sh_link`.
+        if u32_at(elf, header + 4)? != 2 {
+            continue;
+        }
+        let offset = u32_at(elf, header + 16)? as usize;
+        let size = u32_at(elf, header + 20)? as usize;
+        let link = u32_at(elf, header + 24)? as usize;
+        let strings = u32_at(elf, section_headers + link * header_size + 16)? as usize;
+        for symbol in (offset..offset + size).step_by(16) {
+            let name = string_at(elf, strings, u32_at(elf, symbol)? as usize)?;
+            symbols.push((name, u32_at(elf, symbol + 4)?));
+        }
+    }
+    Some(symbols)
+}
+
+// The interrupt handlers of `table` and the exception handlers defined in `elf`, interrupts first in the
+// order of the table, without running symex. Symbols are matched by name, an interrupt missing here has no
+// handler in the elf under the name a task `interrupt` uses. `None` if `elf` can't be read as an elf.
+pub fn vector_symbols(elf: &[u8], table: &dyn InterruptTable) -> Option<Vec<Vector>> {
+    let symbols = symbols(elf)?;
+    let address = |name: &str| symbols.iter().find(|(s, _)| s == name).map(|(_, address)| *address);
+    let default = address(DEFAULT_HANDLER);
+
+    let interrupts = (0..=u8::MAX).filter_map(|i| Some((VectorKind::Interrupt(i), table.name(i)?)));
+    let exceptions = EXCEPTIONS.iter().map(|(n, name)| (VectorKind::Exception(*n), *name));
+    Some(
+        interrupts
+            .chain(exceptions)
+            .filter_map(|(kind, name)| {
+                let address = address(name)?;
+                Some(Vector {
+                    kind,
+                    name: name.to_owned(),
+                    address,
+                    default: Some(address) == default,
+                })
+            })
+            .collect(),
+    )
+}
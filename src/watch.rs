@@ -0,0 +1,66 @@
+use std::{
+    fs,
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+// How often the watched files are checked.
+const POLL: Duration = Duration::from_millis(200);
+// A change only counts once the files have been left alone this long, the linker writes an elf in several
+// steps.
+const SETTLE: Duration = Duration::from_millis(500);
+
+// Modification times of `paths`, `None` for a file that can't be read (e.g. while the linker replaces it).
+fn modified(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+// Decides when a change of the watched files is finished: a change starts when the modification times differ
+// from the last settled ones, and ends once they stay the same for `settle`.
+pub struct Debounce {
+    settled: Vec<Option<SystemTime>>,
+    pending: Option<(Vec<Option<SystemTime>>, Duration)>,
+    settle: Duration,
+}
+
+impl Debounce {
+    pub fn new(initial: Vec<Option<SystemTime>>, settle: Duration) -> Debounce {
+        Debounce {
+            settled: initial,
+            pending: None,
+            settle,
+        }
+    }
+
+    // Feeds the modification times seen `elapsed` after the previous ones, true when a change has settled.
+    pub fn update(&mut self, current: Vec<Option<SystemTime>>, elapsed: Duration) -> bool {
+        match &mut self.pending {
+            Some((times, quiet)) if *times == current => {
+                *quiet += elapsed;
+                if *quiet >= self.settle {
+                    self.settled = current;
+                    self.pending = None;
+                    return true;
+                }
+            }
+            _ if current != self.settled => self.pending = Some((current, Duration::ZERO)),
+            _ => self.pending = None,
+        }
+        false
+    }
+}
+
+// Blocks until one of `paths` has changed and the change has settled.
+pub fn wait_for_change(paths: &[PathBuf]) {
+    let mut debounce = Debounce::new(modified(paths), SETTLE);
+    loop {
+        thread::sleep(POLL);
+        if debounce.update(modified(paths), POLL) {
+            return;
+        }
+    }
+}
@@ -0,0 +1,8116 @@
+// End to end run of the analysis on a fixture elf, also serves as an example of using the library.
+//
+// The fixture is `test_bin/rtic_simple_resourse`, the rtic_simple_resourse example of armv6-m-examples built
+// for the RP2040. Its `IO_IRQ_BANK0` handler reads a GPIO and either returns early or takes this path:
+//
+//   10000238: str  r0, [r1]        @ NVIC ICER, masks interrupts 13 and 20 (0x102000), a resource each
+//   1000023a: ldr  r1, [pc, #20]
+//   1000023c: ldr  r2, [r1]
+//   1000023e: adds r2, r2, #1
+//   10000240: str  r2, [r1]
+//   10000242: ldr  r1, [pc, #16]
+//   10000244: str  r0, [r1]        @ NVIC ISER, unlocks them again
+//   10000246: bx   lr
+//
+// With the Cortex-M0+ timings (loads, stores, taken branches and bx 2 cycles, everything else 1) this path
+// takes 30 cycles and both resources are locked from cycle 15 to cycle 28.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use srp::common::{Task, TaskResult, Tasks, TasksResult, Trace};
+use symex::{
+    general_assembly::{state::GAState, Result as GAResult},
+    smt::DExpr,
+};
+
+use easy_poc::{
+    analysis::{
+        check_wcet_against_deadline, correlated, get_all_sets, run_analysis_streaming, task_confidence,
+        task_paths, wcet_only, worst_path, AnalysisError, AnalysisEvent, Confidence, PathStats, Server,
+        Warning, WarningKind, CYCLE_MODEL_CORE,
+    },
+    baseline::{check_baseline, load_baseline, BaselineCheck, BaselineTask},
+    breakdown::{breakdown_factor, sensitivity},
+    build::{default_elf, example_elf},
+    ceiling_check::{ceiling_violations, CeilingViolation},
+    chain::{Chain, ChainLatency},
+    cli::{
+        ascii_bar, at_clock, cyccnt_line, elf_files, fit_priorities, histogram, is_binary_report, only_tasks,
+        parse_ceiling, parse_chain, parse_correlation, parse_fit_task, parse_period, parse_reservation,
+        parse_server, parse_sweep, parse_tag, parse_trace_file, resource_matrix, schedulability_exit_code,
+        set_periods, sort_results, utilization_lines, variant_line, warning_errors, MatrixFormat, PriorityFit,
+        Sort,
+    },
+    combination_log::CombinationLog,
+    combinations::reservoir_sample,
+    create_task, discovered_resources, global_max_lock,
+    csv_report::{csv_report, resource_csv},
+    diff::{diff_reports, diff_sections, SectionDiff},
+    expected::check_expected,
+    find_worst,
+    hooks::{
+        basepri_lap, primask_lap, push_mask_laps, stack_lap, NvicAddresses, UserHooks, CYCCNT_LABEL,
+        SPINLOCK_LABEL, SYMBOLIC_LABEL,
+    },
+    html::{html_report, trace_svg},
+    interrupts::{
+        check_interrupt, check_interrupts, interrupt_list, irq_from_bit_vector, mask_labels, resource_ids,
+        resource_name, Chip,
+    },
+    json_report::json_report,
+    laps::{record_laps, PathLaps},
+    lock_fraction,
+    lock_order::{lock_order_hazards, LockOrderHazard},
+    manifest::Manifest,
+    markdown::markdown_report,
+    rta::{self, PreemptionModel, Reservation, RtaConfig},
+    run_analysis,
+    saved_report::{load_report, load_report_bin, save_report, save_report_bin, BINARY_VERSION},
+    schedulability::{failures, schedulability, Schedulability, TaskSchedulability},
+    self_test::{self_test, SELF_TEST_ELF},
+    task::{
+        collapse_paths, deadline_monotonic, preemption_levels, resource_users, section_laps, stack_required,
+        validate_priorities, EqualPriority, EXCEPTION_FRAME,
+    },
+    task_list::{load_task_list, ConfigError},
+    timeline::{chrome_trace, perfetto_trace},
+    trace::{trace_from_laps, validate_trace, TraceBuilder, TraceIntegrityError, TraceIssue},
+    trace_eq, trace_key,
+    trace_file::{dump_trace, load_trace},
+    units::{cycles_to_ns, format_ns, Rounding, TimeUse, TimeValue, UnitError},
+    vectors::{vector_symbols, Vector, VectorKind},
+    watch::Debounce,
+    worst_combinations, Activation, AnalysisConfig, AnalysisReport, CsMechanism, InputTask, TaskFilter,
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test_bin/rtic_simple_resourse");
+
+#[test]
+fn simple_resource_pipeline() {
+    let config = AnalysisConfig {
+        elf: FIXTURE.to_owned(),
+        tasks: vec![InputTask {
+            name: "gpio_handler".to_owned(),
+            interrupt: "IO_IRQ_BANK0".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config).unwrap();
+
+    // an early return path and the path through the critical section.
+    assert_eq!(report.path_counts, vec![("gpio_handler".to_owned(), 2)]);
+    assert_eq!(report.combination_count, 2);
+
+    let worst = &report.worst.0[0];
+    assert_eq!(worst.wcet, 30);
+    assert_eq!(worst.task.trace.start, 0);
+    assert_eq!(worst.task.trace.end, 30);
+
+    assert_eq!(worst.task.trace.inner.len(), 1);
+    let section = &worst.task.trace.inner[0];
+    assert_eq!(section.id, 0x2000.to_string());
+    assert_eq!((section.start, section.end), (15, 28));
+    assert_eq!(section.end - section.start, 13);
+    // opened by the same write, so nested in it.
+    assert_eq!(section.inner.len(), 1);
+    let inner = &section.inner[0];
+    assert_eq!((inner.id.as_str(), inner.start, inner.end), ("1048576", 15, 28));
+    assert!(inner.inner.is_empty());
+}
+
+#[test]
+fn task_elf_overrides_the_application_elf() {
+    let mut task = InputTask {
+        name: "gpio_handler".to_owned(),
+        interrupt: "IO_IRQ_BANK0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    assert_eq!(task.elf("app.elf"), "app.elf");
+
+    task.elf = Some("handler.elf".to_owned());
+    assert_eq!(task.elf("app.elf"), "handler.elf");
+}
+
+#[test]
+fn all_unknown_interrupts_are_reported() {
+    let task = |name: &str, interrupt: &str| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let tasks = vec![
+        task("gpio_handler", "IO_IRQ_BANK0"),
+        task("timer_handler", "TIMER_IRQ_9"),
+        task("uart_handler", "UART0_IRQQ"),
+    ];
+
+    let unknown = check_interrupts(&tasks, Chip::Rp2040.table());
+
+    let names: Vec<&str> = unknown.iter().map(|(task, _)| task.as_str()).collect();
+    assert_eq!(names, vec!["timer_handler", "uart_handler"]);
+    assert!(unknown[1].1.contains("did you mean `UART0_IRQ`?"));
+}
+
+#[test]
+fn worst_results_are_matched_by_task_id() {
+    let result = |id: &str, response_time: Option<u32>, wcet: u32| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 1000,
+            inter_arrival: 1000,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: wcet,
+                inner: vec![],
+            },
+        },
+        response_time,
+        wcet,
+        blocking: 0,
+        interference: 0,
+    };
+    // the combinations list the tasks in different orders and the last one leaves a task out.
+    let combinations = vec![
+        TasksResult(vec![result("a", Some(10), 10), result("b", Some(30), 20)]),
+        TasksResult(vec![result("b", Some(40), 25), result("a", Some(12), 12)]),
+        TasksResult(vec![result("a", None, 15)]),
+    ];
+
+    let worst = find_worst(combinations);
+
+    let worst: Vec<(&str, Option<u32>, u32)> =
+        worst.0.iter().map(|r| (r.task.id.as_str(), r.response_time, r.wcet)).collect();
+    assert_eq!(worst, vec![("a", None, 15), ("b", Some(40), 25)]);
+}
+
+#[test]
+fn worst_result_keeps_every_field() {
+    let section = Trace {
+        id: "resource".to_owned(),
+        start: 3,
+        end: 9,
+        inner: vec![],
+    };
+    let result = TaskResult {
+        task: Task {
+            id: "a".to_owned(),
+            prio: 3,
+            deadline: 500,
+            inter_arrival: 800,
+            trace: Trace {
+                id: "a".to_owned(),
+                start: 0,
+                end: 20,
+                inner: vec![section],
+            },
+        },
+        response_time: Some(47),
+        wcet: 20,
+        blocking: 11,
+        interference: 16,
+    };
+
+    let worst = find_worst(vec![TasksResult(vec![result])]);
+
+    let copy = &worst.0[0];
+    assert_eq!((copy.response_time, copy.wcet, copy.blocking, copy.interference), (Some(47), 20, 11, 16));
+    let task = &copy.task;
+    assert_eq!((task.id.as_str(), task.prio, task.deadline, task.inter_arrival), ("a", 3, 500, 800));
+    assert_eq!((task.trace.id.as_str(), task.trace.start, task.trace.end), ("a", 0, 20));
+    let section = &task.trace.inner[0];
+    assert_eq!((section.id.as_str(), section.start, section.end), ("resource", 3, 9));
+}
+
+#[test]
+fn traces_span_the_whole_path() {
+    // a path without critical sections.
+    let trace = trace_from_laps(0, 30, &[], "task".to_owned()).unwrap();
+    assert_eq!((trace.start, trace.end), (0, 30));
+    assert!(trace.inner.is_empty());
+
+    // a path ending inside a critical section keeps the section, closed at the end of the path.
+    let laps = vec![(4, "1".to_owned()), (8, "1".to_owned()), (12, "2".to_owned())];
+    let e = trace_from_laps(0, 30, &laps, "task".to_owned()).unwrap_err();
+    assert_eq!(
+        e.issues,
+        vec![TraceIssue::Unclosed {
+            id: "2".to_owned(),
+            cycle: 12
+        }]
+    );
+    let sections: Vec<(&str, u32, u32)> =
+        e.trace.inner.iter().map(|s| (s.id.as_str(), s.start, s.end)).collect();
+    assert_eq!(sections, vec![("1", 4, 8), ("2", 12, 30)]);
+    assert_eq!((e.trace.start, e.trace.end), (0, 30));
+}
+
+#[test]
+fn nested_locks_nest_their_sections() {
+    let laps = |laps: &[(usize, &str)]| -> Vec<(usize, String)> {
+        laps.iter().map(|(cycle, label)| (*cycle, label.to_string())).collect()
+    };
+    let spans = |trace: &Trace| (trace.id.to_owned(), trace.start, trace.end, trace.inner.len());
+
+    // lock A, lock B, unlock B, unlock A.
+    let two = laps(&[(5, "A"), (10, "B"), (20, "B"), (30, "A")]);
+    let trace = trace_from_laps(0, 40, &two, "task".to_owned()).unwrap();
+    assert_eq!(trace.inner.len(), 1);
+    let a = &trace.inner[0];
+    assert_eq!(spans(a), ("A".to_owned(), 5, 30, 1));
+    assert_eq!(spans(&a.inner[0]), ("B".to_owned(), 10, 20, 0));
+
+    // and C inside B, with a second section on A after B is released.
+    let three = laps(&[
+        (5, "A"),
+        (10, "B"),
+        (12, "C"),
+        (15, "C"),
+        (20, "B"),
+        (22, "C"),
+        (25, "C"),
+        (30, "A"),
+    ]);
+    let trace = trace_from_laps(0, 40, &three, "task".to_owned()).unwrap();
+    assert_eq!(trace.inner.len(), 1);
+    let a = &trace.inner[0];
+    assert_eq!(spans(a), ("A".to_owned(), 5, 30, 2));
+    let b = &a.inner[0];
+    assert_eq!(spans(b), ("B".to_owned(), 10, 20, 1));
+    assert_eq!(spans(&b.inner[0]), ("C".to_owned(), 12, 15, 0));
+    assert_eq!(spans(&a.inner[1]), ("C".to_owned(), 22, 25, 0));
+}
+
+#[test]
+fn a_mask_of_two_interrupts_locks_two_resources() {
+    assert_eq!(mask_labels(0x6), vec!["2".to_owned(), "4".to_owned()]);
+    assert_eq!(resource_ids("0x102000"), vec!["8192".to_owned(), "1048576".to_owned()]);
+    assert_eq!(resource_ids("spinlock3"), vec!["spinlock3".to_owned()]);
+
+    // one write masks both interrupts, they are unmasked one by one. The sections overlap, each is measured
+    // on its own without an issue.
+    let mut laps: Vec<(usize, String)> = mask_labels(0x6).into_iter().map(|l| (5, l)).collect();
+    laps.extend([(9, "2".to_owned()), (14, "4".to_owned())]);
+    let trace = trace_from_laps(0, 20, &laps, "task".to_owned()).unwrap();
+    assert_eq!(trace.inner.len(), 1);
+    let outer = &trace.inner[0];
+    assert_eq!((outer.id.as_str(), outer.start, outer.end), ("4", 5, 14));
+    let inner = &outer.inner[0];
+    assert_eq!((inner.id.as_str(), inner.start, inner.end), ("2", 5, 9));
+
+    // unmasked together by one write, in any order.
+    let laps = vec![(5, "2".to_owned()), (5, "4".to_owned()), (9, "2".to_owned()), (9, "4".to_owned())];
+    let trace = trace_from_laps(0, 20, &laps, "task".to_owned()).unwrap();
+    assert_eq!(trace.inner[0].id, "4");
+    assert_eq!((trace.inner[0].inner[0].start, trace.inner[0].inner[0].end), (5, 9));
+
+    // a section around the write still reports an interleaved unlock.
+    let laps = vec![(2, "1".to_owned()), (5, "2".to_owned()), (8, "1".to_owned()), (9, "2".to_owned())];
+    let e = trace_from_laps(0, 20, &laps, "task".to_owned()).unwrap_err();
+    assert_eq!(e.issues, vec![TraceIssue::Interleaved { id: "1".to_owned(), cycle: 8 }]);
+}
+
+#[test]
+fn an_overlapping_nested_lock_keeps_the_outer_section_open() {
+    // an outer lock masking interrupt 13, an inner one masking 13 and 20 (RTIC nesting a resource with a
+    // higher ceiling), unlocked in the reverse order.
+    let mut laps = vec![];
+    push_mask_laps(&mut laps, 5, 0x2000, false);
+    push_mask_laps(&mut laps, 10, 0x102000, false);
+    push_mask_laps(&mut laps, 15, 0x102000, true);
+    push_mask_laps(&mut laps, 20, 0x2000, true);
+    assert_eq!(
+        laps,
+        vec![
+            (5, "8192".to_owned()),
+            (10, "<nested lock> 8192".to_owned()),
+            (10, "1048576".to_owned()),
+            (15, "1048576".to_owned()),
+            (15, "<nested unlock> 8192".to_owned()),
+            (20, "8192".to_owned()),
+        ]
+    );
+
+    let path = PathLaps {
+        max_cycles: 30,
+        cycle_laps: laps,
+    };
+    let trace = trace_from_laps(0, 30, &section_laps(&path), "task".to_owned()).unwrap();
+    assert_eq!(trace.inner.len(), 1);
+    let outer = &trace.inner[0];
+    assert_eq!((outer.id.as_str(), outer.start, outer.end), ("8192", 5, 20));
+    let inner: Vec<(&str, u32, u32)> = outer.inner.iter().map(|s| (s.id.as_str(), s.start, s.end)).collect();
+    assert_eq!(inner, vec![("1048576", 10, 15)]);
+}
+
+#[test]
+fn a_nested_interrupt_disable_keeps_one_primask_section() {
+    // `cpsid` at 5, a nested critical section disabling again at 8 and restoring the 1 it saved at 12, and the
+    // outer `cpsie` at 20 closing after its 1 cycle.
+    let writes = [(0, 1, 5), (1, 1, 8), (1, 1, 12), (1, 0, 20)];
+    let laps: Vec<(usize, String)> = writes
+        .iter()
+        .filter_map(|&(old, new, cycle)| primask_lap(old, new, cycle, cycle + 1))
+        .collect();
+    assert_eq!(laps, vec![(5, "primask".to_owned()), (21, "primask".to_owned())]);
+
+    let trace = trace_from_laps(0, 30, &laps, "task".to_owned()).unwrap();
+    let sections: Vec<(&str, u32, u32)> =
+        trace.inner.iter().map(|s| (s.id.as_str(), s.start, s.end)).collect();
+    assert_eq!(sections, vec![("primask", 5, 21)]);
+
+    // enabling with interrupts already enabled opens nothing.
+    assert_eq!(primask_lap(0, 0, 3, 4), None);
+}
+
+#[test]
+fn a_task_on_the_same_level_blocks_for_its_wcet() {
+    // two tasks sharing a vector, neither preempts the other.
+    let task = |id: &str, wcet: u32| Task {
+        id: id.to_owned(),
+        prio: 1,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("a", 30), task("b", 50)]);
+
+    let rta = rta::response_time(&tasks, &RtaConfig::default());
+    let results: Vec<(&str, u32, u32, Option<u32>)> = rta
+        .results
+        .0
+        .iter()
+        .map(|r| (r.task.id.as_str(), r.blocking, r.interference, r.response_time))
+        .collect();
+    assert_eq!(results, vec![("a", 50, 0, Some(80)), ("b", 30, 0, Some(80))]);
+
+    // in a FIFO group the other job interferes instead.
+    let fifo = RtaConfig {
+        equal_priority: EqualPriority::Fifo,
+        ..RtaConfig::default()
+    };
+    let rta = rta::response_time(&tasks, &fifo);
+    let a = &rta.results.0[0];
+    assert_eq!((a.blocking, a.interference, a.response_time), (0, 50, Some(80)));
+}
+
+#[test]
+fn the_cold_start_penalty_is_paid_by_every_interfering_task() {
+    let task = |id: &str, prio: u8| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: 10,
+            inner: vec![],
+        },
+    };
+    // `low` is preempted by `high` and, in the FIFO group, waits for `peer`.
+    let tasks = Tasks(vec![task("low", 1), task("peer", 1), task("high", 2)]);
+    let config = RtaConfig {
+        cold_start_penalty: 100,
+        equal_priority: EqualPriority::Fifo,
+        ..RtaConfig::default()
+    };
+
+    let rta = rta::response_time(&tasks, &config);
+    let response_times: Vec<Option<u32>> = rta.results.0.iter().map(|r| r.response_time).collect();
+    // the own job and the first of each of the two others pay it: 3 * 10 + 3 * 100.
+    assert_eq!(response_times[0], Some(330));
+    // `high` has nothing interfering, it only pays for its own job.
+    assert_eq!(response_times[2], Some(110));
+}
+
+#[test]
+fn a_wcet_over_the_deadline_reports_the_deadline_it_was_compared_with() {
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 100,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let path = |max_cycles: usize| PathLaps {
+        max_cycles,
+        cycle_laps: vec![],
+    };
+
+    let over = check_wcet_against_deadline(&task, &[path(80), path(150)]).unwrap();
+    assert_eq!((over.task.as_str(), over.wcet, over.deadline), ("task", 150, 100));
+    assert!(check_wcet_against_deadline(&task, &[path(100)]).is_none());
+
+    // a background task is analysed without a deadline, its configured one isn't compared with.
+    let background = InputTask {
+        background: true,
+        ..task
+    };
+    assert!(check_wcet_against_deadline(&background, &[path(150)]).is_none());
+}
+
+#[test]
+fn sections_below_the_ceiling_are_reported() {
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let task = |id: &str, prio: u8, resources: &[&str]| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: 20,
+            inner: resources
+                .iter()
+                .map(|r| Trace {
+                    id: r.to_string(),
+                    start: 5,
+                    end: 10,
+                    inner: vec![],
+                })
+                .collect(),
+        },
+    };
+    let inputs = vec![
+        input("low", "TIMER_IRQ_0", 1),
+        input("mid", "TIMER_IRQ_1", 2),
+        input("high", "TIMER_IRQ_2", 3),
+    ];
+    // both resources are shared with `high`, only the mask 0x6 also masks `mid`.
+    let tasks = vec![task("low", 1, &["4", "6"]), task("mid", 2, &[]), task("high", 3, &["4", "6"])];
+    let ceilings = BTreeMap::from([("4".to_owned(), 3), ("6".to_owned(), 3)]);
+
+    let violations = ceiling_violations(&tasks, &inputs, &ceilings, Chip::Rp2040.table());
+
+    assert_eq!(
+        violations,
+        vec![CeilingViolation {
+            task: "low".to_owned(),
+            resource: "4".to_owned(),
+            ceiling: 3,
+            unmasked: vec!["mid".to_owned()],
+        }]
+    );
+}
+
+#[test]
+fn all_sets_vary_the_last_set_fastest() {
+    let sets = vec![vec![1, 2], vec![3], vec![4, 5]];
+    assert_eq!(get_all_sets(&sets), vec![vec![4, 3, 1], vec![5, 3, 1], vec![4, 3, 2], vec![5, 3, 2]]);
+    assert_eq!(get_all_sets::<u32>(&[]), vec![Vec::<u32>::new()]);
+    assert!(get_all_sets(&[vec![1], vec![]]).is_empty());
+}
+
+#[test]
+fn stack_is_summed_over_the_levels_of_a_core() {
+    let task = |name: &str, interrupt: &str, priority: u32, core: Option<u8>| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let tasks = vec![
+        InputTask {
+            background: true,
+            ..task("idle", "idle", 0, None)
+        },
+        task("a", "TIMER_IRQ_0", 1, None),
+        task("b", "TIMER_IRQ_1", 1, None),
+        task("c", "TIMER_IRQ_2", 2, None),
+        task("d", "SIO_IRQ_PROC1", 1, Some(1)),
+    ];
+    let max_stack: Vec<(String, u64)> = [("idle", 200), ("a", 40), ("b", 64), ("c", 16), ("d", 8)]
+        .iter()
+        .map(|(name, stack)| (name.to_string(), *stack))
+        .collect();
+
+    let required = stack_required(&tasks, None, &max_stack);
+
+    // `a` and `b` share a level, only the deeper one counts.
+    let frame = EXCEPTION_FRAME;
+    assert_eq!(required, BTreeMap::from([(0, 200 + 64 + frame + 16 + frame), (1, 8 + frame)]));
+    assert_eq!(stack_lap("<stack> 1000 936"), Some((1000, 936)));
+    assert_eq!(stack_lap("4"), None);
+}
+
+#[test]
+fn chains_miss_their_deadline_above_it_or_unbounded() {
+    let result = |id: &str, response_time: Option<u32>| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 1000,
+            inter_arrival: 1000,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: 10,
+                inner: vec![],
+            },
+        },
+        response_time,
+        wcet: 10,
+        blocking: 0,
+        interference: 0,
+    };
+    let results = TasksResult(vec![result("a", Some(100)), result("b", Some(200)), result("c", None)]);
+    let chain = |tasks: &[&str], deadline: Option<u32>| Chain {
+        name: "chain".to_owned(),
+        tasks: tasks.iter().map(|t| t.to_string()).collect(),
+        activation_delay: 5,
+        deadline,
+    };
+
+    let latency = ChainLatency::new(&results, &chain(&["a", "b"], Some(305)));
+    assert_eq!(latency.latency, Some(305));
+    assert!(!latency.missed());
+    assert!(ChainLatency::new(&results, &chain(&["a", "b"], Some(304))).missed());
+    assert!(ChainLatency::new(&results, &chain(&["a", "c"], Some(1000))).missed());
+    assert!(!ChainLatency::new(&results, &chain(&["a", "c"], None)).missed());
+}
+
+#[test]
+fn a_configuration_error_is_returned() {
+    let config = AnalysisConfig {
+        elf: FIXTURE.to_owned(),
+        tasks: vec![InputTask {
+            name: "gpio_handler".to_owned(),
+            interrupt: "IO_IRQ_BANK0".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![Chain {
+            name: "chain".to_owned(),
+            tasks: vec!["gpio_handler".to_owned(), "uart_handler".to_owned()],
+            activation_delay: 0,
+            deadline: None,
+        }],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    // checked before symex runs, so the fixture is never analysed.
+    let error = run_analysis(&config).err();
+    assert_eq!(error, Some(AnalysisError::Config("chain chain: no task named uart_handler".to_owned())));
+}
+
+#[test]
+fn a_warning_is_an_error_in_strict_mode() {
+    let config = AnalysisConfig {
+        elf: "missing.elf".to_owned(),
+        tasks: vec![InputTask {
+            name: "timer".to_owned(),
+            interrupt: "TIM2".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        // never loaded, the warning comes first.
+        trace_files: vec![("timer".to_owned(), "timer.json".into())],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: true,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Stm32f4,
+    };
+
+    // the Cortex-M4 of the stm32f4 doesn't have the timings symex counts with.
+    match run_analysis(&config) {
+        Err(AnalysisError::Strict(warning)) => assert_eq!(warning.kind, WarningKind::CycleModel),
+        other => panic!("expected a strict mode error, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn a_task_symex_fails_on_is_named_in_the_error() {
+    let config = AnalysisConfig {
+        elf: "missing.elf".to_owned(),
+        tasks: vec![InputTask {
+            name: "gpio_handler".to_owned(),
+            interrupt: "IO_IRQ_BANK0".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: Some("missing-task.elf".to_owned()),
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    match run_analysis(&config) {
+        Err(AnalysisError::Task(task, e)) => {
+            assert_eq!(task, "gpio_handler");
+            assert!(matches!(*e, AnalysisError::ElfRead(elf, _) if elf == "missing-task.elf"));
+        }
+        other => panic!("expected the task to fail, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn a_server_above_level_255_is_an_error() {
+    let config = AnalysisConfig {
+        elf: "missing.elf".to_owned(),
+        tasks: vec![InputTask {
+            name: "top".to_owned(),
+            interrupt: "TIMER_IRQ_0".to_owned(),
+            priority: 255,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        // above every task, so on level 256.
+        servers: vec![Server {
+            name: "server0".to_owned(),
+            budget: 10,
+            period: 100,
+            priority: None,
+        }],
+        // never loaded, the server errs first.
+        trace_files: vec![("top".to_owned(), "top.json".into())],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    let error = run_analysis(&config).err().map(|e| e.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("server server0 is on preemption level 256, levels go up to 255, give it a lower priority")
+    );
+
+    let config = AnalysisConfig {
+        servers: vec![Server {
+            priority: Some(300),
+            ..config.servers[0].clone()
+        }],
+        ..config
+    };
+    let error = run_analysis(&config).err();
+    assert_eq!(
+        error,
+        Some(AnalysisError::Config("server server0 has priority 300, priorities go up to 255".to_owned()))
+    );
+}
+
+#[test]
+fn a_malformed_trace_file_is_rejected() {
+    let dir = env::temp_dir().join(format!("easy-poc-malformed-trace-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("task.json");
+    // a section ending before it starts, as edited by hand.
+    let task = Task {
+        id: "task".to_owned(),
+        prio: 1,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: "task".to_owned(),
+            start: 0,
+            end: 40,
+            inner: vec![Trace {
+                id: "1".to_owned(),
+                start: 30,
+                end: 10,
+                inner: vec![],
+            }],
+        },
+    };
+    dump_trace(&task, &path).unwrap();
+
+    let error = load_trace(&path).err();
+    fs::remove_dir_all(&dir).unwrap();
+    let error = error.unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("broken trace"));
+}
+
+#[test]
+fn a_symbolic_mask_fails_with_require_concrete_locks() {
+    let dir = env::temp_dir().join(format!("easy-poc-symbolic-lock-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // the hooks label a write of a value that isn't constant with `SYMBOLIC_LABEL`, here on the second path.
+    let paths = vec![
+        PathLaps {
+            max_cycles: 30,
+            cycle_laps: vec![(5, "8192".to_owned()), (20, "8192".to_owned())],
+        },
+        PathLaps {
+            max_cycles: 25,
+            cycle_laps: vec![(7, SYMBOLIC_LABEL.to_owned()), (18, "8192".to_owned())],
+        },
+    ];
+    record_laps(&dir, "task", &paths).unwrap();
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let confidence = task_confidence(&task, &paths);
+    assert_eq!(confidence.reasons, vec!["symbolic critical section value on 1 of 2 paths".to_owned()]);
+
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: true,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let error = run_analysis(&config).err();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        error,
+        Some(AnalysisError::SymbolicLock(
+            "task task: symbolic critical section value at cycle 7 of path 1, make the locked value concrete \
+             or analyse without --require-concrete-locks"
+                .to_owned()
+        ))
+    );
+}
+
+#[test]
+fn saved_reports_load_back_unchanged() {
+    let dir = env::temp_dir().join(format!("easy-poc-saved-report-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    let task = |id: &str, prio: u8, trace: Trace| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace,
+    };
+    // `low` nests a second resource in the one it shares with `high`.
+    let nested = section("1", 5, 30, vec![section("2", 10, 20, vec![])]);
+    let shared = section("1", 3, 8, vec![]);
+    dump_trace(&task("low", 1, section("low", 0, 40, vec![nested])), &dir.join("low.json")).unwrap();
+    dump_trace(&task("high", 2, section("high", 0, 15, vec![shared])), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config).unwrap();
+    let path = dir.join("report.json");
+    save_report(&report, &path).unwrap();
+    let loaded = load_report(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&report).unwrap());
+    assert_eq!(loaded.worst.0.len(), 2);
+    for (loaded, original) in loaded.worst.0.iter().zip(&report.worst.0) {
+        assert!(trace_eq(&loaded.task.trace, &original.task.trace));
+        assert_eq!(
+            (loaded.wcet, loaded.blocking, loaded.interference, loaded.response_time),
+            (original.wcet, original.blocking, original.interference, original.response_time)
+        );
+    }
+    let low = &loaded.worst.0[0].task.trace;
+    assert_eq!((low.inner[0].id.as_str(), low.inner[0].inner[0].id.as_str()), ("1", "2"));
+}
+
+#[test]
+fn binary_reports_load_back_unchanged() {
+    let dir = env::temp_dir().join(format!("easy-poc-binary-report-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    let task = |id: &str, prio: u8, trace: Trace| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace,
+    };
+    // `low` nests a second resource in the one it shares with `high`.
+    let nested = section("1", 5, 30, vec![section("2", 10, 20, vec![])]);
+    let shared = section("1", 3, 8, vec![]);
+    dump_trace(&task("low", 1, section("low", 0, 40, vec![nested])), &dir.join("low.json")).unwrap();
+    dump_trace(&task("high", 2, section("high", 0, 15, vec![shared])), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config).unwrap();
+    let path = dir.join("report.bin");
+    save_report_bin(&report, &path).unwrap();
+    let loaded = load_report_bin(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&report).unwrap());
+    assert_eq!(loaded.worst.0.len(), 2);
+    for (loaded, original) in loaded.worst.0.iter().zip(&report.worst.0) {
+        assert!(trace_eq(&loaded.task.trace, &original.task.trace));
+        assert_eq!(
+            (loaded.wcet, loaded.blocking, loaded.interference, loaded.response_time),
+            (original.wcet, original.blocking, original.interference, original.response_time)
+        );
+    }
+    let low = &loaded.worst.0[0].task.trace;
+    assert_eq!((low.inner[0].id.as_str(), low.inner[0].inner[0].id.as_str()), ("1", "2"));
+}
+
+#[test]
+fn a_binary_report_of_another_version_is_rejected() {
+    let dir = env::temp_dir().join(format!("easy-poc-binary-version-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("report.bin");
+    let mut bytes = b"EPOCRPT\0".to_vec();
+    bytes.extend((BINARY_VERSION + 1).to_le_bytes());
+    fs::write(&path, bytes).unwrap();
+    let other_version = load_report_bin(&path).err();
+    fs::write(&path, b"{\"worst\": []}").unwrap();
+    let json = load_report_bin(&path).err();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let version = BINARY_VERSION + 1;
+    let expected = format!(
+        "{} is a binary report of version {version}, this version reads {BINARY_VERSION}",
+        path.display()
+    );
+    assert_eq!(other_version, Some(expected));
+    assert_eq!(json, Some(format!("{} is not a binary report", path.display())));
+}
+
+#[test]
+fn a_wcet_above_the_inter_arrival_time_warns() {
+    let dir = env::temp_dir().join(format!("easy-poc-overload-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let task = |id: &str, prio: u8, wcet: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    dump_trace(&task("slow", 1, 60), &dir.join("slow.json")).unwrap();
+    dump_trace(&task("fast", 2, 20), &dir.join("fast.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, period: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: period,
+        activation: Activation::Periodic { period },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // `slow` runs for 60 cycles every 50.
+        tasks: vec![input("slow", "TIMER_IRQ_0", 1, 50), input("fast", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("slow".to_owned(), dir.join("slow.json")),
+            ("fast".to_owned(), dir.join("fast.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let overloads: Vec<&Warning> =
+        report.warnings.iter().filter(|w| w.kind == WarningKind::WcetOverInterArrival).collect();
+    assert_eq!(overloads.len(), 1);
+    assert_eq!(overloads[0].task.as_deref(), Some("slow"));
+    assert!(overloads[0].message.contains("WCET 60 cycles"));
+}
+
+#[test]
+fn comparing_two_builds_gives_wcet_and_section_changes() {
+    let dir = env::temp_dir().join(format!("easy-poc-compare-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let task = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    dump_trace(&task("low", 40, vec![section("1", 5, 15)]), &dir.join("low-release.json")).unwrap();
+    // the debug build holds the resource longer and locks another one.
+    let sections = vec![section("1", 5, 25), section("2", 30, 35)];
+    dump_trace(&task("low", 50, sections), &dir.join("low-debug.json")).unwrap();
+    dump_trace(&task("high", 20, vec![]), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let release = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low-release.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let a = run_analysis(&release).unwrap();
+    let debug = AnalysisConfig {
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low-debug.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        ..release
+    };
+    let b = run_analysis(&debug).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let tasks = diff_reports(&a, &b);
+    assert_eq!((tasks[0].task.as_str(), tasks[0].wcet, tasks[0].wcet_percent()), ("low", 10, Some(25.0)));
+    assert_eq!((tasks[1].task.as_str(), tasks[1].wcet), ("high", 0));
+    let sections = diff_sections(&a, &b);
+    let section = |resource: &str, a: Option<u32>, b: Option<u32>| SectionDiff {
+        task: "low".to_owned(),
+        resource: resource.to_owned(),
+        a,
+        b,
+    };
+    assert_eq!(sections, vec![section("1", Some(10), Some(20)), section("2", None, Some(5))]);
+    assert!(sections.iter().all(|s| s.grew()));
+}
+
+#[test]
+fn every_combination_is_a_line_of_the_jsonl_log() {
+    let path = env::temp_dir().join(format!("easy-poc-combinations-{}.jsonl", std::process::id()));
+    let result = |id: &str, response_time: Option<u32>| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 100,
+            inter_arrival: 100,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: 10,
+                inner: vec![],
+            },
+        },
+        response_time,
+        wcet: 10,
+        blocking: 0,
+        interference: 0,
+    };
+
+    let mut log = CombinationLog::create(&path).unwrap();
+    for i in 0..3 {
+        let paths = vec![("a".to_owned(), i), ("b".to_owned(), 0)];
+        let results = TasksResult(vec![result("a", Some(10 + i as u32)), result("b", None)]);
+        log.record(i, &paths, 0.2, &results).unwrap();
+    }
+    drop(log);
+    let text = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let lines: Vec<serde_json::Value> =
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2]["combination"], 2);
+    assert_eq!(lines[2]["paths"][0]["path"], 2);
+    assert_eq!(lines[2]["response_times"][0]["response_time"], 12);
+    assert!(lines[2]["response_times"][1]["response_time"].is_null());
+}
+
+#[test]
+fn tasks_differing_in_subpriority_share_a_level_and_dont_interfere() {
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let tasks = vec![
+        input("a", "TIMER_IRQ_0", 4),
+        input("b", "TIMER_IRQ_1", 5),
+        input("c", "TIMER_IRQ_2", 6),
+    ];
+    // PRIGROUP 0 leaves the lowest bit as subpriority, 4 and 5 both preempt at 2.
+    let levels = preemption_levels(&tasks, Some(0));
+    assert_eq!(levels, vec![2, 2, 3]);
+    assert_eq!(preemption_levels(&tasks, None), vec![4, 5, 6]);
+
+    let task = |id: &str, level: u32| Task {
+        id: id.to_owned(),
+        prio: level as u8,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: 10,
+            inner: vec![],
+        },
+    };
+    let set = Tasks(tasks.iter().zip(&levels).map(|(t, level)| task(&t.name, *level)).collect());
+    let rta = rta::response_time(&set, &RtaConfig::default());
+    let interferers: Vec<Vec<&str>> =
+        rta.interferers.iter().map(|i| i.iter().map(|i| i.task.as_str()).collect()).collect();
+    assert_eq!(interferers, vec![vec!["c"], vec!["c"], vec![]]);
+}
+
+#[test]
+fn the_rp2040_interrupt_list_has_io_irq_bank0_at_13() {
+    let list = interrupt_list(Chip::Rp2040.table());
+    assert_eq!(list.len(), 26);
+    assert!(list.contains(&(13, "IO_IRQ_BANK0")));
+    assert_eq!(list.first(), Some(&(0, "TIMER_IRQ_0")));
+    assert_eq!(list.last(), Some(&(25, "RTC_IRQ")));
+}
+
+#[test]
+fn a_reserved_mask_bit_is_reported_not_panicked() {
+    // bit 13 is IO_IRQ_BANK0, 26 and 31 are past the last RP2040 interrupt
+    let (irqs, reserved) = irq_from_bit_vector(1 << 13 | 1 << 26 | 1 << 31, Chip::Rp2040.table());
+    assert_eq!(irqs, vec!["IO_IRQ_BANK0"]);
+    assert_eq!(reserved, vec![26, 31]);
+}
+
+#[test]
+fn a_fractional_time_is_rounded_by_the_policy() {
+    let cycles = |text: &str, rounding: Rounding, time_use: TimeUse| {
+        text.parse::<TimeValue>().unwrap().to_cycles(Some(3_000_000), rounding, time_use).unwrap()
+    };
+    // 1.4us at 3 MHz is 4.2 cycles, 1.5us is 4.5.
+    assert_eq!(cycles("1.4us", Rounding::Ceil, TimeUse::Limit), 5);
+    assert_eq!(cycles("1.4us", Rounding::Floor, TimeUse::Demand), 4);
+    assert_eq!(cycles("1.4us", Rounding::Nearest, TimeUse::Demand), 4);
+    assert_eq!(cycles("1.5us", Rounding::Nearest, TimeUse::Limit), 5);
+    // conservative floors limits and ceils demand.
+    assert_eq!(cycles("1.4us", Rounding::Conservative, TimeUse::Limit), 4);
+    assert_eq!(cycles("1.4us", Rounding::Conservative, TimeUse::Demand), 5);
+    // whole cycles and exact times are not rounded.
+    assert_eq!(cycles("7", Rounding::Ceil, TimeUse::Demand), 7);
+    assert_eq!(cycles("7 cycles", Rounding::Floor, TimeUse::Demand), 7);
+    assert_eq!(cycles("2ms", Rounding::Floor, TimeUse::Demand), 6000);
+
+    assert_eq!("nearest".parse(), Ok(Rounding::Nearest));
+    assert!("up".parse::<Rounding>().is_err());
+    assert_eq!("1.5h".parse::<TimeValue>(), Err(UnitError::UnknownUnit("1.5h".to_owned())));
+    assert_eq!("us".parse::<TimeValue>(), Err(UnitError::InvalidNumber("us".to_owned())));
+    let time = "1.5us".parse::<TimeValue>().unwrap();
+    assert_eq!(
+        time.to_cycles(None, Rounding::Ceil, TimeUse::Limit),
+        Err(UnitError::MissingClock("1.5us".to_owned()))
+    );
+}
+
+#[test]
+fn measuring_from_the_body_symbol_leaves_the_vector_out() {
+    // TIMER_IRQ_1 of the full example masks its resources, calls `Alarm1::clear_interrupt` and unmasks them.
+    let body = "_ZN70_$LT$rp2040_hal..timer..Alarm1$u20$as$u20$rp2040_hal..timer..Alarm$GT$\
+                15clear_interrupt17hf84edbe326d296a9E";
+    let mut config = AnalysisConfig {
+        elf: concat!(env!("CARGO_MANIFEST_DIR"), "/test_bin/rtic_full_example").to_owned(),
+        tasks: vec![InputTask {
+            name: "alarm1_handler".to_owned(),
+            interrupt: "TIMER_IRQ_1".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    assert_eq!(config.tasks[0].entry_symbol(), "TIMER_IRQ_1");
+    let vector = run_analysis(&config).unwrap();
+
+    config.tasks[0].entry_symbol = Some(body.to_owned());
+    assert_eq!(config.tasks[0].entry_symbol(), body);
+    let from_body = run_analysis(&config).unwrap();
+
+    let (vector, from_body) = (&vector.worst.0[0], &from_body.worst.0[0]);
+    assert!(from_body.wcet < vector.wcet, "{} from the body, {}", from_body.wcet, vector.wcet);
+    // the masking is done around the call, measured from the body no section is left.
+    assert!(!vector.task.trace.inner.is_empty());
+    assert!(from_body.task.trace.inner.is_empty());
+}
+
+#[test]
+fn a_symbolic_mask_reduces_the_confidence_of_its_task_only() {
+    let dir = env::temp_dir().join(format!("easy-poc-confidence-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |lock: &str| PathLaps {
+        max_cycles: 30,
+        cycle_laps: vec![(5, lock.to_owned()), (20, "8192".to_owned())],
+    };
+    record_laps(&dir, "concrete", &[path("8192")]).unwrap();
+    record_laps(&dir, "symbolic", &[path(SYMBOLIC_LABEL)]).unwrap();
+    let task = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task("concrete", "TIMER_IRQ_0", 1), task("symbolic", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let confidence: Vec<(&str, Confidence)> =
+        report.confidence.iter().map(|c| (c.task.as_str(), c.confidence)).collect();
+    assert_eq!(confidence, vec![("concrete", Confidence::High), ("symbolic", Confidence::Reduced)]);
+    assert!(report.confidence[0].reasons.is_empty());
+    let reasons = &report.confidence[1].reasons;
+    assert_eq!(reasons, &vec!["symbolic critical section value on 1 of 1 paths".to_owned()]);
+}
+
+#[test]
+fn the_manifest_has_the_elf_hash_and_the_tasks() {
+    let dir = env::temp_dir().join(format!("easy-poc-manifest-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let elf = dir.join("app.elf");
+    fs::write(&elf, b"abc").unwrap();
+    let task = |name: &str, interrupt: &str| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: elf.to_string_lossy().into_owned(),
+        tasks: vec![task("a", "TIMER_IRQ_0"), task("b", "TIMER_IRQ_1")],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let manifest = Manifest::new(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let manifest = manifest.unwrap();
+
+    // sha256 of "abc".
+    assert_eq!(manifest.elf_sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(manifest.tasks.len(), 2);
+    assert_eq!(manifest.elf, config.elf);
+    assert_eq!(manifest.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(manifest.target, "rp2040");
+
+    let config = AnalysisConfig {
+        chip: Chip::Stm32f4,
+        ..config
+    };
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&elf, b"abc").unwrap();
+    let manifest = Manifest::new(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(manifest.unwrap().target, "stm32f4");
+}
+
+#[test]
+fn the_breakdown_factor_of_a_known_set() {
+    let task = |id: &str, prio: u8, wcet: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 120,
+        inter_arrival: 120,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    // alone, a task meets its deadline until its WCET of 10 has grown to the deadline of 120.
+    let factor = breakdown_factor(&[vec![task("a", 2, 10)]], &RtaConfig::default());
+    assert!((factor - 12.0).abs() < 0.02, "{factor}");
+
+    // b is preempted once by a within its period, so 20 + 10 cycles grow to 120 at 4.
+    let factor = breakdown_factor(&[vec![task("a", 2, 10), task("b", 1, 20)]], &RtaConfig::default());
+    assert!((factor - 4.0).abs() < 0.01, "{factor}");
+
+    // not schedulable as it is, 110 + 20 cycles in a deadline of 120.
+    let factor = breakdown_factor(&[vec![task("a", 2, 110), task("b", 1, 20)]], &RtaConfig::default());
+    assert!(factor < 1.0, "{factor}");
+}
+
+#[test]
+fn the_trace_builder_nests_interleaves_and_reports_imbalance() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+
+    let mut nested = TraceBuilder::new();
+    nested.push_lock(2, "a");
+    nested.push_lock(4, "b");
+    assert!(nested.is_locked("a") && nested.is_locked("b"));
+    nested.push_unlock(6, "b");
+    nested.push_unlock(8, "a");
+    let trace = nested.finish(0, 10, "t".to_owned()).unwrap();
+    let expected = section("t", 0, 10, vec![section("a", 2, 8, vec![section("b", 4, 6, vec![])])]);
+    assert!(trace_eq(&trace, &expected));
+
+    // a is released while b is still held, it stays locked until b is.
+    let mut interleaved = TraceBuilder::new();
+    interleaved.push_lock(2, "a");
+    interleaved.push_lock(4, "b");
+    interleaved.push_unlock(5, "a");
+    interleaved.push_unlock(7, "b");
+    let error = interleaved.finish(0, 10, "t".to_owned()).unwrap_err();
+    assert_eq!(error.issues, vec![TraceIssue::Interleaved { id: "a".to_owned(), cycle: 5 }]);
+    let expected = section("t", 0, 10, vec![section("a", 2, 7, vec![section("b", 4, 7, vec![])])]);
+    assert!(trace_eq(&error.trace, &expected));
+
+    let mut unbalanced = TraceBuilder::new();
+    unbalanced.push_unlock(1, "c");
+    unbalanced.push_lock(3, "d");
+    let error = unbalanced.finish(0, 10, "t".to_owned()).unwrap_err();
+    assert_eq!(
+        error.issues,
+        vec![
+            TraceIssue::UnmatchedUnlock { id: "c".to_owned(), cycle: 1 },
+            TraceIssue::Unclosed { id: "d".to_owned(), cycle: 3 },
+        ]
+    );
+    // the unclosed section is closed at the end of the trace.
+    assert!(trace_eq(&error.trace, &section("t", 0, 10, vec![section("d", 3, 10, vec![])])));
+}
+
+#[test]
+fn two_runs_on_the_same_paths_give_the_same_report() {
+    let dir = env::temp_dir().join(format!("easy-poc-reproducible-{}", std::process::id()));
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![(5, "8192".to_owned()), (cycles - 5, "8192".to_owned())],
+    };
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    // the same paths in another order, as symex may find them.
+    let run = |paths: &[PathLaps]| {
+        fs::create_dir_all(&dir).unwrap();
+        record_laps(&dir, "task", paths).unwrap();
+        let mut combinations = vec![];
+        let report = run_analysis_streaming(&config, |event| {
+            if let AnalysisEvent::CombinationEvaluated { paths, .. } = event {
+                combinations.push(paths.to_vec());
+            }
+        });
+        fs::remove_dir_all(&dir).unwrap();
+        (report.unwrap(), combinations)
+    };
+    let (first, first_combinations) = run(&[path(20), path(40), path(30)]);
+    let (second, second_combinations) = run(&[path(30), path(20), path(40)]);
+
+    assert_eq!(first.path_stats, second.path_stats);
+    assert_eq!(first.path_stats[0].cycles, vec![40, 30, 20]);
+    assert_eq!(first_combinations, second_combinations);
+    assert_eq!(first.worst_paths, vec![("task".to_owned(), 0)]);
+    assert_eq!(first.worst_paths, second.worst_paths);
+    assert_eq!(first.worst.0[0].wcet, second.worst.0[0].wcet);
+}
+
+#[test]
+fn an_excluded_call_is_left_out_of_the_wcet() {
+    let config = AnalysisConfig {
+        elf: concat!(env!("CARGO_MANIFEST_DIR"), "/test_bin/rtic_full_example").to_owned(),
+        tasks: vec![InputTask {
+            name: "alarm1_handler".to_owned(),
+            interrupt: "TIMER_IRQ_1".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    let measured = run_analysis(&config).unwrap();
+
+    // TIMER_IRQ_1 calls `Alarm1::clear_interrupt` once on every path.
+    let config = AnalysisConfig {
+        exclude: vec![".*Alarm1.*clear_interrupt.*".to_owned()],
+        ..config
+    };
+    let excluded = run_analysis(&config).unwrap();
+
+    assert_eq!(measured.excluded_calls, vec![("alarm1_handler".to_owned(), 0)]);
+    assert_eq!(excluded.excluded_calls, vec![("alarm1_handler".to_owned(), 1)]);
+    let (measured, excluded) = (measured.worst.0[0].wcet, excluded.worst.0[0].wcet);
+    assert!(excluded < measured, "{excluded} with the call excluded, {measured}");
+}
+
+#[test]
+fn two_targets_with_the_same_behaviour_have_an_all_zero_diff() {
+    let dir = env::temp_dir().join(format!("easy-poc-compare-targets-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let task = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    let section = Trace {
+        id: "1".to_owned(),
+        start: 5,
+        end: 15,
+        inner: vec![],
+    };
+    dump_trace(&task("low", 40, vec![section]), &dir.join("low.json")).unwrap();
+    dump_trace(&task("high", 20, vec![]), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let rp2040 = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let a = run_analysis(&rp2040).unwrap();
+    // the same tasks on the timers of the STM32F4.
+    let stm32f4 = AnalysisConfig {
+        tasks: vec![input("low", "TIM2", 1), input("high", "TIM3", 2)],
+        chip: Chip::Stm32f4,
+        ..rp2040
+    };
+    let b = run_analysis(&stm32f4).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let tasks = diff_reports(&a, &b);
+    let diffs: Vec<(&str, i64, Option<i64>)> =
+        tasks.iter().map(|t| (t.task.as_str(), t.wcet, t.response_time)).collect();
+    assert_eq!(diffs, vec![("low", 0, Some(0)), ("high", 0, Some(0))]);
+    assert!(diff_sections(&a, &b).iter().all(|s| s.a == s.b));
+}
+
+#[test]
+fn an_overloaded_task_does_not_converge_within_the_cap() {
+    let task = |id: &str, prio: u8, wcet: u32, period: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: period,
+        inter_arrival: period,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    // high takes the whole CPU, every window low tries adds another job of it.
+    let tasks = Tasks(vec![task("high", 2, 10, 10), task("low", 1, 5, 1000)]);
+    let rta = rta::response_time(&tasks, &RtaConfig::default());
+
+    assert!(rta.convergence[0].converged);
+    assert_eq!(rta.results.0[0].response_time, Some(10));
+    assert!(!rta.convergence[1].converged);
+    assert_eq!(rta.convergence[1].iterations, rta::MAX_ITERATIONS);
+    assert_eq!(rta.results.0[1].response_time, None);
+
+    // with room to spare it converges after a few iterations.
+    let tasks = Tasks(vec![task("high", 2, 10, 100), task("low", 1, 5, 1000)]);
+    let rta = rta::response_time(&tasks, &RtaConfig::default());
+    assert!(rta.convergence[1].converged);
+    assert!(rta.convergence[1].iterations < 5);
+    assert_eq!(rta.results.0[1].response_time, Some(15));
+}
+
+#[test]
+fn half_the_task_inside_critical_sections_is_a_lock_fraction_of_one_half() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    // 100 cycles with 30 + 20 locked, the nested section is already inside the outer one.
+    let nested = section("2", 15, 25, vec![]);
+    let sections = vec![section("1", 10, 40, vec![nested]), section("3", 60, 80, vec![])];
+    let task = Task {
+        id: "task".to_owned(),
+        prio: 1,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: section("task", 0, 100, sections),
+    };
+    assert!((lock_fraction(&task) - 0.5).abs() < 1e-6);
+
+    let empty = Task {
+        trace: section("task", 0, 0, vec![]),
+        ..task
+    };
+    assert_eq!(lock_fraction(&empty), 0.0);
+}
+
+#[test]
+fn a_task_pattern_selects_the_matching_tasks() {
+    let names = ["timer_0", "timer_1", "uart", "my_timer_2", "gpio["];
+    let selected = |patterns: &[&str]| -> Vec<&str> {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        let filter = TaskFilter::new(&patterns);
+        names.iter().copied().filter(|name| filter.matches(name)).collect()
+    };
+    // matched against the whole name, so my_timer_2 is left out.
+    assert_eq!(selected(&["timer_.*"]), vec!["timer_0", "timer_1"]);
+    assert_eq!(selected(&["uart", "timer_1"]), vec!["timer_1", "uart"]);
+    // not a valid regex, compared as a plain name.
+    assert_eq!(selected(&["gpio["]), vec!["gpio["]);
+    assert_eq!(selected(&[]), names.to_vec());
+}
+
+#[test]
+fn opposite_nesting_of_shared_resources_is_a_hazard() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    let task = |id: &str, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 1,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: section(id, 0, 100, inner),
+    };
+    let tasks = vec![
+        // a locks 2 while holding 1, b locks 1 while holding 2.
+        task("a", vec![section("1", 10, 50, vec![section("2", 20, 30, vec![])])]),
+        task("b", vec![section("2", 10, 50, vec![section("1", 20, 30, vec![])])]),
+        // the same order as a, not a hazard between them.
+        task("c", vec![section("1", 10, 50, vec![section("2", 20, 30, vec![])])]),
+        // the two resources one after the other, not nested.
+        task("d", vec![section("2", 10, 20, vec![]), section("1", 30, 40, vec![])]),
+    ];
+
+    let hazard = |task_a: &str, task_b: &str, outer: &str, inner: &str| LockOrderHazard {
+        task_a: task_a.to_owned(),
+        task_b: task_b.to_owned(),
+        resources: (outer.to_owned(), inner.to_owned()),
+    };
+    assert_eq!(lock_order_hazards(&tasks), vec![hazard("a", "b", "1", "2"), hazard("b", "c", "2", "1")]);
+}
+
+#[test]
+fn a_context_switch_cost_is_paid_twice_per_preempting_job() {
+    let task = |id: &str, prio: u8, wcet: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("high", 3, 10), task("mid", 2, 5), task("low", 1, 30)]);
+    let free = rta::response_time(&tasks, &RtaConfig::default());
+    let config = RtaConfig {
+        context_switch_cost: 3,
+        ..RtaConfig::default()
+    };
+    let costly = rta::response_time(&tasks, &config);
+
+    let response_times = |rta: &rta::RtaResult| -> Vec<Option<u32>> {
+        rta.results.0.iter().map(|r| r.response_time).collect()
+    };
+    assert_eq!(response_times(&free), vec![Some(10), Some(15), Some(45)]);
+    // low is preempted by one job of each of the others, each paying 2 * 3 cycles.
+    let jobs: u64 = costly.interferers[2].iter().map(|i| i.jobs).sum();
+    assert_eq!(jobs, 2);
+    assert_eq!(response_times(&costly), vec![Some(10), Some(15 + 6), Some(45 + 2 * 6)]);
+}
+
+#[test]
+fn only_the_failing_task_is_listed() {
+    let dir = env::temp_dir().join(format!("easy-poc-only-failures-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let (schedulable, lines) = failures(&report, &TaskFilter::new(&[]));
+    assert!(!schedulable);
+    assert_eq!(lines, vec!["task low misses by 10 cycles".to_owned()]);
+    // selecting only the task that meets its deadline.
+    let (schedulable, lines) = failures(&report, &TaskFilter::new(&["high".to_owned()]));
+    assert!(schedulable);
+    assert_eq!(lines, vec!["all 1 tasks schedulable".to_owned()]);
+}
+
+#[test]
+fn identical_traces_hash_equal_and_reordered_ones_do_not() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    // two sections, the first with one nested inside it ending at `nested_end`.
+    let trace = |nested_end: u32, reversed: bool| {
+        let nested = section("2", 12, nested_end, vec![]);
+        let mut sections = vec![section("1", 10, 20, vec![nested]), section("3", 40, 50, vec![])];
+        if reversed {
+            sections.reverse();
+        }
+        section("t", 0, 100, sections)
+    };
+
+    assert_eq!(trace_key(&trace(18, false)), trace_key(&trace(18, false)));
+    assert!(trace_eq(&trace(18, false), &trace(18, false)));
+    assert_ne!(trace_key(&trace(18, false)), trace_key(&trace(18, true)));
+    assert!(!trace_eq(&trace(18, false), &trace(18, true)));
+    // a change deep inside changes the key.
+    assert_ne!(trace_key(&trace(18, false)), trace_key(&trace(19, false)));
+}
+
+#[test]
+fn bin_dir_and_bin_name_change_the_default_elf() {
+    assert_eq!(default_elf(None, None), "test_bin/rtic_full_example");
+    assert_eq!(default_elf(Some("target/thumbv6m".to_owned()), None), "target/thumbv6m/rtic_full_example");
+    assert_eq!(default_elf(None, Some("blinky".to_owned())), "test_bin/blinky");
+    assert_eq!(default_elf(Some("out".to_owned()), Some("app".to_owned())), "out/app");
+}
+
+#[test]
+fn the_task_with_the_tight_deadline_has_the_least_headroom() {
+    let task = |id: &str, prio: u8, wcet: u32, deadline: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    // tight runs first and meets its deadline of 15 with 5 cycles to spare, the others have 970.
+    let combinations = [vec![task("tight", 3, 10, 15), task("mid", 2, 10, 1000), task("low", 1, 10, 1000)]];
+    let headroom = |id: &str| sensitivity(&combinations, &RtaConfig::default(), id);
+    assert_eq!(headroom("tight"), Some(5));
+    assert_eq!(headroom("mid"), Some(970));
+    assert_eq!(headroom("low"), Some(970));
+
+    let overloaded = [vec![task("tight", 3, 20, 15)]];
+    assert_eq!(sensitivity(&overloaded, &RtaConfig::default(), "tight"), None);
+}
+
+#[test]
+fn perfetto_names_a_track_for_every_task() {
+    let result = |id: &str, inner: Vec<Trace>| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 1000,
+            inter_arrival: 1000,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: 30,
+                inner,
+            },
+        },
+        response_time: Some(30),
+        wcet: 30,
+        blocking: 0,
+        interference: 0,
+    };
+    let section = Trace {
+        id: 0x2000.to_string(),
+        start: 15,
+        end: 28,
+        inner: vec![],
+    };
+    let results = TasksResult(vec![result("gpio_handler", vec![section]), result("timer_handler", vec![])]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&perfetto_trace(&results, "app", Chip::Rp2040.table(), false)).unwrap();
+    let events = json["traceEvents"].as_array().unwrap();
+    let metadata: Vec<(&str, Option<u64>, &str)> = events
+        .iter()
+        .filter(|e| e["ph"] == "M")
+        .map(|e| (e["name"].as_str().unwrap(), e["tid"].as_u64(), e["args"]["name"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        metadata,
+        vec![
+            ("process_name", None, "app"),
+            ("thread_name", Some(0), "gpio_handler"),
+            ("thread_name", Some(1), "timer_handler"),
+        ]
+    );
+    // the critical section is an async slice on the track of its task, named after the interrupt it masks.
+    let slices: Vec<(&str, &str, u64, u64)> = events
+        .iter()
+        .filter(|e| e["ph"] == "b" || e["ph"] == "e")
+        .map(|e| {
+            let (name, ph) = (e["name"].as_str().unwrap(), e["ph"].as_str().unwrap());
+            (name, ph, e["ts"].as_u64().unwrap(), e["tid"].as_u64().unwrap())
+        })
+        .collect();
+    let name = "0x2000 (IO_IRQ_BANK0)";
+    assert_eq!(slices, vec![(name, "b", 15, 0), (name, "e", 28, 0)]);
+}
+
+#[test]
+fn a_task_over_max_paths_keeps_its_longest_paths() {
+    let dir = env::temp_dir().join(format!("easy-poc-max-paths-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // in no particular order, as a hand written laps file can have them.
+    record_laps(&dir, "task", &[path(20), path(50), path(30), path(40)]).unwrap();
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: Some(2),
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    assert_eq!(report.combination_count, 2);
+    assert_eq!(report.worst.0[0].wcet, 50);
+    assert_eq!(report.confidence[0].confidence, Confidence::Reduced);
+    assert_eq!(report.confidence[0].reasons, vec!["truncated 2 paths".to_owned()]);
+    assert!(report.path_stats[0].incomplete);
+    // the stats are of every path symex found, before any is dropped.
+    assert_eq!(report.path_stats[0].cycles, vec![50, 40, 30, 20]);
+}
+
+#[test]
+fn a_lock_free_task_has_no_sections_and_no_blocking() {
+    let input = |name: &str, interrupt: &str| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let path = |max_cycles: usize, cycle_laps: Vec<(usize, String)>| PathLaps {
+        max_cycles,
+        cycle_laps,
+    };
+    let free = create_task(&path(42, vec![]), &input("free", "TIMER_IRQ_0"), 1).unwrap();
+    assert_eq!((free.trace.start, free.trace.end), (0, 42));
+    assert!(free.trace.inner.is_empty());
+    let laps = vec![(5, "8192".to_owned()), (25, "8192".to_owned())];
+    let locker = create_task(&path(30, laps), &input("locker", "TIMER_IRQ_1"), 2).unwrap();
+
+    // the lock free task on the lower level blocks nothing either.
+    let rta = rta::response_time(&Tasks(vec![free, locker]), &RtaConfig::default());
+    let results: Vec<(&str, u32, u32)> =
+        rta.results.0.iter().map(|r| (r.task.id.as_str(), r.wcet, r.blocking)).collect();
+    assert_eq!(results, vec![("free", 42, 0), ("locker", 30, 0)]);
+
+    assert!(find_worst(vec![]).0.is_empty());
+}
+
+#[test]
+fn a_half_full_bar() {
+    assert_eq!(ascii_bar(0.5, 10), "[#####.....]");
+    assert_eq!(ascii_bar(0.0, 4), "[....]");
+    assert_eq!(ascii_bar(1.0, 4), "[####]");
+    // over the deadline the bar is full and marked.
+    assert_eq!(ascii_bar(1.5, 4), "[####]!");
+}
+
+#[test]
+fn a_ceiling_override_above_the_derived_one_blocks_and_below_it_is_an_error() {
+    let dir = env::temp_dir().join(format!("easy-poc-ceiling-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |start: u32, end: u32| Trace {
+        id: 0x2000.to_string(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let task = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    // low and mid share the resource, so its derived ceiling is the priority of mid.
+    dump_trace(&task("low", 40, vec![section(10, 20)]), &dir.join("low.json")).unwrap();
+    dump_trace(&task("mid", 20, vec![section(5, 10)]), &dir.join("mid.json")).unwrap();
+    dump_trace(&task("high", 10, vec![]), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let derived = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![
+            input("low", "TIMER_IRQ_0", 1),
+            input("mid", "TIMER_IRQ_1", 2),
+            input("high", "TIMER_IRQ_2", 3),
+        ],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("mid".to_owned(), dir.join("mid.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    // blocking of low, mid and high.
+    let blocking = |config: &AnalysisConfig| -> Vec<u32> {
+        let report = run_analysis(config).unwrap();
+        report.worst.0.iter().map(|r| r.blocking).collect()
+    };
+
+    let derived_blocking = blocking(&derived);
+    let raised = AnalysisConfig {
+        ceilings: vec![("8192".to_owned(), 3)],
+        ..derived
+    };
+    let raised_blocking = blocking(&raised);
+    let lowered = AnalysisConfig {
+        ceilings: vec![("8192".to_owned(), 1)],
+        ..raised
+    };
+    let error = run_analysis(&lowered).err();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(derived_blocking, vec![0, 10, 0]);
+    // with the ceiling at the level of high, low holding the resource blocks it too.
+    assert_eq!(raised_blocking, vec![0, 10, 10]);
+    let message = "the ceiling of 0x2000 (IO_IRQ_BANK0) is set to 1, below the priority of a task using it";
+    assert_eq!(error, Some(AnalysisError::Config(message.to_owned())));
+}
+
+#[test]
+fn wcet_only_gives_the_longest_path_of_a_function() {
+    let task = wcet_only(FIXTURE, "IO_IRQ_BANK0", CsMechanism::Nvic, &[], NvicAddresses::default())
+        .unwrap()
+        .unwrap();
+    // the path through the critical section, see the top of this file.
+    assert_eq!((task.trace.start, task.trace.end), (0, 30));
+    let sections: Vec<(&str, u32, u32)> =
+        task.trace.inner.iter().map(|s| (s.id.as_str(), s.start, s.end)).collect();
+    assert_eq!(sections, vec![("8192", 15, 28)]);
+
+    let missing = wcet_only(FIXTURE, "NO_SUCH_IRQ", CsMechanism::Nvic, &[], NvicAddresses::default());
+    assert_eq!(missing.err(), Some(AnalysisError::FunctionNotFound("NO_SUCH_IRQ".to_owned())));
+}
+
+// Writes to R2 seen by `count_r2_writes`, the hook is a plain function so it can't capture a counter.
+static R2_WRITES: AtomicUsize = AtomicUsize::new(0);
+
+fn count_r2_writes(_state: &mut GAState, _value: DExpr) -> GAResult<()> {
+    R2_WRITES.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+#[test]
+fn a_registered_hook_fires_next_to_the_built_in_ones() {
+    let config = AnalysisConfig {
+        elf: FIXTURE.to_owned(),
+        tasks: vec![InputTask {
+            name: "gpio_handler".to_owned(),
+            interrupt: "IO_IRQ_BANK0".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    // the counter is loaded into r2 and incremented on the path through the critical section.
+    let config = config.with_register_write_hook("R2", count_r2_writes);
+
+    let report = run_analysis(&config).unwrap();
+
+    assert!(R2_WRITES.load(Ordering::Relaxed) >= 2);
+    // the critical section hooks still ran.
+    let worst = &report.worst.0[0];
+    assert_eq!(worst.wcet, 30);
+    assert_eq!(worst.task.trace.inner.len(), 1);
+}
+
+#[test]
+fn collapsing_a_many_path_task_keeps_one_worst_case() {
+    let dir = env::temp_dir().join(format!("easy-poc-collapse-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // 50 paths, the longest one (path 49) holds the resource for 2 cycles, path 0 for 30.
+    let paths: Vec<PathLaps> = (0..50)
+        .map(|i| PathLaps {
+            max_cycles: 100 + i,
+            cycle_laps: vec![(10, "8192".to_owned()), (40 - 28 * i / 49, "8192".to_owned())],
+        })
+        .collect();
+    record_laps(&dir, "task", &paths).unwrap();
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: true,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    // one trace is kept for the task however many paths it has.
+    assert_eq!(report.path_counts, vec![("task".to_owned(), 1)]);
+    assert_eq!(report.combination_count, 1);
+    // it has the longest WCET and the longest section, which are on different paths.
+    let worst = &report.worst.0[0];
+    assert_eq!(worst.wcet, 149);
+    let sections: Vec<(&str, u32)> =
+        worst.task.trace.inner.iter().map(|s| (s.id.as_str(), s.end - s.start)).collect();
+    assert_eq!(sections, vec![("8192", 30)]);
+    assert!(collapse_paths(&[]).is_none());
+}
+
+#[test]
+fn the_worst_path_of_a_task_is_its_position_in_the_paths() {
+    let result = |id: &str, response_time: Option<u32>| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 1000,
+            inter_arrival: 1000,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: 10,
+                inner: vec![],
+            },
+        },
+        response_time,
+        wcet: 10,
+        blocking: 0,
+        interference: 0,
+    };
+    let results = vec![
+        TasksResult(vec![result("a", Some(10)), result("b", Some(30))]),
+        TasksResult(vec![result("a", Some(12)), result("b", Some(40))]),
+        TasksResult(vec![result("a", Some(20)), result("b", Some(40))]),
+    ];
+    // a is worst in the last combination, b in the second as the first of two equally bad ones.
+    assert_eq!(worst_combinations(&results), vec![2, 1]);
+
+    let dir = env::temp_dir().join(format!("easy-poc-worst-path-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let paths: Vec<PathLaps> = [40, 120, 80]
+        .iter()
+        .map(|&max_cycles| PathLaps {
+            max_cycles,
+            cycle_laps: vec![],
+        })
+        .collect();
+    record_laps(&dir, "task", &paths).unwrap();
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let mut combinations = vec![];
+    let report = run_analysis_streaming(&config, |event| {
+        if let AnalysisEvent::CombinationEvaluated { paths, .. } = event {
+            combinations.push(paths.to_vec());
+        }
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    // the worst path is the one with index `i` in the combinations, and the paths are replayed longest first.
+    let (name, i) = &report.worst_paths[0];
+    assert_eq!(name, "task");
+    assert!(combinations.iter().any(|c| c[0] == ("task".to_owned(), *i)));
+    assert_eq!((*i, report.worst.0[0].wcet), (0, 120));
+}
+
+#[test]
+fn the_same_results_serialize_to_the_same_bytes() {
+    let result = |id: &str, inner: Vec<Trace>| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 1000,
+            inter_arrival: 1000,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: 30,
+                inner,
+            },
+        },
+        response_time: Some(30),
+        wcet: 30,
+        blocking: 0,
+        interference: 0,
+    };
+    let section = || Trace {
+        id: 0x2000.to_string(),
+        start: 15,
+        end: 28,
+        inner: vec![],
+    };
+    let results = || {
+        TasksResult(vec![result("gpio_handler", vec![section()]), result("timer_handler", vec![])])
+    };
+    let table = Chip::Rp2040.table();
+
+    for pretty in [false, true] {
+        let chrome = chrome_trace(&results(), table, pretty);
+        assert_eq!(chrome, chrome_trace(&results(), table, pretty));
+        let perfetto = perfetto_trace(&results(), "app", table, pretty);
+        assert_eq!(perfetto, perfetto_trace(&results(), "app", table, pretty));
+    }
+    // compact by default, one line.
+    assert!(!chrome_trace(&results(), table, false).contains('\n'));
+    assert!(chrome_trace(&results(), table, true).contains('\n'));
+}
+
+#[test]
+fn an_interrupt_latency_is_paid_once_per_job() {
+    let task = |id: &str, prio: u8, wcet: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("high", 3, 10), task("mid", 2, 5), task("low", 1, 30)]);
+    let config = RtaConfig {
+        interrupt_latency: 15,
+        ..RtaConfig::default()
+    };
+    let latency = rta::response_time(&tasks, &config);
+
+    let response_times: Vec<Option<u32>> = latency.results.0.iter().map(|r| r.response_time).collect();
+    // every task pays for its own job, mid for one job of high as well and low for one job of each.
+    assert_eq!(response_times, vec![Some(10 + 15), Some(15 + 2 * 15), Some(45 + 3 * 15)]);
+}
+
+#[test]
+fn every_variant_in_the_elf_dir_is_in_the_table() {
+    let dir = env::temp_dir().join(format!("easy-poc-elf-dir-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/test_bin");
+    fs::copy(format!("{fixtures}/rtic_simple_resourse"), dir.join("simple.elf")).unwrap();
+    fs::copy(format!("{fixtures}/rtic_full_example"), dir.join("full")).unwrap();
+    let trace = Task {
+        id: "timer".to_owned(),
+        prio: 1,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: "timer".to_owned(),
+            start: 0,
+            end: 40,
+            inner: vec![],
+        },
+    };
+    // not a variant, it has an extension other than `.elf`.
+    dump_trace(&trace, &dir.join("timer.json")).unwrap();
+    let task = InputTask {
+        name: "timer".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 100,
+        activation: Activation::Periodic { period: 100 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let mut config = AnalysisConfig {
+        elf: String::new(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![("timer".to_owned(), dir.join("timer.json"))],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let elfs = elf_files(&dir).unwrap();
+    let mut table = vec![];
+    for elf in &elfs {
+        config.elf = elf.to_string_lossy().into_owned();
+        let report = run_analysis(&config).unwrap();
+        let name = elf.file_name().unwrap().to_string_lossy();
+        table.push(variant_line(&name, &schedulability(&report), &TaskFilter::new(&[])));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(elfs.iter().map(|e| e.file_name().unwrap()).collect::<Vec<_>>(), vec!["full", "simple.elf"]);
+    assert_eq!(table, vec!["full: schedulable", "simple.elf: schedulable"]);
+}
+
+#[test]
+fn a_path_without_instructions_warns() {
+    let dir = env::temp_dir().join(format!("easy-poc-empty-path-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let paths = vec![
+        PathLaps {
+            max_cycles: 30,
+            cycle_laps: vec![],
+        },
+        PathLaps {
+            max_cycles: 0,
+            cycle_laps: vec![],
+        },
+    ];
+    record_laps(&dir, "gpio_handler", &paths).unwrap();
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![InputTask {
+            name: "gpio_handler".to_owned(),
+            interrupt: "IO_IRQ_BANK0".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    let strict = run_analysis(&AnalysisConfig {
+        strict: true,
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    let report = report.unwrap();
+    let empty: Vec<&Warning> = report.warnings.iter().filter(|w| w.kind == WarningKind::EmptyPaths).collect();
+    assert_eq!(empty.len(), 1);
+    assert_eq!(empty[0].task.as_deref(), Some("gpio_handler"));
+    assert!(empty[0].message.contains("no instructions on 1 of 2 paths"), "{}", empty[0].message);
+    match strict {
+        Err(AnalysisError::Strict(warning)) => assert_eq!(warning.kind, WarningKind::EmptyPaths),
+        other => panic!("expected a strict mode error, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn the_schedulability_of_a_mixed_report() {
+    let dir = env::temp_dir().join(format!("easy-poc-schedulability-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("mid", 10), &dir.join("mid.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by both, 40 + 10 + 20 cycles against a deadline of 50.
+        tasks: vec![
+            input("low", "TIMER_IRQ_0", 1, 50),
+            input("mid", "TIMER_IRQ_1", 2, 100),
+            input("high", "TIMER_IRQ_2", 3, 1000),
+        ],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("mid".to_owned(), dir.join("mid.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let task = |task: &str, response_time: u32, deadline: u32| TaskSchedulability {
+        task: task.to_owned(),
+        response_time: Some(response_time),
+        deadline,
+        schedulable: response_time <= deadline,
+    };
+    assert_eq!(
+        schedulability(&report),
+        Schedulability {
+            schedulable: false,
+            tasks: vec![task("low", 70, 50), task("mid", 30, 100), task("high", 20, 1000)],
+            missed_chains: vec![],
+            tightest: Some("low".to_owned()),
+            max_utilization: report.max_utilization,
+            low_utilization: false,
+        }
+    );
+}
+
+#[test]
+fn the_longest_lock_of_the_low_priority_task_is_the_global_max() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    let task = |id: &str, prio: u8, trace: Trace| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace,
+    };
+    let low = vec![section("1", 10, 50, vec![section("2", 20, 30, vec![])])];
+    let high = vec![section("1", 5, 25, vec![]), section("3", 30, 45, vec![])];
+    let tasks = vec![
+        task("high", 2, section("high", 0, 60, high)),
+        task("low", 1, section("low", 0, 80, low)),
+    ];
+
+    assert_eq!(global_max_lock(&tasks), Some((40, "low".to_owned(), "1".to_owned())));
+    assert_eq!(global_max_lock(&[task("free", 1, section("free", 0, 10, vec![]))]), None);
+}
+
+#[test]
+fn the_self_test_passes_on_the_fixture() {
+    for result in self_test(SELF_TEST_ELF) {
+        assert!(result.passed(), "{}: {:?} cycles, expected {:?}", result.name, result.cycles, result.expected);
+    }
+}
+
+#[test]
+fn the_text_report_is_sorted_by_priority_then_name() {
+    let result = |id: &str, prio: u8, response_time: Option<u32>| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio,
+            deadline: 100,
+            inter_arrival: 100,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: 10,
+                inner: vec![],
+            },
+        },
+        response_time,
+        wcet: 10,
+        blocking: 0,
+        interference: 0,
+    };
+    let results = [
+        result("low", 1, Some(90)),
+        result("uart", 3, Some(20)),
+        result("gpio", 3, Some(40)),
+        result("mid", 2, None),
+    ];
+    let sorted = |sort: Sort| {
+        let mut sorted: Vec<&TaskResult> = results.iter().collect();
+        sort_results(&mut sorted, sort);
+        sorted.iter().map(|r| r.task.id.as_str()).collect::<Vec<_>>()
+    };
+
+    assert_eq!(sorted(Sort::Priority), vec!["gpio", "uart", "mid", "low"]);
+    assert_eq!(sorted(Sort::Config), vec!["low", "uart", "gpio", "mid"]);
+    // unbounded first, then by slack.
+    assert_eq!(sorted(Sort::Slack), vec!["mid", "low", "gpio", "uart"]);
+}
+
+#[test]
+fn a_task_whose_interrupt_is_never_enabled_is_likely_unused() {
+    let dir = env::temp_dir().join(format!("easy-poc-unused-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // gpio_handler writes the bit of IO_IRQ_BANK0 (13), nothing writes the bit of TIMER_IRQ_0.
+    let gpio = vec![PathLaps {
+        max_cycles: 30,
+        cycle_laps: vec![(15, "8192".to_owned()), (28, "8192".to_owned())],
+    }];
+    let timer = vec![PathLaps {
+        max_cycles: 20,
+        cycle_laps: vec![],
+    }];
+    record_laps(&dir, "gpio_handler", &gpio).unwrap();
+    record_laps(&dir, "timer", &timer).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: FIXTURE.to_owned(),
+        tasks: vec![input("gpio_handler", "IO_IRQ_BANK0", 1), input("timer", "TIMER_IRQ_0", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        // enables nothing itself.
+        init_symbol: Some("__nop".to_owned()),
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let unused: Vec<Option<&str>> = report
+        .warnings
+        .iter()
+        .filter(|w| w.kind == WarningKind::LikelyUnused)
+        .map(|w| w.task.as_deref())
+        .collect();
+    assert_eq!(unused, vec![Some("timer")]);
+}
+
+#[test]
+fn the_built_elf_is_the_executable_of_the_example() {
+    // what cargo prints building an example: its dependencies, then the example, then the summary.
+    let messages = [
+        r#"{"reason":"compiler-artifact","target":{"name":"rp2040_hal"},"executable":null}"#,
+        r#"{"reason":"compiler-artifact","target":{"name":"blinky"},"executable":"/t/examples/blinky"}"#,
+        r#"{"reason":"compiler-artifact","target":{"name":"full"},"executable":"/t/examples/full"}"#,
+        r#"{"reason":"build-finished","success":true}"#,
+    ]
+    .join("\n");
+
+    assert_eq!(example_elf(&messages, "full"), Some(PathBuf::from("/t/examples/full")));
+    assert_eq!(example_elf(&messages, "missing"), None);
+}
+
+#[test]
+fn a_run_with_a_warning_fails_with_warn_as_error() {
+    let dir = env::temp_dir().join(format!("easy-poc-warn-as-error-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = Task {
+        id: "timer".to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: "timer".to_owned(),
+            start: 0,
+            end: 40,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace, &dir.join("timer.json")).unwrap();
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![InputTask {
+            name: "timer".to_owned(),
+            interrupt: "TIM2".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![("timer".to_owned(), dir.join("timer.json"))],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Stm32f4,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    // the one warning, about the cycle model of the stm32f4, doesn't make the task miss its deadline.
+    assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+    assert_eq!(report.worst.0[0].response_time, Some(40));
+    let errors = warning_errors(&report.warnings, true);
+    assert_eq!(errors, vec![format!("error: {}", report.warnings[0])]);
+    assert!(warning_errors(&report.warnings, false).is_empty());
+}
+
+#[test]
+fn a_tight_reservation_makes_a_schedulable_set_miss() {
+    let task = |id: &str, prio: u8, wcet: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 100,
+        inter_arrival: 100,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("high", 2, 10), task("low", 1, 30)]);
+    let reservation = Reservation {
+        budget: 20,
+        period: 50,
+    };
+    let config = RtaConfig {
+        reservation: Some(reservation),
+        ..RtaConfig::default()
+    };
+
+    let response_times = |rta: &rta::RtaResult| -> Vec<Option<u32>> {
+        rta.results.0.iter().map(|r| r.response_time).collect()
+    };
+    assert_eq!(response_times(&rta::response_time(&tasks, &RtaConfig::default())), vec![Some(10), Some(40)]);
+    // 30 cycles without supply before the first budget and between each. high needs one budget, low needs
+    // three for its own 30 cycles and two jobs of high.
+    assert_eq!(reservation.time_to_supply(10), 10 + 2 * 30);
+    assert_eq!(reservation.time_to_supply(0), 0);
+    assert_eq!(response_times(&rta::response_time(&tasks, &config)), vec![Some(70), Some(50 + 4 * 30)]);
+}
+
+#[test]
+fn a_correlation_prunes_the_combinations_picking_part_of_it() {
+    let task = |id: &str, wcet: u32| Task {
+        id: id.to_owned(),
+        prio: 1,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    // path 0 of both tasks follows the same configuration flag.
+    let paths = vec![
+        vec![(0, task("a", 40)), (1, task("a", 20))],
+        vec![(0, task("b", 30)), (1, task("b", 10))],
+        vec![(0, task("c", 5))],
+    ];
+    let correlations = vec![vec![("a".to_owned(), 0), ("b".to_owned(), 0)]];
+
+    let all = get_all_sets(&paths);
+    let kept: Vec<Vec<(&str, usize)>> = all
+        .iter()
+        .filter(|combination| correlated(combination, &correlations))
+        .map(|combination| combination.iter().map(|(path, t)| (t.id.as_str(), *path)).collect())
+        .collect();
+    assert_eq!(all.len(), 4);
+    // the sets list the tasks last to first.
+    assert_eq!(kept, vec![vec![("c", 0), ("b", 0), ("a", 0)], vec![("c", 0), ("b", 1), ("a", 1)]]);
+}
+
+#[test]
+fn the_cold_start_penalty_raises_the_response_times_but_not_the_utilization() {
+    let dir = env::temp_dir().join(format!("easy-poc-cold-start-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let warm = run_analysis(&config);
+    let cold = run_analysis(&AnalysisConfig {
+        cold_start_penalty: 100,
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let (warm, cold) = (warm.unwrap(), cold.unwrap());
+
+    let response_times = |report: &AnalysisReport| -> Vec<Option<u32>> {
+        report.worst.0.iter().map(|r| r.response_time).collect()
+    };
+    assert_eq!(response_times(&warm), vec![Some(60), Some(20)]);
+    // low pays for its own first job and the first of high.
+    assert_eq!(response_times(&cold), vec![Some(60 + 2 * 100), Some(20 + 100)]);
+    assert_eq!(cold.max_utilization, warm.max_utilization);
+}
+
+#[test]
+fn every_resource_is_discovered_with_the_tasks_using_it() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    let task = |id: &str, trace: Trace| Task {
+        id: id.to_owned(),
+        prio: 1,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace,
+    };
+    // both lock "1", high nests its own "3" in it.
+    let tasks = vec![
+        task("low", section("low", 0, 50, vec![section("1", 5, 15, vec![]), section("2", 20, 30, vec![])])),
+        task("high", section("high", 0, 20, vec![section("1", 2, 12, vec![section("3", 4, 8, vec![])])])),
+    ];
+
+    let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<BTreeSet<String>>();
+    assert_eq!(discovered_resources(&tasks), ids(&["1", "2", "3"]));
+    let users = resource_users(&tasks);
+    assert_eq!(users.len(), 3);
+    assert_eq!(users["1"], ids(&["high", "low"]));
+    assert_eq!(users["2"], ids(&["low"]));
+    assert_eq!(users["3"], ids(&["high"]));
+}
+
+#[test]
+fn require_concrete_locks_only_fails_on_a_symbolic_lock() {
+    let dir = env::temp_dir().join(format!("easy-poc-concrete-locks-{}", std::process::id()));
+    let concrete = dir.join("concrete");
+    let symbolic = dir.join("symbolic");
+    fs::create_dir_all(&concrete).unwrap();
+    fs::create_dir_all(&symbolic).unwrap();
+    let path = |lock: &str| PathLaps {
+        max_cycles: 30,
+        cycle_laps: vec![(5, lock.to_owned()), (20, "8192".to_owned())],
+    };
+    record_laps(&concrete, "task", &[path("8192")]).unwrap();
+    record_laps(&symbolic, "task", &[path(SYMBOLIC_LABEL)]).unwrap();
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(concrete.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: true,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let concrete_run = run_analysis(&config);
+    let config = AnalysisConfig {
+        replay_laps: Some(symbolic),
+        ..config
+    };
+    let with_flag = run_analysis(&config);
+    let without_flag = run_analysis(&AnalysisConfig {
+        require_concrete_locks: false,
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(concrete_run.is_ok());
+    // without the flag the symbolic lock only lowers the confidence of the task.
+    assert_ne!(without_flag.unwrap().confidence[0].confidence, Confidence::High);
+    assert!(matches!(with_flag, Err(AnalysisError::SymbolicLock(message)) if message.starts_with("task task:")));
+}
+
+#[test]
+fn the_latency_of_a_two_task_chain_is_the_sum_of_its_response_times_and_delay() {
+    let dir = env::temp_dir().join(format!("easy-poc-chain-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("button", 20), &dir.join("button.json")).unwrap();
+    dump_trace(&trace("led", 40), &dir.join("led.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("button", "IO_IRQ_BANK0", 2), input("led", "TIMER_IRQ_0", 1)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        // the button handler pends the led task, which is released 10 cycles after it returns.
+        chains: vec![Chain {
+            name: "button to led".to_owned(),
+            tasks: vec!["button".to_owned(), "led".to_owned()],
+            activation_delay: 10,
+            deadline: Some(100),
+        }],
+        servers: vec![],
+        trace_files: vec![
+            ("button".to_owned(), dir.join("button.json")),
+            ("led".to_owned(), dir.join("led.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    // button responds in 20 cycles, led in 40 + 20 as button preempts it.
+    assert_eq!(
+        report.chain_latencies,
+        vec![ChainLatency {
+            chain: "button to led".to_owned(),
+            tasks: vec!["button".to_owned(), "led".to_owned()],
+            latency: Some(20 + 10 + 60),
+            deadline: Some(100),
+        }]
+    );
+    assert!(!report.chain_latencies[0].missed());
+}
+
+#[test]
+fn a_tag_selects_the_tasks_having_it_and_is_in_the_json_report() {
+    let dir = env::temp_dir().join(format!("easy-poc-tags-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("motor", 40), &dir.join("motor.json")).unwrap();
+    dump_trace(&trace("uart", 20), &dir.join("uart.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, subsystem: &str| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::from([
+            ("subsystem".to_owned(), subsystem.to_owned()),
+            ("safety".to_owned(), "asil-b".to_owned()),
+        ]),
+    };
+    let tasks = vec![input("motor", "TIMER_IRQ_0", 1, "motor"), input("uart", "TIMER_IRQ_1", 2, "comms")];
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: tasks.clone(),
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("motor".to_owned(), dir.join("motor.json")),
+            ("uart".to_owned(), dir.join("uart.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let tag = |key: &str, value: &str| (key.to_owned(), value.to_owned());
+    let motor = TaskFilter::new(&[]).with_tags(&[tag("subsystem", "motor")], &tasks);
+    assert!(motor.matches("motor"));
+    assert!(!motor.matches("uart"));
+    // a task needs every tag given.
+    let both = TaskFilter::new(&[]).with_tags(&[tag("subsystem", "comms"), tag("safety", "asil-b")], &tasks);
+    assert!(both.matches("uart"));
+    assert!(!TaskFilter::new(&[]).with_tags(&[tag("safety", "asil-d")], &tasks).matches("motor"));
+
+    let json: serde_json::Value = serde_json::from_str(&json_report(&report, &motor, false)).unwrap();
+    let json_tasks = json["tasks"].as_array().unwrap();
+    assert_eq!(json_tasks.len(), 1);
+    assert_eq!(json_tasks[0]["id"], "motor");
+    assert_eq!(json_tasks[0]["tags"]["subsystem"], "motor");
+    assert_eq!(json_tasks[0]["tags"]["safety"], "asil-b");
+}
+
+#[test]
+fn a_change_triggers_once_the_writes_have_settled() {
+    let settle = Duration::from_millis(500);
+    let poll = Duration::from_millis(200);
+    let at = |ms: u64| Some(SystemTime::UNIX_EPOCH + Duration::from_millis(ms));
+    let mut debounce = Debounce::new(vec![at(0), at(0)], settle);
+
+    // nothing changed.
+    assert!(!debounce.update(vec![at(0), at(0)], poll));
+    // the linker writes the elf in steps, and removes it for a moment in between.
+    assert!(!debounce.update(vec![at(1000), at(0)], poll));
+    assert!(!debounce.update(vec![None, at(0)], poll));
+    assert!(!debounce.update(vec![at(1200), at(0)], poll));
+    // quiet for 200, 400 and then 600 ms.
+    assert!(!debounce.update(vec![at(1200), at(0)], poll));
+    assert!(!debounce.update(vec![at(1200), at(0)], poll));
+    assert!(debounce.update(vec![at(1200), at(0)], poll));
+    // settled, the same times don't trigger again.
+    assert!(!debounce.update(vec![at(1200), at(0)], settle));
+    // a change of the other file triggers on its own.
+    assert!(!debounce.update(vec![at(1200), at(3000)], poll));
+    assert!(debounce.update(vec![at(1200), at(3000)], settle));
+}
+
+#[test]
+fn the_preemption_count_is_the_higher_priority_jobs_in_the_busy_period() {
+    let task = |id: &str, prio: u8, wcet: u32, inter_arrival: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("high", 3, 10, 40), task("mid", 2, 20, 100), task("low", 1, 50, 1000)]);
+
+    let rta = rta::response_time(&tasks, &RtaConfig::default());
+    // low finishes at 50 + 3 * 10 + 20 = 100, high is released 3 times in the 100 cycles and mid once.
+    assert_eq!(rta.results.0[2].response_time, Some(100));
+    assert_eq!(rta.preemptions, vec![Some(0), Some(1), Some(4)]);
+}
+
+#[test]
+fn replayed_laps_give_the_tasks_of_the_live_run() {
+    let dir = env::temp_dir().join(format!("easy-poc-record-laps-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let config = AnalysisConfig {
+        elf: FIXTURE.to_owned(),
+        tasks: vec![InputTask {
+            name: "gpio_handler".to_owned(),
+            interrupt: "IO_IRQ_BANK0".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: Some(dir.clone()),
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let live = run_analysis(&config);
+    let replayed = run_analysis(&AnalysisConfig {
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let (live, replayed) = (live.unwrap(), replayed.unwrap());
+
+    assert_eq!(replayed.path_counts, live.path_counts);
+    assert_eq!(replayed.combination_count, live.combination_count);
+    assert_eq!(replayed.worst_paths, live.worst_paths);
+    assert_eq!(replayed.worst.0.len(), 1);
+    let (live, replayed) = (&live.worst.0[0], &replayed.worst.0[0]);
+    assert_eq!((replayed.wcet, replayed.response_time), (live.wcet, live.response_time));
+    assert!(trace_eq(&replayed.task.trace, &live.task.trace));
+}
+
+#[test]
+fn deadline_monotonic_priorities_reorder_the_tasks_and_make_them_schedulable() {
+    let dir = env::temp_dir().join(format!("easy-poc-dm-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("urgent", 20), &dir.join("urgent.json")).unwrap();
+    dump_trace(&trace("lazy", 40), &dir.join("lazy.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    // urgent is configured below lazy, 20 + 40 cycles against its deadline of 50.
+    let tasks = vec![input("urgent", "TIMER_IRQ_0", 1, 50), input("lazy", "TIMER_IRQ_1", 2, 1000)];
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: tasks.clone(),
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("urgent".to_owned(), dir.join("urgent.json")),
+            ("lazy".to_owned(), dir.join("lazy.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let assigned = deadline_monotonic(&tasks, None);
+    let configured = run_analysis(&config);
+    let dm = run_analysis(&AnalysisConfig {
+        tasks: assigned.clone(),
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    let priorities: Vec<(&str, u32)> = assigned.iter().map(|t| (t.name.as_str(), t.priority)).collect();
+    assert_eq!(priorities, vec![("urgent", 2), ("lazy", 1)]);
+    // with a PRIGROUP the priorities are shifted past the subpriority bits.
+    let shifted: Vec<u32> = deadline_monotonic(&tasks, Some(1)).iter().map(|t| t.priority).collect();
+    assert_eq!(shifted, vec![8, 4]);
+    assert!(!schedulability(&configured.unwrap()).schedulable);
+    let dm = schedulability(&dm.unwrap());
+    assert!(dm.schedulable);
+    let response_times: Vec<Option<u32>> = dm.tasks.iter().map(|t| t.response_time).collect();
+    assert_eq!(response_times, vec![Some(20), Some(60)]);
+}
+
+#[test]
+fn ignoring_a_single_user_resource_changes_nothing_and_a_shared_one_warns() {
+    let dir = env::temp_dir().join(format!("easy-poc-ignore-resource-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let trace = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    // 4096 is only locked by low, 8192 by both.
+    let low = trace("low", 100, vec![section("4096", 10, 30), section("8192", 40, 70)]);
+    dump_trace(&low, &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20, vec![section("8192", 5, 10)]), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "IO_IRQ_BANK0", 1), input("high", "TIMER_IRQ_0", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let all = run_analysis(&config);
+    let config = AnalysisConfig {
+        ignored_resources: vec!["4096".to_owned()],
+        ..config
+    };
+    let single = run_analysis(&config);
+    let shared = run_analysis(&AnalysisConfig {
+        ignored_resources: vec!["0x2000".to_owned()],
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let (all, single, shared) = (all.unwrap(), single.unwrap(), shared.unwrap());
+
+    let results = |report: &AnalysisReport| -> Vec<(Option<u32>, u32)> {
+        report.worst.0.iter().map(|r| (r.response_time, r.blocking)).collect()
+    };
+    let ignored_warnings = |report: &AnalysisReport| {
+        report.warnings.iter().filter(|w| w.kind == WarningKind::SharedIgnoredResource).count()
+    };
+    // high is blocked by the 30 cycles low holds 8192.
+    assert_eq!(results(&all), vec![(Some(120), 0), (Some(50), 30)]);
+    assert_eq!(results(&single), results(&all));
+    assert_eq!(ignored_warnings(&single), 0);
+    assert_eq!(results(&shared), vec![(Some(120), 0), (Some(20), 0)]);
+    assert_eq!(ignored_warnings(&shared), 1);
+}
+
+#[test]
+fn a_bus_contention_allowance_inflates_the_response_times() {
+    let task = |id: &str, prio: u8, wcet: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("high", 2, 10), task("low", 1, 30)]);
+    let config = RtaConfig {
+        bus_contention: HashMap::from([("high".to_owned(), 7)]),
+        ..RtaConfig::default()
+    };
+
+    let response_times = |config: &RtaConfig| -> Vec<Option<u32>> {
+        rta::response_time(&tasks, config).results.0.iter().map(|r| r.response_time).collect()
+    };
+    assert_eq!(response_times(&RtaConfig::default()), vec![Some(10), Some(40)]);
+    // every job of high pays it, its own and the one preempting low.
+    assert_eq!(response_times(&config), vec![Some(10 + 7), Some(40 + 7)]);
+}
+
+#[test]
+fn the_clock_sweep_finds_the_lowest_schedulable_clock() {
+    assert_eq!(parse_sweep("1000000,2000000,100000"), Ok((1_000_000, 2_000_000, 100_000)));
+    assert!(parse_sweep("2000000,1000000,100000").is_err());
+    assert!(parse_sweep("1000000,2000000,0").is_err());
+    assert!(parse_sweep("1000000,2000000").is_err());
+    assert!(parse_sweep("1MHz,2MHz,1").is_err());
+
+    let dir = env::temp_dir().join(format!("easy-poc-clock-sweep-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = Task {
+        id: "timer".to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: "timer".to_owned(),
+            start: 0,
+            end: 60,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace, &dir.join("timer.json")).unwrap();
+    // 60 cycles against a deadline of 50 us, which is 50 cycles at 1 MHz and 60 at 1.2 MHz.
+    let tasks = vec![InputTask {
+        name: "timer".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 50,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    }];
+    let mut config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![("timer".to_owned(), dir.join("timer.json"))],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let mut schedulable = vec![];
+    for hz in (1_000_000..=2_000_000).step_by(100_000) {
+        config.tasks = at_clock(&tasks, 1_000_000, hz).unwrap();
+        schedulable.push((hz, schedulability(&run_analysis(&config).unwrap()).schedulable));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(at_clock(&tasks, 1_000_000, 1_100_000).unwrap()[0].deadline, 55);
+    let lowest = schedulable.iter().find(|(_, schedulable)| *schedulable).map(|(hz, _)| *hz);
+    assert_eq!(lowest, Some(1_200_000));
+    assert!(schedulable.iter().all(|&(hz, schedulable)| schedulable == (hz >= 1_200_000)));
+}
+
+#[test]
+fn tasks_on_one_vector_need_one_priority_unless_they_are_dispatched() {
+    let task = |name: &str, priority: u32, entry_symbol: Option<&str>| InputTask {
+        name: name.to_owned(),
+        interrupt: "SW0_IRQ".to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: entry_symbol.map(str::to_owned),
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+
+    let conflicting = [task("a", 1, None), task("b", 2, None)];
+    assert_eq!(
+        validate_priorities(&conflicting, EqualPriority::Error),
+        Err("tasks a and b are both on SW0_IRQ but have priorities 1 and 2, a vector has one priority (give \
+             every task on it an entry symbol if it is a dispatcher)"
+            .to_owned())
+    );
+    // one of them without an entry symbol is still a conflict.
+    let half = [task("a", 1, Some("a_body")), task("b", 2, None)];
+    assert!(validate_priorities(&half, EqualPriority::Error).is_err());
+    let dispatched = [task("a", 1, Some("a_body")), task("b", 2, Some("b_body"))];
+    assert_eq!(validate_priorities(&dispatched, EqualPriority::Error), Ok(()));
+}
+
+#[test]
+fn the_markdown_report_has_a_header_and_a_row_per_task() {
+    let dir = env::temp_dir().join(format!("easy-poc-markdown-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let markdown = markdown_report(&report, &TaskFilter::new(&[]));
+    let lines: Vec<&str> = markdown.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "| Task | Priority | WCET | Blocking | Interference | Response time | Deadline | Schedulable |",
+            "| ---- | -------- | ---- | -------- | ------------ | ------------- | -------- | ----------- |",
+            "| low  | 1        | 40   | 0        | 20           | 60            | 50       | ❌           |",
+            "| high | 2        | 20   | 0        | 0            | 20            | 1000     | ✅           |",
+            "",
+            "Max utilization: 0.060",
+        ]
+    );
+}
+
+#[test]
+fn the_warnings_of_a_run_are_in_the_saved_report() {
+    let dir = env::temp_dir().join(format!("easy-poc-saved-warnings-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = Task {
+        id: "timer".to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: "timer".to_owned(),
+            start: 0,
+            end: 40,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace, &dir.join("timer.json")).unwrap();
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![InputTask {
+            name: "timer".to_owned(),
+            interrupt: "TIM2".to_owned(),
+            priority: 1,
+            deadline: 1000,
+            activation: Activation::Periodic { period: 1000 },
+            cs_mechanism: CsMechanism::Nvic,
+            entry_symbol: None,
+            elf: None,
+            enabled_after: None,
+            bus_contention: 0,
+            jitter: 0,
+            core: None,
+            background: false,
+            expected_wcet: None,
+            tags: BTreeMap::new(),
+        }],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![("timer".to_owned(), dir.join("timer.json"))],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Stm32f4,
+    };
+
+    let path = dir.join("report.json");
+    let report = run_analysis(&config);
+    let saved = report.as_ref().ok().map(|report| {
+        save_report(report, &path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        (value, load_report(&path).unwrap())
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+    let (value, loaded) = saved.unwrap();
+
+    // the stm32f4 has a Cortex-M4, symex times a Cortex-M0+, a warning about no task in particular.
+    assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+    let warning = &report.warnings[0];
+    assert_eq!(warning.kind, WarningKind::CycleModel);
+    assert_eq!(warning.task, None);
+    assert_eq!(value["warnings"][0]["kind"], "cycle-model");
+    assert!(value["warnings"][0]["task"].is_null());
+    assert_eq!(value["warnings"][0]["message"], warning.message.as_str());
+    assert_eq!(loaded.warnings, report.warnings);
+}
+
+#[test]
+fn a_period_override_changes_the_interference_in_the_rta() {
+    let dir = env::temp_dir().join(format!("easy-poc-set-period-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 100), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let before = run_analysis(&config);
+    let mut tasks = config.tasks.clone();
+    let overrides = vec![parse_period("high=30/25").unwrap()];
+    let applied = set_periods(&mut tasks, &overrides, 1_000_000);
+    let after = run_analysis(&AnalysisConfig { tasks, ..config });
+    fs::remove_dir_all(&dir).unwrap();
+    let (before, after) = (before.unwrap(), after.unwrap());
+
+    assert_eq!(applied.unwrap(), vec!["high inter arrival time 30 cycles, deadline 25 cycles".to_owned()]);
+    let response_times = |report: &AnalysisReport| -> Vec<(String, Option<u32>, u32)> {
+        let results = report.worst.0.iter();
+        results.map(|r| (r.task.id.clone(), r.response_time, r.task.deadline)).collect()
+    };
+    // high once in 60 cycles at a period of 1000, four times as 40 + 4 * 20 = 120 cycles at a period of 30.
+    assert_eq!(
+        response_times(&before),
+        vec![("low".to_owned(), Some(60), 100), ("high".to_owned(), Some(20), 1000)]
+    );
+    assert_eq!(
+        response_times(&after),
+        vec![("low".to_owned(), Some(120), 100), ("high".to_owned(), Some(20), 25)]
+    );
+
+    let mut tasks = vec![];
+    let unknown = vec![parse_period("idle=10ms").unwrap()];
+    assert!(set_periods(&mut tasks, &unknown, 1_000_000).is_err());
+    assert!(parse_period("high").is_err());
+}
+
+#[test]
+fn tied_response_times_go_to_the_larger_wcet_then_blocking_then_the_first_combination() {
+    let result = |id: &str, wcet: u32, blocking: u32| TaskResult {
+        task: Task {
+            id: id.to_owned(),
+            prio: 1,
+            deadline: 1000,
+            inter_arrival: 1000,
+            trace: Trace {
+                id: id.to_owned(),
+                start: 0,
+                end: wcet,
+                inner: vec![],
+            },
+        },
+        response_time: Some(50),
+        wcet,
+        blocking,
+        interference: 0,
+    };
+    // every result responds at 50. a ties on WCET and is decided by blocking, b by WCET, c is the same
+    // everywhere.
+    let combinations = vec![
+        TasksResult(vec![result("a", 30, 5), result("b", 20, 30), result("c", 10, 0)]),
+        TasksResult(vec![result("a", 30, 20), result("b", 40, 10), result("c", 10, 0)]),
+        TasksResult(vec![result("a", 30, 10), result("b", 30, 20), result("c", 10, 0)]),
+    ];
+
+    assert_eq!(worst_combinations(&combinations), vec![1, 1, 0]);
+    let worst = find_worst(combinations);
+    let worst: Vec<(&str, u32, u32)> =
+        worst.0.iter().map(|r| (r.task.id.as_str(), r.wcet, r.blocking)).collect();
+    assert_eq!(worst, vec![("a", 30, 20), ("b", 40, 10), ("c", 10, 0)]);
+}
+
+#[test]
+fn a_task_is_only_scheduled_if_its_enabled_after_symbol_enables_it() {
+    let dir = env::temp_dir().join(format!("easy-poc-enabled-after-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // the laps of the handlers, the enables come from symex on the fixture.
+    let gpio = vec![PathLaps {
+        max_cycles: 30,
+        cycle_laps: vec![(15, "8192".to_owned()), (28, "8192".to_owned())],
+    }];
+    let timer = vec![PathLaps {
+        max_cycles: 20,
+        cycle_laps: vec![],
+    }];
+    record_laps(&dir, "gpio_handler", &gpio).unwrap();
+    record_laps(&dir, "timer", &timer).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        // the gpio handler unlocks its resources through ISER, enabling IO_IRQ_BANK0 and UART0_IRQ.
+        enabled_after: Some("IO_IRQ_BANK0".to_owned()),
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: FIXTURE.to_owned(),
+        tasks: vec![input("gpio_handler", "IO_IRQ_BANK0", 1), input("timer", "TIMER_IRQ_0", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    assert_eq!(report.not_enabled, vec![("timer".to_owned(), "IO_IRQ_BANK0".to_owned())]);
+    let scheduled: Vec<&str> = report.worst.0.iter().map(|r| r.task.id.as_str()).collect();
+    assert_eq!(scheduled, vec!["gpio_handler"]);
+}
+
+#[test]
+fn validate_trace_finds_inverted_outside_and_overlapping_sections() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    // two sections touching at cycle 20, the second with one nested in it.
+    let valid = section(
+        "task",
+        0,
+        40,
+        vec![section("a", 5, 20, vec![]), section("b", 20, 35, vec![section("c", 22, 30, vec![])])],
+    );
+    assert_eq!(validate_trace(&valid), Ok(()));
+
+    let inverted = section("task", 0, 40, vec![section("a", 20, 5, vec![])]);
+    let expected = TraceIntegrityError::Inverted {
+        id: "a".to_owned(),
+        start: 20,
+        end: 5,
+    };
+    assert_eq!(validate_trace(&inverted), Err(expected));
+
+    let outside = section("task", 0, 40, vec![section("b", 20, 35, vec![section("c", 30, 38, vec![])])]);
+    let expected = TraceIntegrityError::OutsideParent {
+        id: "c".to_owned(),
+        parent: "b".to_owned(),
+    };
+    assert_eq!(validate_trace(&outside), Err(expected));
+
+    // listed out of order, the overlap is still between the earlier and the later one.
+    let overlap = section("task", 0, 40, vec![section("b", 15, 30, vec![]), section("a", 5, 20, vec![])]);
+    let expected = TraceIntegrityError::Overlap {
+        id: "b".to_owned(),
+        previous: "a".to_owned(),
+    };
+    assert_eq!(validate_trace(&overlap), Err(expected));
+}
+
+#[test]
+fn only_the_top_k_paths_of_each_task_are_combined() {
+    let dir = env::temp_dir().join(format!("easy-poc-top-k-paths-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // in no particular order, as a hand written laps file can have them.
+    record_laps(&dir, "low", &[path(20), path(50), path(30), path(40)]).unwrap();
+    record_laps(&dir, "high", &[path(5), path(15), path(10)]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: Some(2),
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let analysed = |config: &AnalysisConfig| {
+        let mut combinations = vec![];
+        let report = run_analysis_streaming(config, |event| {
+            if let AnalysisEvent::CombinationEvaluated { paths, .. } = event {
+                combinations.push(paths.to_vec());
+            }
+        });
+        report.map(|report| (report, combinations))
+    };
+    let top_two = analysed(&config);
+    let worst_only = analysed(&AnalysisConfig {
+        max_paths: Some(1),
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let ((top_two, top_two_combinations), (worst_only, worst_only_combinations)) =
+        (top_two.unwrap(), worst_only.unwrap());
+
+    // paths are numbered longest first, so the two kept of each task are paths 0 and 1.
+    let kept: BTreeSet<(String, usize)> = top_two_combinations.iter().flatten().cloned().collect();
+    let expected: BTreeSet<(String, usize)> =
+        [("low", 0), ("low", 1), ("high", 0), ("high", 1)].map(|(t, p)| (t.to_owned(), p)).into();
+    assert_eq!(kept, expected);
+    assert_eq!(top_two.combination_count, 4);
+    assert_eq!(top_two.path_counts, vec![("low".to_owned(), 2), ("high".to_owned(), 2)]);
+    let wcets: Vec<u32> = top_two.worst.0.iter().map(|r| r.wcet).collect();
+    assert_eq!(wcets, vec![50, 15]);
+
+    // K = 1 is the worst path of each task alone.
+    assert_eq!(worst_only_combinations, vec![vec![("high".to_owned(), 0), ("low".to_owned(), 0)]]);
+    let wcets: Vec<u32> = worst_only.worst.0.iter().map(|r| r.wcet).collect();
+    assert_eq!(wcets, vec![50, 15]);
+}
+
+// Bytes allocated and not yet freed by the current thread, and the most there were at once, so a test can
+// check how much memory it held. Tests run on threads of their own, so they don't count each other.
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_BYTES: Cell<usize> = const { Cell::new(0) };
+    static PEAK_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE_BYTES.with(|live| {
+            live.set(live.get() + layout.size());
+            live.get()
+        });
+        PEAK_BYTES.with(|peak| peak.set(peak.get().max(live)));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // memory freed by another thread than the one allocating it is only taken off that one.
+        LIVE_BYTES.with(|live| live.set(live.get().saturating_sub(layout.size())));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn a_large_product_of_paths_is_analysed_without_holding_the_combinations() {
+    let dir = env::temp_dir().join(format!("easy-poc-large-product-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    let interrupts = [
+        "TIMER_IRQ_0",
+        "TIMER_IRQ_1",
+        "TIMER_IRQ_2",
+        "TIMER_IRQ_3",
+        "PWM_IRQ_WRAP",
+        "USBCTRL_IRQ",
+        "XIP_IRQ",
+    ];
+    for i in 0..interrupts.len() {
+        record_laps(&dir, &format!("task{i}"), &[path(10), path(20), path(30), path(40)]).unwrap();
+    }
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: interrupts
+            .iter()
+            .enumerate()
+            .map(|(i, interrupt)| input(&format!("task{i}"), interrupt, i as u32 + 1))
+            .collect(),
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    // 4^7 = 16384 combinations. Held as a list they would take over 30 MB.
+    let peak_before = LIVE_BYTES.with(|live| live.get());
+    PEAK_BYTES.with(|peak| peak.set(peak_before));
+    let report = run_analysis(&config);
+    let peak = PEAK_BYTES.with(|peak| peak.get()) - peak_before;
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    assert_eq!(report.combination_count, 16384);
+    assert!(peak < 8 << 20, "held {peak} bytes at once");
+    // the longest path of each task is path 0, the lowest priority task waits for all the others.
+    assert!(report.worst_paths.iter().all(|(_, path)| *path == 0));
+    let lowest = report.worst.0.iter().find(|r| r.task.id == "task0").unwrap();
+    assert_eq!(lowest.response_time, Some(280));
+}
+
+#[test]
+fn a_lock_left_held_on_one_path_of_two_warns() {
+    let dir = env::temp_dir().join(format!("easy-poc-unreleased-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // the shorter path masks IO_IRQ_BANK0 (bit 13) and unmasks it, the longer one returns with it masked.
+    let released = PathLaps {
+        max_cycles: 30,
+        cycle_laps: vec![(15, "8192".to_owned()), (28, "8192".to_owned())],
+    };
+    let held = PathLaps {
+        max_cycles: 40,
+        cycle_laps: vec![(10, "8192".to_owned())],
+    };
+    record_laps(&dir, "task", &[released, held]).unwrap();
+    let task = InputTask {
+        name: "task".to_owned(),
+        interrupt: "TIMER_IRQ_0".to_owned(),
+        priority: 1,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![task],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let unreleased: Vec<&Warning> =
+        report.warnings.iter().filter(|w| w.kind == WarningKind::UnreleasedLock).collect();
+    assert_eq!(unreleased.len(), 1, "{:?}", report.warnings);
+    assert_eq!(unreleased[0].task.as_deref(), Some("task"));
+    // paths are numbered longest first, the one leaving the lock held is path 0.
+    assert!(
+        unreleased[0].message.contains("is released on 1 paths but left locked on path 0"),
+        "{}",
+        unreleased[0].message
+    );
+}
+
+#[test]
+fn the_resource_matrix_marks_the_users_and_ceilings() {
+    let dir = env::temp_dir().join(format!("easy-poc-resource-matrix-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let trace = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    // 8192 masks IO_IRQ_BANK0, 1048576 UART0_IRQ.
+    let low = trace("low", 40, vec![section("8192", 5, 15)]);
+    let mid = trace("mid", 30, vec![section("8192", 2, 8), section("1048576", 10, 14)]);
+    let high = trace("high", 20, vec![section("1048576", 3, 6)]);
+    dump_trace(&low, &dir.join("low.json")).unwrap();
+    dump_trace(&mid, &dir.join("mid.json")).unwrap();
+    dump_trace(&high, &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![
+            input("low", "TIMER_IRQ_0", 1, 1000),
+            input("mid", "TIMER_IRQ_1", 2, 1000),
+            input("high", "TIMER_IRQ_2", 3, 1000),
+        ],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("mid".to_owned(), dir.join("mid.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    // resources are in id order, so 1048576 comes first. Its ceiling is high's level, the other one mid's.
+    let table = report.chip.table();
+    let (uart, gpio) = (resource_name("1048576", table), resource_name("8192", table));
+    let csv = resource_matrix(&report, MatrixFormat::Csv);
+    let expected = [
+        format!("task,priority,{uart},{gpio}"),
+        "high,3,x,".to_owned(),
+        "mid,2,x,x".to_owned(),
+        "low,1,,x".to_owned(),
+        "ceiling,,3,2".to_owned(),
+    ];
+    assert_eq!(csv.lines().collect::<Vec<&str>>(), expected);
+
+    let text = resource_matrix(&report, MatrixFormat::Text);
+    let text: Vec<&str> = text.lines().collect();
+    assert_eq!(text.len(), 5);
+    let width = uart.len();
+    assert_eq!(text[0], format!("task     priority  {uart}  {gpio}"));
+    assert_eq!(text[2], format!("mid      2         {:width$}  x", "x"));
+    assert_eq!(text[4], format!("ceiling            {:width$}  2", "3"));
+}
+
+#[test]
+fn a_wcet_off_its_expected_count_by_more_than_the_tolerance_fails() {
+    let dir = env::temp_dir().join(format!("easy-poc-expected-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("mid", 30), &dir.join("mid.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, expected_wcet: Option<u32>| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![
+            input("low", "TIMER_IRQ_0", 1, Some(42)),
+            input("mid", "TIMER_IRQ_1", 2, None),
+            input("high", "TIMER_IRQ_2", 3, Some(25)),
+        ],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("mid".to_owned(), dir.join("mid.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let checked = |tolerance_percent: u32| -> Vec<(String, Option<i64>, u32, bool)> {
+        let results = check_expected(&config.tasks, &report, tolerance_percent);
+        results.iter().map(|r| (r.task.clone(), r.delta(), r.tolerance, r.passed())).collect()
+    };
+    // 10% of 42 is 4 cycles, low is 2 under it. 10% of 25 is 2 cycles, high is 5 under it. mid isn't checked.
+    assert_eq!(
+        checked(10),
+        vec![("low".to_owned(), Some(-2), 4, true), ("high".to_owned(), Some(-5), 2, false)]
+    );
+    assert_eq!(
+        checked(20),
+        vec![("low".to_owned(), Some(-2), 8, true), ("high".to_owned(), Some(-5), 5, true)]
+    );
+    // no tolerance asks for the exact count.
+    assert!(checked(0).iter().all(|(_, _, _, passed)| !passed));
+}
+
+#[test]
+fn a_sample_of_the_combinations_is_the_same_for_the_same_seed() {
+    let dir = env::temp_dir().join(format!("easy-poc-sample-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let items: Vec<u32> = (0..100).collect();
+    let (sample, total) = reservoir_sample(items.iter().copied(), 5, 7);
+    assert_eq!((sample.len(), total), (5, 100));
+    // in the order they came in.
+    assert!(sample.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(reservoir_sample(items.iter().copied(), 5, 7).0, sample);
+    assert_ne!(reservoir_sample(items.iter().copied(), 5, 8).0, sample);
+    // fewer items than asked for are all kept.
+    assert_eq!(reservoir_sample(items.iter().copied().take(3), 5, 7), (vec![0, 1, 2], 3));
+
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // in no particular order, as a hand written laps file can have them.
+    record_laps(&dir, "low", &[path(20), path(50), path(30), path(40)]).unwrap();
+    record_laps(&dir, "high", &[path(5), path(15), path(10)]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: Some(5),
+        seed: 7,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    // 4 * 3 = 12 combinations, 5 of them analysed.
+    let analysed = |config: &AnalysisConfig| {
+        let mut combinations = vec![];
+        let report = run_analysis_streaming(config, |event| {
+            if let AnalysisEvent::CombinationEvaluated { paths, .. } = event {
+                combinations.push(paths.to_vec());
+            }
+        });
+        report.map(|report| (report.sampled, combinations))
+    };
+    let first = analysed(&config);
+    let again = analysed(&config);
+    let other_seed = analysed(&AnalysisConfig { seed: 8, ..config });
+    fs::remove_dir_all(&dir).unwrap();
+    let (first, again, other_seed) = (first.unwrap(), again.unwrap(), other_seed.unwrap());
+
+    assert_eq!(first.0, Some((5, 12)));
+    assert_eq!(first.1.len(), 5);
+    assert_eq!(again.1, first.1);
+    assert_eq!(other_seed.0, Some((5, 12)));
+    assert_ne!(other_seed.1, first.1);
+}
+
+#[test]
+fn the_preemption_model_of_priorities_fifo_groups_and_cores() {
+    let task = |id: &str, prio: u8| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: 10,
+            inner: vec![],
+        },
+    };
+    // `low` and `peer` share a level, `remote` is on the other core.
+    let tasks = Tasks(vec![task("low", 1), task("peer", 1), task("high", 2), task("remote", 3)]);
+    let [low, peer, high, remote] = [0, 1, 2, 3].map(|i| &tasks.0[i]);
+    let base = RtaConfig {
+        cores: HashMap::from([("remote".to_owned(), 1)]),
+        ..RtaConfig::default()
+    };
+    let fifo = RtaConfig {
+        equal_priority: EqualPriority::Fifo,
+        cores: HashMap::from([("remote".to_owned(), 1)]),
+        ..RtaConfig::default()
+    };
+    let interferers = |model: &PreemptionModel, task: &Task| -> Vec<String> {
+        model.interferers(task, &tasks).map(|t| t.id.clone()).collect()
+    };
+
+    // fully preemptive: only a higher level on the same core preempts, a peer that started first blocks.
+    let model = PreemptionModel::new(&base);
+    assert!(model.can_preempt(high, low));
+    assert!(!model.can_preempt(low, high));
+    assert!(!model.can_preempt(peer, low));
+    assert!(!model.can_preempt(remote, low));
+    assert_eq!(interferers(&model, low), vec!["high"]);
+    assert!(interferers(&model, high).is_empty());
+    assert!(model.runs_first(peer, low));
+    assert_eq!(model.core(remote), 1);
+
+    // in a FIFO group the peer interferes instead of running first.
+    let model = PreemptionModel::new(&fifo);
+    assert!(!model.can_preempt(peer, low));
+    assert_eq!(interferers(&model, low), vec!["peer", "high"]);
+    assert_eq!(interferers(&model, peer), vec!["low", "high"]);
+    assert!(!model.runs_first(peer, low));
+    assert!(interferers(&model, remote).is_empty());
+}
+
+#[test]
+fn the_html_report_has_a_table_and_a_flame_graph_per_task() {
+    let dir = env::temp_dir().join(format!("easy-poc-html-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let html = html_report(&report, &TaskFilter::new(&[]));
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert_eq!(html.matches("<table>").count(), 1);
+    // the header row and a row for each task.
+    assert_eq!(html.matches("<tr>").count(), 3);
+    let low = "<tr><td>low</td><td>1</td><td>40</td><td>0</td><td>20</td><td>60</td><td>50</td><td>no</td>";
+    assert!(html.contains(low));
+    assert_eq!(html.matches("<svg").count(), 2);
+    assert!(html.contains("<h2>low</h2>\n<svg") && html.contains("<h2>high</h2>\n<svg"));
+    // nothing to fetch, so the file can be sent around on its own.
+    assert!(!html.contains("src=") && !html.contains("href="));
+
+    let only_high = html_report(&report, &TaskFilter::new(&["high".to_owned()]));
+    assert_eq!(only_high.matches("<svg").count(), 1);
+}
+
+#[test]
+fn a_schedulable_set_with_tiny_wcets_warns_about_the_low_utilization() {
+    let dir = env::temp_dir().join(format!("easy-poc-low-utilization-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 2), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 1), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1_000_000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // 3 cycles in a million, as when symex stops right at the entry of each handler.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    let unschedulable = run_analysis(&AnalysisConfig {
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 2), input("high", "TIMER_IRQ_1", 2, 1000)],
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let (report, unschedulable) = (report.unwrap(), unschedulable.unwrap());
+
+    let low_utilization = |report: &AnalysisReport| -> Vec<(Option<String>, String)> {
+        let warnings = report.warnings.iter().filter(|w| w.kind == WarningKind::LowUtilization);
+        warnings.map(|w| (w.task.clone(), w.message.clone())).collect()
+    };
+    let message = "max utilization 0.000003 is below 0.001, the WCETs may not have been measured properly";
+    assert_eq!(low_utilization(&report), vec![(None, message.to_owned())]);
+    // a set that misses a deadline has a real problem to look at first.
+    assert!(low_utilization(&unschedulable).is_empty());
+}
+
+#[test]
+fn the_streaming_analysis_reports_the_tasks_then_the_combinations_then_the_report() {
+    let dir = env::temp_dir().join(format!("easy-poc-streaming-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let mut events = vec![];
+    let report = run_analysis_streaming(&config, |event| {
+        events.push(match event {
+            AnalysisEvent::TaskAnalyzed { task, paths } => format!("task {task} with {paths} paths"),
+            AnalysisEvent::CombinationEvaluated { index, schedulable, .. } => {
+                format!("combination {index} schedulable {schedulable}")
+            }
+            AnalysisEvent::Done(report) => format!("done with {} combinations", report.combination_count),
+        })
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    report.unwrap();
+
+    // trace files are one path each, so there is a single combination, in which low misses.
+    assert_eq!(
+        events,
+        vec![
+            "task low with 1 paths",
+            "task high with 1 paths",
+            "combination 0 schedulable false",
+            "done with 1 combinations",
+        ]
+    );
+}
+
+#[test]
+fn a_new_task_fits_only_at_the_priorities_keeping_every_deadline() {
+    let dir = env::temp_dir().join(format!("easy-poc-fit-task-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 30), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 30), &dir.join("high.json")).unwrap();
+    dump_trace(&trace("new", 20), &dir.join("new.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let mut config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 1000), input("high", "TIMER_IRQ_1", 4, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+            ("new".to_owned(), dir.join("new.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let fit = parse_fit_task("new@TIMER_IRQ_2=1000/40").unwrap();
+    let fits = fit_priorities(&mut config, &fit, 1_000_000);
+    fs::remove_dir_all(&dir).unwrap();
+    let fits = fits.unwrap();
+
+    // 1 and 4 are taken. Below high, new responds in 20 + 30 = 50 cycles, past its deadline of 40.
+    let outcomes: Vec<(u32, &str)> = fits
+        .iter()
+        .map(|(priority, fit)| match fit {
+            PriorityFit::Skipped(_) => (*priority, "skipped"),
+            PriorityFit::Schedulable => (*priority, "schedulable"),
+            PriorityFit::Missing { missing: 1, total: 3 } => (*priority, "new misses"),
+            PriorityFit::Missing { .. } => (*priority, "others miss"),
+        })
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![(1, "skipped"), (2, "new misses"), (3, "new misses"), (4, "skipped"), (5, "schedulable")]
+    );
+
+    assert!(parse_fit_task("new=1000").is_err());
+    assert_eq!(parse_fit_task("new@TIMER_IRQ_2=1000").unwrap().deadline, None);
+}
+
+#[test]
+fn the_task_list_loads_and_a_malformed_entry_is_an_error() {
+    // the task list of the fixture, shipped next to it.
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tasks.toml");
+    let list = load_task_list(path, Chip::Rp2040.table(), 125_000_000).unwrap();
+    let first = &list.tasks[0];
+    assert_eq!((first.name.as_str(), first.interrupt.as_str()), ("button_handler", "IO_IRQ_BANK0"));
+    assert_eq!((first.priority, first.deadline), (2, 125000));
+    assert_eq!(first.activation, Activation::Periodic { period: 125000 });
+
+    let dir = env::temp_dir().join(format!("easy-poc-task-list-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let load = |name: &str, entry: &str| {
+        let path = dir.join(name);
+        fs::write(&path, format!("[[task]]\n{entry}")).unwrap();
+        load_task_list(&path.to_string_lossy(), Chip::Rp2040.table(), 125_000_000)
+    };
+    let entry = |priority: &str, interrupt: &str, deadline: &str| {
+        format!(
+            "name = \"timer\"\ninterrupt = \"{interrupt}\"\npriority = {priority}\ndeadline = {deadline}\n\
+             inter_arrival = 1000\n"
+        )
+    };
+    // a priority given as a string, an interrupt the RP2040 doesn't have and a deadline of no time at all.
+    let malformed = load("malformed.toml", &entry("\"high\"", "TIMER_IRQ_0", "1000"));
+    let unknown = load("unknown.toml", &entry("1", "TIMER_IRQ_9", "1000"));
+    let zero = load("zero.toml", &entry("1", "TIMER_IRQ_0", "0"));
+    let with_unit = load("unit.toml", &entry("1", "TIMER_IRQ_0", "\"8us\""));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(matches!(malformed, Err(ConfigError::Parse(..))), "{:?}", malformed.err());
+    match unknown {
+        Err(ConfigError::UnknownInterrupts(unknown)) => assert_eq!(unknown[0].0, "timer"),
+        other => panic!("expected an unknown interrupt, got {:?}", other.err()),
+    }
+    let expected = ConfigError::InvalidField {
+        task: "timer".to_owned(),
+        field: "deadline",
+        message: "has to be at least 1 cycle".to_owned(),
+    };
+    assert_eq!(zero.err(), Some(expected));
+    // 8us at 125 MHz.
+    assert_eq!(with_unit.unwrap().tasks[0].deadline, 1000);
+}
+
+#[test]
+fn a_ceiling_on_the_command_line_is_a_resource_and_a_priority() {
+    assert_eq!(parse_ceiling("0x102000=3"), Ok(("0x102000".to_owned(), 3)));
+    assert_eq!(parse_ceiling("8192=12"), Ok(("8192".to_owned(), 12)));
+    assert!(parse_ceiling("0x102000").is_err());
+    assert_eq!(parse_ceiling("8192=high"), Err("`high` is not a valid priority".to_owned()));
+}
+
+#[test]
+fn a_server_on_the_command_line_is_a_budget_a_period_and_maybe_a_priority() {
+    assert_eq!(parse_server("100/1000"), Ok((100, 1000, None)));
+    assert_eq!(parse_server("100/1000@4"), Ok((100, 1000, Some(4))));
+    assert!(parse_server("100").is_err());
+    assert_eq!(parse_server("100/1ms"), Err("`1ms` is not a valid number".to_owned()));
+    assert!(parse_server("100/1000@high").is_err());
+}
+
+#[test]
+fn a_trace_file_on_the_command_line_is_a_task_and_a_file() {
+    assert_eq!(
+        parse_trace_file("timer=traces/timer.json"),
+        Ok(("timer".to_owned(), PathBuf::from("traces/timer.json")))
+    );
+    // only the first `=` separates them.
+    assert_eq!(parse_trace_file("timer=a=b.json"), Ok(("timer".to_owned(), PathBuf::from("a=b.json"))));
+    assert!(parse_trace_file("traces/timer.json").is_err());
+}
+
+#[test]
+fn a_reservation_on_the_command_line_is_a_budget_per_period() {
+    let reservation = parse_reservation("300/1000").unwrap();
+    assert_eq!((reservation.budget, reservation.period), (300, 1000));
+    assert!(parse_reservation("300").is_err());
+    assert_eq!(parse_reservation("300/-1").err(), Some("`-1` is not a valid number".to_owned()));
+}
+
+#[test]
+fn a_correlation_on_the_command_line_is_a_path_of_each_task() {
+    assert_eq!(
+        parse_correlation("gpio:1,timer:0"),
+        Ok(vec![("gpio".to_owned(), 1), ("timer".to_owned(), 0)])
+    );
+    assert_eq!(parse_correlation("gpio:2"), Ok(vec![("gpio".to_owned(), 2)]));
+    assert!(parse_correlation("gpio:1,timer").is_err());
+    assert_eq!(parse_correlation("gpio:first"), Err("`first` is not a valid path index".to_owned()));
+}
+
+#[test]
+fn a_chain_on_the_command_line_has_its_tasks_delay_and_deadline() {
+    let chain = parse_chain("input=gpio,filter,uart+10/500").unwrap();
+    assert_eq!(chain.name, "input");
+    assert_eq!(chain.tasks, vec!["gpio", "filter", "uart"]);
+    assert_eq!((chain.activation_delay, chain.deadline), (10, Some(500)));
+
+    // the delay and deadline are optional.
+    let chain = parse_chain("input=gpio,uart").unwrap();
+    assert_eq!((chain.activation_delay, chain.deadline), (0, None));
+    assert_eq!(parse_chain("input=gpio/500").unwrap().deadline, Some(500));
+
+    assert!(parse_chain("gpio,uart").is_err());
+    let invalid = parse_chain("input=gpio+fast").err();
+    assert_eq!(invalid, Some("`fast` is not a valid number of cycles".to_owned()));
+}
+
+#[test]
+fn a_tag_on_the_command_line_is_a_key_and_a_value() {
+    assert_eq!(parse_tag("subsystem=motor"), Ok(("subsystem".to_owned(), "motor".to_owned())));
+    assert_eq!(parse_tag("note=a=b"), Ok(("note".to_owned(), "a=b".to_owned())));
+    assert_eq!(parse_tag("empty="), Ok(("empty".to_owned(), String::new())));
+    assert!(parse_tag("motor").is_err());
+}
+
+#[test]
+fn a_report_ending_in_bin_is_saved_in_the_binary_format() {
+    assert!(is_binary_report(Path::new("out/report.bin")));
+    assert!(!is_binary_report(Path::new("out/report.json")));
+    assert!(!is_binary_report(Path::new("out/report")));
+    assert!(!is_binary_report(Path::new("out/bin")));
+}
+
+#[test]
+fn release_jitter_adds_jobs_of_higher_priority_tasks_and_delays_the_own() {
+    let task = |id: &str, prio: u8, wcet: u32, inter_arrival: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("low", 1, 30, 1000), task("high", 2, 20, 60)]);
+    let results = |config: &RtaConfig| -> Vec<(u32, Option<u32>)> {
+        let rta = rta::response_time(&tasks, config);
+        rta.results.0.iter().map(|r| (r.interference, r.response_time)).collect()
+    };
+
+    // on time, one job of high fits in low's 50 cycles.
+    assert_eq!(results(&RtaConfig::default()), vec![(20, Some(50)), (0, Some(20))]);
+
+    // released up to 15 cycles late, a second job of high can come within 50 + 15 cycles of the first. Each
+    // task also waits for its own late release.
+    let jittery = RtaConfig {
+        jitter: HashMap::from([("low".to_owned(), 5), ("high".to_owned(), 15)]),
+        ..RtaConfig::default()
+    };
+    assert_eq!(results(&jittery), vec![(40, Some(75)), (0, Some(35))]);
+}
+
+#[test]
+fn a_spinlock_held_on_the_other_core_blocks_but_an_interrupt_mask_does_not() {
+    let section = |id: &str, start: u32, end: u32| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let task = |id: &str, prio: u8, wcet: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival: 1000,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner,
+        },
+    };
+    let spinlock = format!("{SPINLOCK_LABEL}3");
+    let tasks = Tasks(vec![
+        task("low", 1, 40, vec![section(&spinlock, 5, 15), section("8192", 20, 24)]),
+        task("high", 2, 20, vec![]),
+        task("other", 3, 30, vec![section(&spinlock, 2, 10), section("8192", 12, 22)]),
+    ]);
+    let results = |config: &RtaConfig| -> Vec<(u32, u32, Option<u32>)> {
+        let rta = rta::response_time(&tasks, config);
+        rta.results.0.iter().map(|r| (r.blocking, r.interference, r.response_time)).collect()
+    };
+
+    // on one core both sections of low are under the ceiling of other and block high and other.
+    let single = results(&RtaConfig::default());
+    assert_eq!(single, vec![(0, 50, Some(90)), (10, 30, Some(60)), (10, 0, Some(40))]);
+
+    // with other on the second core nothing preempts across, low and other each spin once for the
+    // longest section of the other on the spinlock. The interrupt mask is never waited for.
+    let dual = RtaConfig {
+        cores: HashMap::from([("other".to_owned(), 1)]),
+        ..RtaConfig::default()
+    };
+    assert_eq!(results(&dual), vec![(8, 20, Some(68)), (0, 0, Some(20)), (10, 0, Some(40))]);
+}
+
+#[test]
+fn a_task_slower_than_its_baseline_by_more_than_the_tolerance_regressed() {
+    let dir = env::temp_dir().join(format!("easy-poc-baseline-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    // a `--format json` report of an earlier run, the other fields are ignored.
+    let path = dir.join("baseline.json");
+    let json = r#"{"tasks": [{"id": "low", "wcet": 40, "response_time": 50}]}"#;
+    fs::write(&path, json).unwrap();
+    let loaded = load_baseline(&path);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let baseline_task = |id: &str, wcet: u32, response_time: Option<u32>| BaselineTask {
+        id: id.to_owned(),
+        wcet,
+        response_time,
+    };
+    // low responded in 50 cycles before and does in 60 now, high wasn't in the baseline yet.
+    assert_eq!(loaded.unwrap(), vec![baseline_task("low", 40, Some(50))]);
+    let baseline = vec![baseline_task("low", 40, Some(50)), baseline_task("gone", 10, Some(10))];
+    let regressed = |tolerance_percent: u32| -> Vec<(String, bool, bool)> {
+        let checks = check_baseline(&report, &baseline, tolerance_percent);
+        checks.iter().map(|c| (c.task.clone(), c.baseline.is_some(), c.regressed())).collect()
+    };
+    // 10% of 50 allows 55 cycles, 20% allows 60.
+    assert_eq!(regressed(10), vec![("low".to_owned(), true, true), ("high".to_owned(), false, false)]);
+    assert_eq!(regressed(20), vec![("low".to_owned(), true, false), ("high".to_owned(), false, false)]);
+
+    // a response time that became unbounded regressed whatever the tolerance, getting faster never did.
+    let check = |response_time: Option<u32>, baseline: BaselineTask| BaselineCheck {
+        task: "low".to_owned(),
+        wcet: 40,
+        response_time,
+        baseline: Some(baseline),
+        tolerance_percent: 100,
+    };
+    assert!(check(None, baseline_task("low", 40, Some(50))).regressed());
+    assert!(!check(Some(30), baseline_task("low", 45, None)).regressed());
+    assert!(!check(Some(30), baseline_task("low", 45, Some(60))).regressed());
+}
+
+#[test]
+fn the_csv_reports_have_a_header_and_quote_resource_names_with_commas() {
+    let dir = env::temp_dir().join(format!("easy-poc-csv-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let trace = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    // 8192 masks IO_IRQ_BANK0, the lock of high is named like a mask of two interrupts would be.
+    dump_trace(&trace("low", 40, vec![section("8192", 5, 15)]), &dir.join("low.json")).unwrap();
+    let high = trace("high", 20, vec![section("dma \"rx\", tx", 3, 6)]);
+    dump_trace(&high, &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let csv = csv_report(&report, &TaskFilter::new(&[]));
+    assert_eq!(
+        csv.lines().collect::<Vec<&str>>(),
+        vec![
+            "id,priority,deadline,wcet,blocking,interference,response_time,schedulable",
+            "low,1,50,40,0,20,60,false",
+            "high,2,1000,20,0,0,20,true",
+        ]
+    );
+    let only_high = csv_report(&report, &TaskFilter::new(&["high".to_owned()]));
+    assert_eq!(only_high.lines().nth(1), Some("high,2,1000,20,0,0,20,true"));
+
+    // resources are in id order, the ceiling is the priority of their only user.
+    let gpio = resource_name("8192", report.chip.table());
+    let resources = resource_csv(&report);
+    assert_eq!(
+        resources.lines().map(str::to_owned).collect::<Vec<String>>(),
+        vec![
+            "resource,ceiling,longest_section".to_owned(),
+            format!("{gpio},1,10"),
+            r#""dma ""rx"", tx",2,3"#.to_owned(),
+        ]
+    );
+}
+
+#[test]
+fn cycle_counts_near_the_u32_limit_are_analysed_or_rejected_but_never_wrap() {
+    let dir = env::temp_dir().join(format!("easy-poc-cycle-overflow-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let path = |max_cycles: usize, cycle_laps: Vec<(usize, String)>| PathLaps {
+        max_cycles,
+        cycle_laps,
+    };
+    let limit = u32::MAX as usize;
+
+    // the longest path a trace can hold, and one lap past it.
+    let longest = create_task(&path(limit, vec![]), &input("long", "TIMER_IRQ_0", 1), 1).unwrap();
+    assert_eq!(longest.trace.end, u32::MAX);
+    let laps = vec![(10, "8192".to_owned()), (limit + 1, "8192".to_owned())];
+    let overflow = create_task(&path(limit + 1, laps), &input("long", "TIMER_IRQ_0", 1), 1).err();
+    assert_eq!(overflow, Some(AnalysisError::CycleOverflow("long".to_owned(), limit + 1)));
+
+    // two tasks of almost u32::MAX cycles overload the core, the response time of the lower one has no fixed
+    // point below the limit instead of wrapping to a short one. The utilization is summed as f64.
+    let task = |id: &str, prio: u8| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: u32::MAX,
+        inter_arrival: u32::MAX,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: u32::MAX - 10,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("low", 1), task("high", 2)]);
+    let rta = rta::response_time(&tasks, &RtaConfig::default());
+    let response_times: Vec<Option<u32>> = rta.results.0.iter().map(|r| r.response_time).collect();
+    assert_eq!(response_times, vec![None, Some(u32::MAX - 10)]);
+    assert!((rta::utilization(&tasks) - 2.0).abs() < 1e-6);
+
+    // a replayed path too long for a trace stops the analysis with an error naming the task.
+    record_laps(&dir, "long", &[path(limit + 1, vec![])]).unwrap();
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("long", "TIMER_IRQ_0", 1)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let error = report.err().unwrap();
+    assert_eq!(error, AnalysisError::CycleOverflow("long".to_owned(), limit + 1));
+    assert_eq!(error.to_string(), "task long: cycle count 4294967296 does not fit in a trace (u32)");
+}
+
+#[test]
+fn a_broken_trace_fails_the_analysis_under_verify() {
+    let dir = env::temp_dir().join(format!("easy-poc-verify-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let section = |id: &str, start: u32, end: u32| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner: vec![],
+    };
+    let trace = |id: &str, end: u32, inner: Vec<Trace>| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner,
+        },
+    };
+    // a hand written trace file with the sections of high overlapping.
+    dump_trace(&trace("low", 40, vec![]), &dir.join("low.json")).unwrap();
+    let high = trace("high", 20, vec![section("8192", 2, 10), section("1048576", 8, 15)]);
+    dump_trace(&high, &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let unverified = run_analysis(&config);
+    let verified = run_analysis(&AnalysisConfig { verify: true, ..config });
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(unverified.is_ok());
+    let expected = TraceIntegrityError::Overlap {
+        id: "1048576".to_owned(),
+        previous: "8192".to_owned(),
+    };
+    let error = verified.err().unwrap();
+    assert_eq!(error, AnalysisError::BrokenTrace("high".to_owned(), 0, expected));
+    assert_eq!(
+        error.to_string(),
+        "task high path 0: broken trace, `1048576` starts before `8192` next to it ends"
+    );
+}
+
+#[test]
+fn a_jsonl_output_that_cant_be_created_is_an_error() {
+    let dir = env::temp_dir().join(format!("easy-poc-jsonl-error-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        // in a directory that doesn't exist.
+        jsonl_out: Some(dir.join("missing").join("combinations.jsonl")),
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let path = dir.join("missing").join("combinations.jsonl").display().to_string();
+    match report {
+        Err(AnalysisError::JsonlOut(error_path, _)) => assert_eq!(error_path, path),
+        Err(e) => panic!("expected a jsonl output error, got {e}"),
+        Ok(_) => panic!("expected a jsonl output error"),
+    }
+}
+
+#[test]
+fn an_enable_symbol_that_cant_be_executed_is_an_error() {
+    let dir = env::temp_dir().join(format!("easy-poc-enable-error-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // the elf with the symbols that enable the interrupts doesn't exist.
+        tasks: vec![
+            InputTask {
+                enabled_after: Some("init".to_owned()),
+                ..input("low", "TIMER_IRQ_0", 1, 50)
+            },
+            input("high", "TIMER_IRQ_1", 2, 1000),
+        ],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let enabled_after = run_analysis(&config);
+    let init = run_analysis(&AnalysisConfig {
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        init_symbol: Some("init".to_owned()),
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    let elf = dir.join("missing.elf").to_string_lossy().into_owned();
+    match enabled_after.err() {
+        Some(AnalysisError::Task(task, e)) => {
+            assert_eq!(task, "low");
+            assert!(matches!(*e, AnalysisError::ElfRead(ref path, _) if *path == elf));
+        }
+        e => panic!("expected the error of task low, got {e:?}"),
+    }
+    assert!(matches!(init.err(), Some(AnalysisError::ElfRead(path, _)) if path == elf));
+}
+
+#[test]
+fn the_paths_of_a_task_are_looked_up_without_panicking_on_bad_input() {
+    let dir = env::temp_dir().join(format!("easy-poc-task-paths-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // high has no laps to replay.
+    record_laps(&dir, "low", &[path(20), path(50), path(30)]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let low = task_paths(&config, "low");
+    let worst_low = worst_path(&config, "low");
+    let high = task_paths(&config, "high");
+    let unknown = task_paths(&config, "unknown");
+    let bad_exclude = task_paths(
+        &AnalysisConfig {
+            exclude: vec!["(".to_owned()],
+            ..config
+        },
+        "low",
+    );
+    fs::remove_dir_all(&dir).unwrap();
+
+    let cycles: Vec<usize> = low.unwrap().unwrap().iter().map(|p| p.max_cycles).collect();
+    assert_eq!(cycles, vec![50, 30, 20]);
+    assert_eq!(worst_low.unwrap().map(|p| p.max_cycles), Some(50));
+    assert!(matches!(high, Err(AnalysisError::Laps(task, _)) if task == "high"));
+    assert_eq!(unknown, Ok(None));
+    assert!(matches!(bad_exclude, Err(AnalysisError::Config(e)) if e.starts_with("invalid exclude regex")));
+}
+
+#[test]
+fn every_output_format_exits_with_1_when_a_task_misses_its_deadline() {
+    let dir = env::temp_dir().join(format!("easy-poc-exit-code-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let missed = run_analysis(&config);
+    let relaxed = config.tasks.iter().cloned().map(|task| InputTask { deadline: 1000, ..task }).collect();
+    let met = run_analysis(&AnalysisConfig { tasks: relaxed, ..config });
+    fs::remove_dir_all(&dir).unwrap();
+
+    // the exit code comes from the report, not from the format it is printed in.
+    assert_eq!(schedulability_exit_code(&missed.unwrap()), 1);
+    assert_eq!(schedulability_exit_code(&met.unwrap()), 0);
+}
+
+#[test]
+fn a_misspelled_interrupt_suggests_the_closest_name() {
+    let table = Chip::Rp2040.table();
+    assert_eq!(check_interrupt("TIMER_IRQ_0", table), Ok(()));
+    // a letter O typed for the digit 0 is one edit away.
+    assert_eq!(
+        check_interrupt("TIMER_IRQ_O", table),
+        Err("unknown interrupt `TIMER_IRQ_O`, did you mean `TIMER_IRQ_0`?".to_owned())
+    );
+    // nothing within three edits, so no guess.
+    let unrelated = check_interrupt("ETHERNET_WAKEUP", table).unwrap_err();
+    assert!(!unrelated.contains("did you mean"));
+}
+
+#[test]
+fn a_write_for_a_second_interrupt_records_only_that_interrupt() {
+    let table = Chip::Rp2040.table();
+    // IO_IRQ_BANK0 (13) is masked first, UART0_IRQ (20) while it still is, and they are enabled again one at
+    // a time. ISER/ICER only act on the bits written, so each write records the interrupt it toggles and
+    // nothing about the one that is already masked or enabled.
+    let mut laps = vec![];
+    push_mask_laps(&mut laps, 5, 0x2000, false);
+    push_mask_laps(&mut laps, 10, 0x100000, false);
+    assert_eq!(laps[1..], [(10, "1048576".to_owned())]);
+    push_mask_laps(&mut laps, 15, 0x100000, true);
+    assert_eq!(laps[2..], [(15, "1048576".to_owned())]);
+    push_mask_laps(&mut laps, 20, 0x2000, true);
+    assert_eq!(laps[3..], [(20, "8192".to_owned())]);
+
+    let trace = trace_from_laps(0, 30, &laps, "task".to_owned()).unwrap();
+    let section = |trace: &Trace| (resource_name(&trace.id, table), trace.start, trace.end);
+    let outer = &trace.inner[0];
+    assert_eq!(section(outer), ("0x2000 (IO_IRQ_BANK0)".to_owned(), 5, 20));
+    assert_eq!(section(&outer.inner[0]), ("0x100000 (UART0_IRQ)".to_owned(), 10, 15));
+}
+
+#[test]
+fn a_mask_is_named_after_its_interrupts_and_other_ids_are_kept() {
+    let table = Chip::Rp2040.table();
+    // each interrupt of a written mask is a resource of its own, named after the interrupt.
+    let names: Vec<String> = mask_labels(0x102000).iter().map(|id| resource_name(id, table)).collect();
+    assert_eq!(names, vec!["0x2000 (IO_IRQ_BANK0)", "0x100000 (UART0_IRQ)"]);
+    // a mask given by the user lists all of its interrupts, a bit without an interrupt by its number.
+    assert_eq!(resource_name("1056768", table), "0x102000 (IO_IRQ_BANK0, UART0_IRQ)");
+    assert_eq!(resource_name(&(1u32 << 31).to_string(), table), "0x80000000 (IRQ31)");
+    // ids that aren't a mask have no name to look up.
+    assert_eq!(resource_name("spinlock3", table), "spinlock3");
+    assert_eq!(resource_name(SYMBOLIC_LABEL, table), SYMBOLIC_LABEL);
+}
+
+#[test]
+fn an_unbounded_response_time_is_the_worst_and_the_other_tasks_are_still_reported() {
+    let dir = env::temp_dir().join(format!("easy-poc-unbounded-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, period: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // high needs 20 of every 15 cycles, low never gets to finish.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 1000), input("high", "TIMER_IRQ_1", 2, 15)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let worst = |id: &str| report.worst.0.iter().find(|r| r.task.id == id).map(|r| r.response_time);
+    assert_eq!(worst("low"), Some(None));
+    assert_eq!(worst("high"), Some(Some(20)));
+    let tasks = schedulability(&report).tasks;
+    let schedulable: Vec<(&str, bool)> = tasks.iter().map(|t| (t.task.as_str(), t.schedulable)).collect();
+    assert!(schedulable.contains(&("low", false)) && schedulable.contains(&("high", true)));
+    assert!(markdown_report(&report, &TaskFilter::new(&[])).contains("| unbounded "));
+}
+
+#[test]
+fn nested_basepri_raises_and_restores_pair_up_by_ceiling() {
+    // RTIC raises BASEPRI to 0x80 for a resource, to 0x40 for one nested inside it (more is masked), and
+    // restores each old value on the way out.
+    let writes = [(0, 0x80, 5, 6), (0x80, 0x40, 10, 11), (0x40, 0x80, 20, 22), (0x80, 0, 30, 31)];
+    let laps: Vec<(usize, String)> =
+        writes.iter().filter_map(|&(old, new, cycle, end)| basepri_lap(old, new, cycle, end)).collect();
+    // raises lap at the write, restores after the writing instruction.
+    assert_eq!(
+        laps,
+        vec![
+            (5, "basepri 128".to_owned()),
+            (10, "basepri 64".to_owned()),
+            (22, "basepri 64".to_owned()),
+            (31, "basepri 128".to_owned()),
+        ]
+    );
+    let trace = trace_from_laps(0, 40, &laps, "task".to_owned()).unwrap();
+    let outer = &trace.inner[0];
+    assert_eq!((outer.id.as_str(), outer.start, outer.end), ("basepri 128", 5, 31));
+    let inner = &outer.inner[0];
+    assert_eq!((inner.id.as_str(), inner.start, inner.end), ("basepri 64", 10, 22));
+
+    // a write keeping the level, or one lowering a level of 0 (nothing masked), opens nothing.
+    assert_eq!(basepri_lap(0x80, 0x80, 5, 6), None);
+    assert_eq!(basepri_lap(0, 0, 5, 6), None);
+}
+
+#[test]
+fn cycles_at_a_clock_that_doesnt_divide_a_second_round_the_safe_way() {
+    // a cycle at 3 MHz is 333.3ns, execution and response times round up and deadlines down.
+    assert_eq!(cycles_to_ns(1, 3_000_000, TimeUse::Demand), Ok(334));
+    assert_eq!(cycles_to_ns(1, 3_000_000, TimeUse::Limit), Ok(333));
+    assert_eq!(cycles_to_ns(3, 3_000_000, TimeUse::Demand), Ok(1000));
+    // at 125 MHz a cycle is exactly 8ns either way.
+    assert_eq!(cycles_to_ns(125, 125_000_000, TimeUse::Demand), Ok(1000));
+    assert_eq!(cycles_to_ns(125, 125_000_000, TimeUse::Limit), Ok(1000));
+    // too many nanoseconds for a u64 is an error, not a wrapped value.
+    let overflow = cycles_to_ns(u64::MAX, 1, TimeUse::Demand);
+    assert!(matches!(overflow, Err(UnitError::Overflow(_))));
+
+    assert_eq!(format_ns(334), "334ns");
+    assert_eq!(format_ns(12_500), "12.5us");
+    assert_eq!(format_ns(1_000_125), "1.000125ms");
+    assert_eq!(format_ns(2_000_000_000), "2s");
+}
+
+#[test]
+fn a_trace_is_drawn_with_each_section_below_the_one_it_is_in() {
+    let section = |id: &str, start: u32, end: u32, inner: Vec<Trace>| Trace {
+        id: id.to_owned(),
+        start,
+        end,
+        inner,
+    };
+    // 100 cycles over 800 pixels, IO_IRQ_BANK0 locked from 20 to 60 and UART0_IRQ inside it from 30 to 40.
+    let uart = section("1048576", 30, 40, vec![]);
+    let trace = section("task", 0, 100, vec![section("8192", 20, 60, vec![uart])]);
+
+    let svg = trace_svg(&trace, Chip::Rp2040.table());
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"60\""));
+    let bars: Vec<&str> = svg.lines().filter(|line| line.starts_with("<g>")).collect();
+    assert_eq!(bars.len(), 3);
+    let bar = |title: &str, x: &str, y: u32, width: &str| {
+        format!("<g><title>{title}</title><rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"18\"")
+    };
+    assert!(bars[0].starts_with(&bar("task (0..100, 100 cycles)", "0.0", 0, "800.0")));
+    assert!(bars[1].starts_with(&bar("0x2000 (IO_IRQ_BANK0) (20..60, 40 cycles)", "160.0", 20, "320.0")));
+    assert!(bars[2].starts_with(&bar("0x100000 (UART0_IRQ) (30..40, 10 cycles)", "240.0", 40, "80.0")));
+    assert!(svg.ends_with("</svg>\n"));
+}
+
+#[test]
+fn a_priority_over_255_is_rejected_instead_of_truncated() {
+    let task = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+
+    // 300 as a u8 is 44, it would silently become a low priority.
+    let too_high = [task("a", "SW0_IRQ", 1), task("b", "SW1_IRQ", 300)];
+    assert_eq!(
+        validate_priorities(&too_high, EqualPriority::Error),
+        Err("task b has priority 300, priorities go up to 255".to_owned())
+    );
+    assert!(validate_priorities(&too_high, EqualPriority::Fifo).is_err());
+    let highest = [task("a", "SW0_IRQ", 1), task("b", "SW1_IRQ", 255)];
+    assert_eq!(validate_priorities(&highest, EqualPriority::Error), Ok(()));
+
+    // a tie between two interrupts is an error unless they are run as a fifo group.
+    let tied = [task("a", "SW0_IRQ", 3), task("b", "SW1_IRQ", 3)];
+    assert!(validate_priorities(&tied, EqualPriority::Error).is_err());
+    assert_eq!(validate_priorities(&tied, EqualPriority::Fifo), Ok(()));
+}
+
+#[test]
+fn the_longest_section_on_each_resource_is_reported_and_bounds_the_blocking() {
+    let dir = env::temp_dir().join(format!("easy-poc-longest-sections-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize, laps: &[(usize, &str)]| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: laps.iter().map(|(cycle, label)| (*cycle, label.to_string())).collect(),
+    };
+    // low locks IO_IRQ_BANK0 for 10 cycles on one path and for 20 on the other, with UART0_IRQ nested inside.
+    let short = path(30, &[(5, "8192"), (15, "8192")]);
+    let long = path(40, &[(5, "8192"), (10, "1048576"), (12, "1048576"), (25, "8192")]);
+    record_laps(&dir, "low", &[short, long]).unwrap();
+    record_laps(&dir, "high", &[path(10, &[(2, "8192"), (6, "8192")])]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let longest: Vec<(&str, u32)> = report.longest_sections.iter().map(|(id, c)| (id.as_str(), *c)).collect();
+    assert_eq!(longest, vec![("1048576", 2), ("8192", 20)]);
+    // high shares IO_IRQ_BANK0 with low, so it can be blocked by the longest section on it.
+    let high = report.worst.0.iter().find(|r| r.task.id == "high").unwrap();
+    assert_eq!(high.blocking, report.longest_sections["8192"]);
+}
+
+#[test]
+fn the_vectors_are_the_handler_symbols_of_the_elf() {
+    // a 32 bit little endian elf with nothing but a symbol table: the header, the names, the symbols and the
+    // section headers (null, .symtab linked to .strtab, .strtab).
+    let symbols = [
+        ("DefaultHandler", 0x1000_0101),
+        ("TIMER_IRQ_0", 0x1000_0201),
+        // cortex-m-rt aliases the handlers the application doesn't define.
+        ("IO_IRQ_BANK0", 0x1000_0101),
+        ("SysTick", 0x1000_0301),
+        ("main", 0x1000_0401),
+    ];
+    let mut strings = vec![0u8];
+    let mut symtab = vec![0u8; 16];
+    for (name, address) in symbols {
+        symtab.extend((strings.len() as u32).to_le_bytes());
+        symtab.extend((address as u32).to_le_bytes());
+        symtab.extend([0; 8]);
+        strings.extend(name.bytes().chain([0]));
+    }
+    let (strings_at, symtab_at) = (52, 52 + strings.len());
+    let headers_at = symtab_at + symtab.len();
+    let section = |kind: u32, offset: usize, size: usize, link: u32| {
+        let words = [0, kind, 0, 0, offset as u32, size as u32, link, 0, 0, 0];
+        words.iter().flat_map(|w| w.to_le_bytes()).collect::<Vec<u8>>()
+    };
+    let mut elf = b"\x7fELF\x01\x01\x01".to_vec();
+    elf.resize(0x20, 0);
+    elf.extend((headers_at as u32).to_le_bytes());
+    elf.resize(0x2e, 0);
+    elf.extend([40, 0, 3, 0]);
+    elf.resize(52, 0);
+    elf.extend(&strings);
+    elf.extend(&symtab);
+    elf.extend(section(0, 0, 0, 0));
+    elf.extend(section(2, symtab_at, symtab.len(), 2));
+    elf.extend(section(3, strings_at, strings.len(), 0));
+
+    let vectors = vector_symbols(&elf, Chip::Rp2040.table()).unwrap();
+
+    let vector = |kind: VectorKind, name: &str, address: u32, default: bool| Vector {
+        kind,
+        name: name.to_owned(),
+        address,
+        default,
+    };
+    // interrupts in the order of the table, then the exceptions. `main` isn't a handler.
+    assert_eq!(
+        vectors,
+        vec![
+            vector(VectorKind::Interrupt(0), "TIMER_IRQ_0", 0x1000_0201, false),
+            vector(VectorKind::Interrupt(13), "IO_IRQ_BANK0", 0x1000_0101, true),
+            vector(VectorKind::Exception(15), "SysTick", 0x1000_0301, false),
+        ]
+    );
+    // a 64 bit elf, or anything else, isn't read.
+    elf[4] = 2;
+    assert_eq!(vector_symbols(&elf, Chip::Rp2040.table()), None);
+    assert_eq!(vector_symbols(b"not an elf", Chip::Rp2040.table()), None);
+}
+
+#[test]
+fn the_paths_explored_for_each_task_are_counted_and_truncation_is_incomplete() {
+    let dir = env::temp_dir().join(format!("easy-poc-path-stats-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // in no particular order, as a hand written laps file can have them.
+    record_laps(&dir, "low", &[path(20), path(50), path(30), path(40)]).unwrap();
+    record_laps(&dir, "high", &[path(5), path(15)]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: Some(2),
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let stats: Vec<_> = report
+        .path_stats
+        .iter()
+        .map(|s| (s.task.as_str(), s.paths, s.min_cycles, s.max_cycles, s.mean_cycles, s.incomplete))
+        .collect();
+    // the stats are of every path symex found, low had two of its four dropped by --max-paths.
+    assert_eq!(stats, vec![("low", 4, 20, 50, 35.0, true), ("high", 2, 5, 15, 10.0, false)]);
+}
+
+#[test]
+fn the_interference_of_a_task_is_broken_down_by_the_task_preempting_it() {
+    let task = |id: &str, prio: u8, wcet: u32, inter_arrival: u32| Task {
+        id: id.to_owned(),
+        prio,
+        deadline: 1000,
+        inter_arrival,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end: wcet,
+            inner: vec![],
+        },
+    };
+    let tasks = Tasks(vec![task("high", 3, 10, 30), task("mid", 2, 5, 1000), task("low", 1, 50, 1000)]);
+
+    let rta = rta::response_time(&tasks, &RtaConfig::default());
+
+    // low converges at 50 + 3 * 10 + 5 = 85, three jobs of high are released in it and one of mid.
+    let low = &rta.results.0[2];
+    assert_eq!((low.response_time, low.interference), (Some(85), 35));
+    let breakdown: Vec<(&str, u64, u64)> =
+        rta.interferers[2].iter().map(|i| (i.task.as_str(), i.jobs, i.cycles)).collect();
+    assert_eq!(breakdown, vec![("high", 3, 30), ("mid", 1, 5)]);
+    assert_eq!(rta.interferers[2].iter().map(|i| i.cycles).sum::<u64>(), low.interference as u64);
+    // mid is only preempted by high, nothing preempts high.
+    let breakdown: Vec<(&str, u64)> = rta.interferers[1].iter().map(|i| (i.task.as_str(), i.jobs)).collect();
+    assert_eq!(breakdown, vec![("high", 1)]);
+    assert!(rta.interferers[0].is_empty());
+}
+
+#[test]
+fn only_the_named_tasks_are_analysed_and_the_others_are_left_out() {
+    let dir = env::temp_dir().join(format!("easy-poc-only-tasks-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let trace = |id: &str, end: u32| Task {
+        id: id.to_owned(),
+        prio: 0,
+        deadline: 0,
+        inter_arrival: 0,
+        trace: Trace {
+            id: id.to_owned(),
+            start: 0,
+            end,
+            inner: vec![],
+        },
+    };
+    dump_trace(&trace("low", 40), &dir.join("low.json")).unwrap();
+    dump_trace(&trace("high", 20), &dir.join("high.json")).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, deadline: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let mut config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        // low is preempted by high, 40 + 20 cycles against a deadline of 50.
+        tasks: vec![input("low", "TIMER_IRQ_0", 1, 50), input("high", "TIMER_IRQ_1", 2, 1000)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![
+            ("low".to_owned(), dir.join("low.json")),
+            ("high".to_owned(), dir.join("high.json")),
+        ],
+        record_laps: None,
+        replay_laps: None,
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let mut tasks = config.tasks.clone();
+    let unknown = only_tasks(&mut tasks, &["nope".to_owned()]);
+    assert_eq!(unknown, Err("--only: there is no task named nope".to_owned()));
+    assert_eq!(only_tasks(&mut tasks, &[]), Ok(vec![]));
+    assert_eq!(tasks.len(), 2);
+    let left_out = only_tasks(&mut config.tasks, &["low".to_owned()]);
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    assert_eq!(left_out, Ok(vec!["high".to_owned()]));
+    // without high in the analysis nothing preempts low, its response time is only partial.
+    let results: Vec<(&str, Option<u32>)> =
+        report.worst.0.iter().map(|r| (r.task.id.as_str(), r.response_time)).collect();
+    assert_eq!(results, vec![("low", Some(40))]);
+}
+
+#[test]
+fn the_histogram_buckets_the_path_cycles_from_the_shortest_to_the_longest() {
+    let stats = |cycles: Vec<usize>| PathStats {
+        task: "task".to_owned(),
+        paths: cycles.len(),
+        min_cycles: cycles.iter().copied().min().unwrap_or(0),
+        max_cycles: cycles.iter().copied().max().unwrap_or(0),
+        mean_cycles: 0.0,
+        cycles,
+        incomplete: false,
+    };
+
+    // 100 to 200 cycles in ten buckets of 11, the fullest bucket gets the whole width.
+    let lines = histogram(&stats(vec![200, 150, 150, 104, 100]));
+    assert_eq!(lines.len(), 10);
+    assert_eq!(lines[0], format!("100..=110 cycles  2  {}", "#".repeat(40)));
+    assert_eq!(lines[1], "111..=121 cycles  0  ");
+    assert_eq!(lines[4], format!("144..=154 cycles  2  {}", "#".repeat(40)));
+    assert_eq!(lines[9], format!("199..=209 cycles  1  {}", "#".repeat(20)));
+    // paths that all take as long are one bucket, no paths no histogram.
+    assert_eq!(histogram(&stats(vec![50, 50, 50])), vec![format!("50..=50 cycles  3  {}", "#".repeat(40))]);
+    assert!(histogram(&stats(vec![])).is_empty());
+}
+
+#[test]
+fn the_dwt_reads_on_the_longest_path_are_reported_with_the_cycles_between_them() {
+    let dir = env::temp_dir().join(format!("easy-poc-cyccnt-reads-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize, laps: &[(usize, &str)]| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: laps.iter().map(|(cycle, label)| (*cycle, label.to_string())).collect(),
+    };
+    // low reads CYCCNT before and after a critical section on its longest path, once on the other one.
+    let longest = path(50, &[(5, CYCCNT_LABEL), (10, "8192"), (20, "8192"), (37, CYCCNT_LABEL)]);
+    record_laps(&dir, "low", &[path(30, &[(4, CYCCNT_LABEL)]), longest]).unwrap();
+    record_laps(&dir, "high", &[path(10, &[(2, "8192"), (6, "8192")])]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    assert_eq!(report.cyccnt_reads, vec![("low".to_owned(), vec![5, 37])]);
+    let (task, reads) = &report.cyccnt_reads[0];
+    let line = cyccnt_line(task, reads);
+    assert_eq!(line, "Task: low reads DWT CYCCNT at cycles 5, 37 (+32) on its longest path.");
+    assert_eq!(
+        cyccnt_line("task", &[3, 10, 24]),
+        "Task: task reads DWT CYCCNT at cycles 3, 10 (+7), 24 (+14) on its longest path."
+    );
+    // the reads aren't critical sections.
+    let low = report.worst.0.iter().find(|r| r.task.id == "low").unwrap();
+    let sections: Vec<&str> = low.task.trace.inner.iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(sections, vec!["8192"]);
+}
+
+#[test]
+fn the_utilization_of_each_task_is_listed_largest_first_with_the_total() {
+    let dir = env::temp_dir().join(format!("easy-poc-utilizations-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // in no particular order, as a hand written laps file can have them.
+    record_laps(&dir, "low", &[path(20), path(50)]).unwrap();
+    record_laps(&dir, "high", &[path(5), path(150)]).unwrap();
+    record_laps(&dir, "mid", &[path(100)]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32, period: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![
+            input("low", "TIMER_IRQ_0", 1, 1000),
+            input("mid", "TIMER_IRQ_2", 2, 500),
+            input("high", "TIMER_IRQ_1", 3, 1000),
+        ],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    // the longest path of each task over its period, in the configured order.
+    let utilizations = vec![("low".to_owned(), 0.05), ("mid".to_owned(), 0.2), ("high".to_owned(), 0.15)];
+    assert_eq!(report.utilizations, utilizations);
+    assert_eq!(
+        utilization_lines(&report, &TaskFilter::new(&[])),
+        vec![
+            "Utilization of mid: 0.200",
+            "Utilization of high: 0.150",
+            "Utilization of low: 0.050",
+            "Total utilization: 0.400",
+        ]
+    );
+    // the total is of every task, not only the ones shown.
+    let lines = utilization_lines(&report, &TaskFilter::new(&["low".to_owned()]));
+    assert_eq!(lines, vec!["Utilization of low: 0.050", "Total utilization: 0.400"]);
+}
+
+#[test]
+fn only_a_chip_with_another_core_than_the_cycle_model_is_warned_about() {
+    let dir = env::temp_dir().join(format!("easy-poc-cycle-model-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: vec![],
+    };
+    // in no particular order, as a hand written laps file can have them.
+    record_laps(&dir, "low", &[path(20), path(50), path(30), path(40)]).unwrap();
+    record_laps(&dir, "high", &[path(5), path(15), path(10)]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "EXTI0", 1), input("high", "EXTI1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Stm32f4,
+    };
+
+    let stm32f4 = run_analysis(&config);
+    let tasks = vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)];
+    let rp2040 = run_analysis(&AnalysisConfig {
+        tasks,
+        chip: Chip::Rp2040,
+        ..config
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    let cycle_model = |report: AnalysisReport| -> Vec<Warning> {
+        report.warnings.into_iter().filter(|w| w.kind == WarningKind::CycleModel).collect()
+    };
+    assert_eq!((Chip::Rp2040.core(), Chip::Stm32f4.core()), (CYCLE_MODEL_CORE, "Cortex-M4"));
+    assert_eq!(
+        cycle_model(stm32f4.unwrap()),
+        vec![Warning {
+            kind: WarningKind::CycleModel,
+            task: None,
+            message: "symex counts cycles with the Cortex-M0+ instruction timings, the Cortex-M4 of this \
+                      chip has other timings, so the WCETs are not the ones of this chip"
+                .to_owned(),
+        }]
+    );
+    // the RP2040 is a Cortex-M0+, the cycles are its own.
+    assert!(cycle_model(rp2040.unwrap()).is_empty());
+}
+
+#[test]
+fn the_worst_case_of_a_task_names_the_path_it_is_from() {
+    let dir = env::temp_dir().join(format!("easy-poc-worst-path-index-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = |cycles: usize, laps: &[(usize, &str)]| PathLaps {
+        max_cycles: cycles,
+        cycle_laps: laps.iter().map(|(cycle, label)| (*cycle, label.to_string())).collect(),
+    };
+    record_laps(&dir, "low", &[path(50, &[(10, "8192"), (30, "8192")])]).unwrap();
+    // the shorter path of high also locks IO_IRQ_BANK0, which raises its ceiling so low's 20 cycle section on
+    // it blocks high. That path is the worst case, not the longest one.
+    record_laps(&dir, "high", &[path(30, &[]), path(28, &[(5, "8192"), (6, "8192")])]).unwrap();
+    let input = |name: &str, interrupt: &str, priority: u32| InputTask {
+        name: name.to_owned(),
+        interrupt: interrupt.to_owned(),
+        priority,
+        deadline: 1000,
+        activation: Activation::Periodic { period: 1000 },
+        cs_mechanism: CsMechanism::Nvic,
+        entry_symbol: None,
+        elf: None,
+        enabled_after: None,
+        bus_contention: 0,
+        jitter: 0,
+        core: None,
+        background: false,
+        expected_wcet: None,
+        tags: BTreeMap::new(),
+    };
+    let config = AnalysisConfig {
+        elf: dir.join("missing.elf").to_string_lossy().into_owned(),
+        tasks: vec![input("low", "TIMER_IRQ_0", 1), input("high", "TIMER_IRQ_1", 2)],
+        prigroup: None,
+        jsonl_out: None,
+        breakdown: false,
+        sensitivity: false,
+        exclude: vec![],
+        context_switch_cost: 0,
+        interrupt_latency: 0,
+        max_paths: None,
+        sample_combinations: None,
+        seed: 0,
+        ceilings: vec![],
+        ignored_resources: vec![],
+        hooks: UserHooks::default(),
+        preconditions: vec![],
+        collapse_paths: false,
+        exhaustive: true,
+        chains: vec![],
+        servers: vec![],
+        trace_files: vec![],
+        record_laps: None,
+        replay_laps: Some(dir.clone()),
+        cache_dir: None,
+        init_symbol: None,
+        cold_start_penalty: 0,
+        correlations: vec![],
+        reservation: None,
+        equal_priority: EqualPriority::Error,
+        require_concrete_locks: false,
+        strict: false,
+        verify: false,
+        utilization_floor: 0.001,
+        timed_out: vec![],
+        chip: Chip::Rp2040,
+    };
+
+    let report = run_analysis(&config);
+    fs::remove_dir_all(&dir).unwrap();
+    let report = report.unwrap();
+
+    let high = report.worst.0.iter().find(|r| r.task.id == "high").unwrap();
+    assert_eq!((high.response_time, high.wcet, high.blocking), (Some(48), 28, 20));
+    let mut worst_paths = report.worst_paths.clone();
+    worst_paths.sort();
+    assert_eq!(worst_paths, vec![("high".to_owned(), 1), ("low".to_owned(), 0)]);
+}